@@ -0,0 +1,985 @@
+//! Approximations for American options on a stock with a continuous
+//! dividend yield.
+use crate::{IvError, PricesAndGreeks, bsm_compute_all, cum_norm, d1, max_or_zero, solver, stats};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct AmericanPricesAndGreeks {
+    pub prices_and_greeks: PricesAndGreeks,
+    /// Stock price at and above which the call is exercised early
+    /// (infinite if early exercise is never optimal)
+    pub call_exercise_boundary: f64,
+    /// Stock price at and below which the put is exercised early
+    /// (zero if early exercise is never optimal)
+    pub put_exercise_boundary: f64,
+}
+
+pub(crate) struct Greeks {
+    pub(crate) delta: f64,
+    pub(crate) gamma: f64,
+    pub(crate) theta: f64,
+    pub(crate) vega: f64,
+    pub(crate) rho: f64,
+    pub(crate) vanna: f64,
+    pub(crate) vomma: f64,
+    pub(crate) charm: f64,
+}
+
+// Central finite differences of price(stock, sigma, rate, maturity).  Theta
+// and charm are minus the derivatives with respect to maturity, as in
+// `bsm_compute_all`, and become one-sided when the maturity is too short to
+// step down.
+pub(crate) fn finite_difference_greeks(
+    price: impl Fn(f64, f64, f64, f64) -> f64,
+    stock: f64,
+    sigma: f64,
+    rate: f64,
+    maturity: f64,
+) -> Greeks {
+    let h_stock = 1.0e-4 * stock;
+    let h_sigma = 1.0e-3 * sigma.max(1.0e-2);
+    let h_rate = 1.0e-4;
+    let h_maturity = 1.0e-4;
+    let maturity_down = (maturity - h_maturity).max(0.0);
+    let maturity_up = maturity + h_maturity;
+    let value = price(stock, sigma, rate, maturity);
+    let up = price(stock + h_stock, sigma, rate, maturity);
+    let down = price(stock - h_stock, sigma, rate, maturity);
+    let sigma_up = price(stock, sigma + h_sigma, rate, maturity);
+    let sigma_down = price(stock, sigma - h_sigma, rate, maturity);
+    let delta_at = |sigma: f64, maturity: f64| {
+        (price(stock + h_stock, sigma, rate, maturity)
+            - price(stock - h_stock, sigma, rate, maturity))
+            / (2.0 * h_stock)
+    };
+    Greeks {
+        delta: (up - down) / (2.0 * h_stock),
+        gamma: (up - 2.0 * value + down) / (h_stock * h_stock),
+        theta: (price(stock, sigma, rate, maturity_down) - price(stock, sigma, rate, maturity_up))
+            / (maturity_up - maturity_down),
+        vega: (sigma_up - sigma_down) / (2.0 * h_sigma),
+        rho: (price(stock, sigma, rate + h_rate, maturity)
+            - price(stock, sigma, rate - h_rate, maturity))
+            / (2.0 * h_rate),
+        vanna: (delta_at(sigma + h_sigma, maturity) - delta_at(sigma - h_sigma, maturity))
+            / (2.0 * h_sigma),
+        vomma: (sigma_up - 2.0 * value + sigma_down) / (h_sigma * h_sigma),
+        charm: (delta_at(sigma, maturity_down) - delta_at(sigma, maturity_up))
+            / (maturity_up - maturity_down),
+    }
+}
+
+const BOUNDARY_PRECISION: f64 = 1.0e-12;
+const BOUNDARY_ITERATIONS: usize = 200;
+
+// Early exercise premium A·(S/S*)^q of the Barone-Adesi-Whaley approximation
+// and the critical price S*, or None if early exercise is never optimal.
+// omega is 1 for calls and -1 for puts.
+fn baw_premium(
+    omega: f64,
+    strike: f64,
+    sigma: f64,
+    rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> Option<(f64, f64, f64)> {
+    let never_exercised = if omega > 0.0 {
+        dividend_yield <= 0.0
+    } else {
+        rate <= 0.0
+    };
+    let sqrt_maturity_sigma = maturity.sqrt() * sigma;
+    if never_exercised || sqrt_maturity_sigma.is_nan() || sqrt_maturity_sigma <= 0.0 {
+        return None;
+    }
+    let variance = sigma * sigma;
+    let n = 2.0 * (rate - dividend_yield) / variance;
+    // M/K(T) = 2r/(σ²(1 - e^{-rT})), which tends to 2/(σ²T) as r → 0
+    let m_over_k = if rate == 0.0 {
+        2.0 / (variance * maturity)
+    } else {
+        2.0 * rate / (variance * -(-rate * maturity).exp_m1())
+    };
+    let exponent = 0.5 * (-(n - 1.0) + omega * ((n - 1.0) * (n - 1.0) + 4.0 * m_over_k).sqrt());
+    let dividend = (-dividend_yield * maturity).exp();
+    let discount = (-rate * maturity).exp();
+    // European price and discounted N(ω·d1) at stock price s
+    let european = |s: f64| {
+        let d1 = d1(s * dividend, strike, discount, sqrt_maturity_sigma);
+        let d2 = d1 - sqrt_maturity_sigma;
+        let price = omega
+            * (s * dividend * cum_norm(omega * d1) - strike * discount * cum_norm(omega * d2));
+        (price, dividend * cum_norm(omega * d1))
+    };
+    // value matching with smooth pasting at the critical price
+    let boundary_condition = |s: f64| {
+        let (price, delta) = european(s);
+        omega * (s - strike) - price - omega * (1.0 - delta) * s / exponent
+    };
+    let boundary = if omega > 0.0 {
+        let mut upper = 2.0 * strike;
+        for _ in 0..64 {
+            if boundary_condition(upper) > 0.0 {
+                break;
+            }
+            upper *= 2.0;
+        }
+        solver::brent(
+            boundary_condition,
+            strike,
+            upper,
+            BOUNDARY_PRECISION * strike,
+            BOUNDARY_ITERATIONS,
+        )
+    } else {
+        solver::brent(
+            boundary_condition,
+            strike * f64::EPSILON,
+            strike,
+            BOUNDARY_PRECISION * strike,
+            BOUNDARY_ITERATIONS,
+        )
+    }
+    .ok()?;
+    let (_, delta) = european(boundary);
+    let coefficient = omega * boundary * (1.0 - delta) / exponent;
+    Some((boundary, coefficient, exponent))
+}
+
+// American price under Barone-Adesi-Whaley
+fn baw_price(
+    omega: f64,
+    stock: f64,
+    strike: f64,
+    sigma: f64,
+    rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    let european = bsm_compute_all(stock, strike, sigma, rate, dividend_yield, maturity);
+    let european_price = if omega > 0.0 {
+        european.call_price
+    } else {
+        european.put_price
+    };
+    match baw_premium(omega, strike, sigma, rate, dividend_yield, maturity) {
+        Some((boundary, _, _)) if omega * (stock - boundary) >= 0.0 => omega * (stock - strike),
+        Some((boundary, coefficient, exponent)) => {
+            european_price + coefficient * (stock / boundary).powf(exponent)
+        }
+        None => european_price,
+    }
+}
+
+/// Returns the Barone-Adesi-Whaley approximation of an American call price.
+///
+/// # Examples
+///
+/// ```
+/// let stock = 100.0;
+/// let strike = 100.0;
+/// let sigma = 0.15;
+/// let rate = 0.1;
+/// let dividend_yield = 0.1;
+/// let maturity = 0.1;
+/// let price = black_scholes::american::baw_call(
+///     stock, strike, sigma, rate, dividend_yield, maturity
+/// );
+/// ```
+pub fn baw_call(
+    stock: f64,
+    strike: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    baw_price(
+        1.0,
+        stock,
+        strike,
+        sigma,
+        risk_free_rate,
+        dividend_yield,
+        maturity,
+    )
+}
+
+/// Returns the Barone-Adesi-Whaley approximation of an American put price.
+///
+/// # Examples
+///
+/// ```
+/// let stock = 100.0;
+/// let strike = 100.0;
+/// let sigma = 0.15;
+/// let rate = 0.1;
+/// let dividend_yield = 0.1;
+/// let maturity = 0.1;
+/// let price = black_scholes::american::baw_put(
+///     stock, strike, sigma, rate, dividend_yield, maturity
+/// );
+/// ```
+pub fn baw_put(
+    stock: f64,
+    strike: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    baw_price(
+        -1.0,
+        stock,
+        strike,
+        sigma,
+        risk_free_rate,
+        dividend_yield,
+        maturity,
+    )
+}
+
+/// Returns American call and put prices and greeks using the
+/// Barone-Adesi-Whaley quadratic approximation, with the early exercise
+/// boundaries.  The European component comes from `bsm_compute_all`;
+/// delta and gamma are analytic and the remaining greeks are finite
+/// differences of the American price.
+///
+/// # Examples
+///
+/// ```
+/// let stock = 90.0;
+/// let strike = 100.0;
+/// let sigma = 0.3;
+/// let rate = 0.05;
+/// let dividend_yield = 0.02;
+/// let maturity = 1.0;
+/// let result = black_scholes::american::baw_compute_all(
+///     stock, strike, sigma, rate, dividend_yield, maturity
+/// );
+/// let put_boundary = result.put_exercise_boundary;
+/// ```
+pub fn baw_compute_all(
+    stock: f64,
+    strike: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> AmericanPricesAndGreeks {
+    let european = bsm_compute_all(
+        stock,
+        strike,
+        sigma,
+        risk_free_rate,
+        dividend_yield,
+        maturity,
+    );
+    // price, delta, gamma and exercise boundary for one side
+    let analytic = |omega: f64, price: f64, delta: f64, gamma: f64| match baw_premium(
+        omega,
+        strike,
+        sigma,
+        risk_free_rate,
+        dividend_yield,
+        maturity,
+    ) {
+        Some((boundary, _, _)) if omega * (stock - boundary) >= 0.0 => {
+            (omega * (stock - strike), omega, 0.0, boundary)
+        }
+        Some((boundary, coefficient, exponent)) => {
+            let premium = coefficient * (stock / boundary).powf(exponent);
+            (
+                price + premium,
+                delta + premium * exponent / stock,
+                gamma + premium * exponent * (exponent - 1.0) / (stock * stock),
+                boundary,
+            )
+        }
+        None => (
+            price,
+            delta,
+            gamma,
+            if omega > 0.0 { f64::INFINITY } else { 0.0 },
+        ),
+    };
+    let (call_price, call_delta, call_gamma, call_exercise_boundary) = analytic(
+        1.0,
+        european.call_price,
+        european.call_delta,
+        european.call_gamma,
+    );
+    let (put_price, put_delta, put_gamma, put_exercise_boundary) = analytic(
+        -1.0,
+        european.put_price,
+        european.put_delta,
+        european.put_gamma,
+    );
+    let call = finite_difference_greeks(
+        |s, sigma, rate, maturity| baw_call(s, strike, sigma, rate, dividend_yield, maturity),
+        stock,
+        sigma,
+        risk_free_rate,
+        maturity,
+    );
+    let put = finite_difference_greeks(
+        |s, sigma, rate, maturity| baw_put(s, strike, sigma, rate, dividend_yield, maturity),
+        stock,
+        sigma,
+        risk_free_rate,
+        maturity,
+    );
+    AmericanPricesAndGreeks {
+        prices_and_greeks: PricesAndGreeks {
+            call_price,
+            call_delta,
+            call_gamma,
+            call_theta: call.theta,
+            call_vega: call.vega,
+            call_rho: call.rho,
+            call_vanna: call.vanna,
+            call_vomma: call.vomma,
+            call_charm: call.charm,
+            put_price,
+            put_delta,
+            put_gamma,
+            put_theta: put.theta,
+            put_vega: put.vega,
+            put_rho: put.rho,
+            put_vanna: put.vanna,
+            put_vomma: put.vomma,
+            put_charm: put.charm,
+        },
+        call_exercise_boundary,
+        put_exercise_boundary,
+    }
+}
+
+// Model parameters shared by the φ and ψ functions of Bjerksund-Stensland
+struct CarryModel {
+    rate: f64,
+    cost_of_carry: f64,
+    sigma: f64,
+}
+
+impl CarryModel {
+    fn lambda(&self, gamma: f64) -> f64 {
+        -self.rate
+            + gamma * self.cost_of_carry
+            + 0.5 * gamma * (gamma - 1.0) * self.sigma * self.sigma
+    }
+    fn kappa(&self, gamma: f64) -> f64 {
+        2.0 * self.cost_of_carry / (self.sigma * self.sigma) + 2.0 * gamma - 1.0
+    }
+    fn drift(&self, gamma: f64) -> f64 {
+        self.cost_of_carry + (gamma - 0.5) * self.sigma * self.sigma
+    }
+
+    // Value of a claim paying S^γ at t if S has not reached the flat
+    // barrier i, and zero if h is hit first (Bjerksund and Stensland, 1993)
+    fn phi(&self, s: f64, t: f64, gamma: f64, h: f64, i: f64) -> f64 {
+        let sqrt_t_sigma = self.sigma * t.sqrt();
+        let d = -((s / h).ln() + self.drift(gamma) * t) / sqrt_t_sigma;
+        (self.lambda(gamma) * t).exp()
+            * s.powf(gamma)
+            * (cum_norm(d)
+                - (i / s).powf(self.kappa(gamma)) * cum_norm(d - 2.0 * (i / s).ln() / sqrt_t_sigma))
+    }
+
+    // Two-period version of phi with barrier i2 up to t1 and i1 from t1 to t
+    #[allow(clippy::too_many_arguments)]
+    fn psi(&self, s: f64, t: f64, gamma: f64, h: f64, i2: f64, i1: f64, t1: f64) -> f64 {
+        let drift = self.drift(gamma);
+        let sqrt_t1_sigma = self.sigma * t1.sqrt();
+        let sqrt_t_sigma = self.sigma * t.sqrt();
+        let e1 = ((s / i1).ln() + drift * t1) / sqrt_t1_sigma;
+        let e2 = ((i2 * i2 / (s * i1)).ln() + drift * t1) / sqrt_t1_sigma;
+        let e3 = ((s / i1).ln() - drift * t1) / sqrt_t1_sigma;
+        let e4 = ((i2 * i2 / (s * i1)).ln() - drift * t1) / sqrt_t1_sigma;
+        let f1 = ((s / h).ln() + drift * t) / sqrt_t_sigma;
+        let f2 = ((i2 * i2 / (s * h)).ln() + drift * t) / sqrt_t_sigma;
+        let f3 = ((i1 * i1 / (s * h)).ln() + drift * t) / sqrt_t_sigma;
+        let f4 = ((s * i1 * i1 / (h * i2 * i2)).ln() + drift * t) / sqrt_t_sigma;
+        let rho = (t1 / t).sqrt();
+        let kappa = self.kappa(gamma);
+        (self.lambda(gamma) * t).exp()
+            * s.powf(gamma)
+            * (stats::bivariate_cum_norm(-e1, -f1, rho)
+                - (i2 / s).powf(kappa) * stats::bivariate_cum_norm(-e2, -f2, rho)
+                - (i1 / s).powf(kappa) * stats::bivariate_cum_norm(-e3, -f3, -rho)
+                + (i1 / i2).powf(kappa) * stats::bivariate_cum_norm(-e4, -f4, -rho))
+    }
+}
+
+// Bjerksund-Stensland (2002) call price and the exercise trigger price at
+// inception, which is infinite if early exercise is never optimal
+fn bs2002_call_and_boundary(
+    stock: f64,
+    strike: f64,
+    sigma: f64,
+    rate: f64,
+    cost_of_carry: f64,
+    maturity: f64,
+) -> (f64, f64) {
+    let carry_discount = ((cost_of_carry - rate) * maturity).exp();
+    let european = crate::call(stock * carry_discount, strike, rate, sigma, maturity);
+    if cost_of_carry >= rate {
+        return (european, f64::INFINITY);
+    }
+    if maturity.sqrt() * sigma <= 0.0 {
+        return (max_or_zero(stock - strike).max(european), strike);
+    }
+    let model = CarryModel {
+        rate,
+        cost_of_carry,
+        sigma,
+    };
+    let variance = sigma * sigma;
+    let beta = (0.5 - cost_of_carry / variance)
+        + ((cost_of_carry / variance - 0.5).powi(2) + 2.0 * rate / variance).sqrt();
+    let b_infinity = beta / (beta - 1.0) * strike;
+    let b_zero = strike.max(rate / (rate - cost_of_carry) * strike);
+    let trigger = |t: f64| {
+        let h = -(cost_of_carry * t + 2.0 * sigma * t.sqrt()) * strike * strike
+            / ((b_infinity - b_zero) * b_zero);
+        b_zero - (b_infinity - b_zero) * h.exp_m1()
+    };
+    // the golden section split of the two step boundary
+    let t1 = 0.5 * (5.0f64.sqrt() - 1.0) * maturity;
+    let i1 = trigger(t1);
+    let i2 = trigger(maturity);
+    if stock >= i2 {
+        return (stock - strike, i2);
+    }
+    let alpha1 = (i1 - strike) * i1.powf(-beta);
+    let alpha2 = (i2 - strike) * i2.powf(-beta);
+    let phi = |gamma, h, i| model.phi(stock, t1, gamma, h, i);
+    let psi = |gamma, h| model.psi(stock, maturity, gamma, h, i2, i1, t1);
+    let price = alpha2 * stock.powf(beta) - alpha2 * phi(beta, i2, i2) + phi(1.0, i2, i2)
+        - phi(1.0, i1, i2)
+        - strike * phi(0.0, i2, i2)
+        + strike * phi(0.0, i1, i2)
+        + alpha1 * phi(beta, i1, i2)
+        - alpha1 * psi(beta, i1)
+        + psi(1.0, i1)
+        - psi(1.0, strike)
+        - strike * psi(0.0, i1)
+        + strike * psi(0.0, strike);
+    // the approximation is a lower bound, so never below the European price
+    (price.max(european), i2)
+}
+
+// Put price and exercise boundary by the put-call transformation
+// P(S, K, r, b) = C(K, S, r - b, -b)
+fn bs2002_put_and_boundary(
+    stock: f64,
+    strike: f64,
+    sigma: f64,
+    rate: f64,
+    cost_of_carry: f64,
+    maturity: f64,
+) -> (f64, f64) {
+    let (price, boundary) = bs2002_call_and_boundary(
+        strike,
+        stock,
+        sigma,
+        rate - cost_of_carry,
+        -cost_of_carry,
+        maturity,
+    );
+    // the call trigger scales with its strike, here the stock price
+    (price, strike * stock / boundary)
+}
+
+/// Returns the Bjerksund-Stensland (2002) approximation of an American
+/// call price.  `cost_of_carry` (aka `b`) is `r - q` for a stock with
+/// dividend yield `q`, `r - r_f` for a currency and 0 for a future.
+///
+/// # Examples
+///
+/// ```
+/// let stock = 42.0;
+/// let strike = 40.0;
+/// let sigma = 0.35;
+/// let rate = 0.04;
+/// let cost_of_carry = -0.04;
+/// let maturity = 0.75;
+/// let price = black_scholes::american::bs2002_call(
+///     stock, strike, sigma, rate, cost_of_carry, maturity
+/// );
+/// ```
+pub fn bs2002_call(
+    stock: f64,
+    strike: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    cost_of_carry: f64,
+    maturity: f64,
+) -> f64 {
+    bs2002_call_and_boundary(
+        stock,
+        strike,
+        sigma,
+        risk_free_rate,
+        cost_of_carry,
+        maturity,
+    )
+    .0
+}
+
+/// Returns the Bjerksund-Stensland (2002) approximation of an American
+/// put price.  See [`bs2002_call`] for `cost_of_carry`.
+///
+/// # Examples
+///
+/// ```
+/// let stock = 40.0;
+/// let strike = 42.0;
+/// let sigma = 0.35;
+/// let rate = 0.04;
+/// let cost_of_carry = 0.04;
+/// let maturity = 0.75;
+/// let price = black_scholes::american::bs2002_put(
+///     stock, strike, sigma, rate, cost_of_carry, maturity
+/// );
+/// ```
+pub fn bs2002_put(
+    stock: f64,
+    strike: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    cost_of_carry: f64,
+    maturity: f64,
+) -> f64 {
+    bs2002_put_and_boundary(
+        stock,
+        strike,
+        sigma,
+        risk_free_rate,
+        cost_of_carry,
+        maturity,
+    )
+    .0
+}
+
+/// Returns American call and put prices and greeks using the
+/// Bjerksund-Stensland (2002) approximation, with the flat exercise
+/// boundaries at inception.  Greeks are finite differences; rho holds the
+/// dividend yield `r - b` fixed, as in `bsm_compute_all`.
+///
+/// # Examples
+///
+/// ```
+/// let stock = 90.0;
+/// let strike = 100.0;
+/// let sigma = 0.3;
+/// let rate = 0.05;
+/// let cost_of_carry = 0.03;
+/// let maturity = 1.0;
+/// let result = black_scholes::american::bs2002_compute_all(
+///     stock, strike, sigma, rate, cost_of_carry, maturity
+/// );
+/// ```
+pub fn bs2002_compute_all(
+    stock: f64,
+    strike: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    cost_of_carry: f64,
+    maturity: f64,
+) -> AmericanPricesAndGreeks {
+    let dividend_yield = risk_free_rate - cost_of_carry;
+    let (call_price, call_exercise_boundary) = bs2002_call_and_boundary(
+        stock,
+        strike,
+        sigma,
+        risk_free_rate,
+        cost_of_carry,
+        maturity,
+    );
+    let (put_price, put_exercise_boundary) = bs2002_put_and_boundary(
+        stock,
+        strike,
+        sigma,
+        risk_free_rate,
+        cost_of_carry,
+        maturity,
+    );
+    let call = finite_difference_greeks(
+        |s, sigma, rate, maturity| {
+            bs2002_call(s, strike, sigma, rate, rate - dividend_yield, maturity)
+        },
+        stock,
+        sigma,
+        risk_free_rate,
+        maturity,
+    );
+    let put = finite_difference_greeks(
+        |s, sigma, rate, maturity| {
+            bs2002_put(s, strike, sigma, rate, rate - dividend_yield, maturity)
+        },
+        stock,
+        sigma,
+        risk_free_rate,
+        maturity,
+    );
+    AmericanPricesAndGreeks {
+        prices_and_greeks: PricesAndGreeks {
+            call_price,
+            call_delta: call.delta,
+            call_gamma: call.gamma,
+            call_theta: call.theta,
+            call_vega: call.vega,
+            call_rho: call.rho,
+            call_vanna: call.vanna,
+            call_vomma: call.vomma,
+            call_charm: call.charm,
+            put_price,
+            put_delta: put.delta,
+            put_gamma: put.gamma,
+            put_theta: put.theta,
+            put_vega: put.vega,
+            put_rho: put.rho,
+            put_vanna: put.vanna,
+            put_vomma: put.vomma,
+            put_charm: put.charm,
+        },
+        call_exercise_boundary,
+        put_exercise_boundary,
+    }
+}
+
+const IV_MIN_SIGMA: f64 = 1.0e-4;
+const IV_MAX_SIGMA: f64 = 10.0;
+const IV_PRECISION: f64 = 1.0e-12;
+const IV_ITERATIONS: usize = 200;
+
+// The American price rises with volatility from the intrinsic value, so the
+// implied volatility is bracketed by a small and a large volatility
+fn american_iv(
+    price: f64,
+    intrinsic: f64,
+    upper_bound: f64,
+    pricer: impl Fn(f64) -> f64,
+) -> Result<f64, IvError> {
+    if price.is_nan() || intrinsic.is_nan() || upper_bound.is_nan() || price < 0.0 {
+        return Err(IvError::InvalidInput);
+    }
+    if price < intrinsic {
+        return Err(IvError::PriceBelowIntrinsic { price, intrinsic });
+    }
+    if price >= upper_bound {
+        return Err(IvError::PriceAboveUpperBound { price, upper_bound });
+    }
+    let objective = |sigma: f64| pricer(sigma) - price;
+    solver::brent(
+        objective,
+        IV_MIN_SIGMA,
+        IV_MAX_SIGMA,
+        IV_PRECISION,
+        IV_ITERATIONS,
+    )
+    .map_err(|sigma| IvError::NoConvergence {
+        iterations: IV_ITERATIONS,
+        residual: objective(sigma),
+    })
+}
+
+fn check_american_iv_inputs(stock: f64, strike: f64, maturity: f64) -> Result<(), IvError> {
+    if stock.is_nan() || strike.is_nan() || stock <= 0.0 || strike <= 0.0 {
+        return Err(IvError::InvalidInput);
+    }
+    if maturity.is_nan() || maturity <= 0.0 {
+        return Err(IvError::NonPositiveMaturity(maturity));
+    }
+    Ok(())
+}
+
+/// Returns the implied volatility of an American call under the
+/// Bjerksund-Stensland (2002) approximation.
+///
+/// # Examples
+///
+/// ```
+/// let price = 5.27;
+/// let stock = 42.0;
+/// let strike = 40.0;
+/// let rate = 0.04;
+/// let cost_of_carry = -0.04;
+/// let maturity = 0.75;
+/// let iv = black_scholes::american::bs2002_call_iv(
+///     price, stock, strike, rate, cost_of_carry, maturity
+/// ).unwrap();
+/// ```
+pub fn bs2002_call_iv(
+    price: f64,
+    stock: f64,
+    strike: f64,
+    risk_free_rate: f64,
+    cost_of_carry: f64,
+    maturity: f64,
+) -> Result<f64, IvError> {
+    check_american_iv_inputs(stock, strike, maturity)?;
+    let carry_discount = ((cost_of_carry - risk_free_rate) * maturity).exp();
+    let european_intrinsic = stock * carry_discount - strike * (-risk_free_rate * maturity).exp();
+    american_iv(
+        price,
+        max_or_zero(stock - strike).max(european_intrinsic),
+        stock.max(stock * carry_discount),
+        |sigma| {
+            bs2002_call(
+                stock,
+                strike,
+                sigma,
+                risk_free_rate,
+                cost_of_carry,
+                maturity,
+            )
+        },
+    )
+}
+
+/// Returns the implied volatility of an American put under the
+/// Bjerksund-Stensland (2002) approximation.  A deep in-the-money put can
+/// trade below the European price and so have no European implied
+/// volatility, but it has an American one while its price exceeds the
+/// intrinsic value.
+///
+/// # Examples
+///
+/// ```
+/// let price = 21.0;
+/// let stock = 80.0;
+/// let strike = 100.0;
+/// let rate = 0.05;
+/// let cost_of_carry = 0.05;
+/// let maturity = 1.0;
+/// let iv = black_scholes::american::bs2002_put_iv(
+///     price, stock, strike, rate, cost_of_carry, maturity
+/// ).unwrap();
+/// ```
+pub fn bs2002_put_iv(
+    price: f64,
+    stock: f64,
+    strike: f64,
+    risk_free_rate: f64,
+    cost_of_carry: f64,
+    maturity: f64,
+) -> Result<f64, IvError> {
+    check_american_iv_inputs(stock, strike, maturity)?;
+    let carry_discount = ((cost_of_carry - risk_free_rate) * maturity).exp();
+    let european_intrinsic = strike * (-risk_free_rate * maturity).exp() - stock * carry_discount;
+    american_iv(
+        price,
+        max_or_zero(strike - stock).max(european_intrinsic),
+        strike,
+        |sigma| {
+            bs2002_put(
+                stock,
+                strike,
+                sigma,
+                risk_free_rate,
+                cost_of_carry,
+                maturity,
+            )
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::*;
+
+    #[test]
+    fn baw_matches_published_values() {
+        // Haug (2007), table 3-1: r = 0.1, b = 0
+        let call = |s, sigma, maturity| baw_call(s, 100.0, sigma, 0.1, 0.1, maturity);
+        assert_abs_diff_eq!(call(90.0, 0.15, 0.1), 0.0206, epsilon = 5e-4);
+        assert_abs_diff_eq!(call(100.0, 0.15, 0.1), 1.8771, epsilon = 5e-4);
+        assert_abs_diff_eq!(call(90.0, 0.35, 0.1), 0.9495, epsilon = 5e-4);
+        assert_abs_diff_eq!(call(100.0, 0.35, 0.1), 4.3777, epsilon = 5e-4);
+        assert_abs_diff_eq!(call(90.0, 0.15, 0.5), 0.8208, epsilon = 5e-4);
+        assert_abs_diff_eq!(call(100.0, 0.15, 0.5), 4.0842, epsilon = 5e-4);
+        assert_abs_diff_eq!(call(110.0, 0.15, 0.5), 10.8087, epsilon = 5e-4);
+    }
+    #[test]
+    fn baw_is_at_least_european_and_intrinsic() {
+        for &s in &[60.0, 80.0, 100.0, 120.0, 140.0] {
+            let european = bsm_compute_all(s, 100.0, 0.3, 0.08, 0.04, 2.0);
+            let call = baw_call(s, 100.0, 0.3, 0.08, 0.04, 2.0);
+            let put = baw_put(s, 100.0, 0.3, 0.08, 0.04, 2.0);
+            assert!(call >= european.call_price - 1e-12 && call >= s - 100.0);
+            assert!(put >= european.put_price - 1e-12 && put >= 100.0 - s);
+        }
+    }
+    #[test]
+    fn baw_call_without_dividends_is_european() {
+        let european = bsm_compute_all(100.0, 95.0, 0.25, 0.05, 0.0, 1.0);
+        let american = baw_compute_all(100.0, 95.0, 0.25, 0.05, 0.0, 1.0);
+        assert_abs_diff_eq!(american.prices_and_greeks.call_price, european.call_price);
+        assert!(american.call_exercise_boundary.is_infinite());
+        assert!(american.put_exercise_boundary > 0.0);
+    }
+    #[test]
+    fn baw_is_exercised_beyond_boundary() {
+        let american = baw_compute_all(100.0, 100.0, 0.3, 0.08, 0.0, 1.0);
+        let boundary = american.put_exercise_boundary;
+        assert!(boundary < 100.0);
+        let below = baw_compute_all(0.9 * boundary, 100.0, 0.3, 0.08, 0.0, 1.0);
+        assert_abs_diff_eq!(
+            below.prices_and_greeks.put_price,
+            100.0 - 0.9 * boundary,
+            epsilon = 1e-12
+        );
+        assert_abs_diff_eq!(below.prices_and_greeks.put_delta, -1.0);
+        // smooth pasting: the delta is continuous across the boundary
+        let above = baw_compute_all(boundary * (1.0 + 1e-9), 100.0, 0.3, 0.08, 0.0, 1.0);
+        assert_abs_diff_eq!(above.prices_and_greeks.put_delta, -1.0, epsilon = 1e-6);
+    }
+    #[test]
+    fn baw_analytic_greeks_match_finite_differences() {
+        let (s, k, sigma, rate, q, maturity) = (95.0, 100.0, 0.3, 0.06, 0.03, 0.75);
+        let american = baw_compute_all(s, k, sigma, rate, q, maturity).prices_and_greeks;
+        let h = 1e-4 * s;
+        let call = |s| baw_call(s, k, sigma, rate, q, maturity);
+        let put = |s| baw_put(s, k, sigma, rate, q, maturity);
+        assert_abs_diff_eq!(
+            american.call_delta,
+            (call(s + h) - call(s - h)) / (2.0 * h),
+            epsilon = 1e-6
+        );
+        assert_abs_diff_eq!(
+            american.call_gamma,
+            (call(s + h) - 2.0 * call(s) + call(s - h)) / (h * h),
+            epsilon = 1e-5
+        );
+        assert_abs_diff_eq!(
+            american.put_delta,
+            (put(s + h) - put(s - h)) / (2.0 * h),
+            epsilon = 1e-6
+        );
+        assert_abs_diff_eq!(
+            american.put_gamma,
+            (put(s + h) - 2.0 * put(s) + put(s - h)) / (h * h),
+            epsilon = 1e-5
+        );
+    }
+    #[test]
+    fn finite_difference_greeks_match_european_greeks() {
+        let (s, k, sigma, rate, maturity) = (105.0, 100.0, 0.25, 0.04, 0.5);
+        let european = bsm_compute_all(s, k, sigma, rate, 0.0, maturity);
+        let greeks = finite_difference_greeks(
+            |s, sigma, rate, maturity| crate::call(s, k, rate, sigma, maturity),
+            s,
+            sigma,
+            rate,
+            maturity,
+        );
+        assert_abs_diff_eq!(greeks.delta, european.call_delta, epsilon = 1e-7);
+        assert_abs_diff_eq!(greeks.gamma, european.call_gamma, epsilon = 1e-6);
+        assert_abs_diff_eq!(greeks.theta, european.call_theta, epsilon = 1e-5);
+        assert_abs_diff_eq!(greeks.vega, european.call_vega, epsilon = 1e-4);
+        assert_abs_diff_eq!(greeks.rho, european.call_rho, epsilon = 1e-5);
+        assert_abs_diff_eq!(greeks.vanna, european.call_vanna, epsilon = 1e-5);
+        assert_abs_diff_eq!(greeks.vomma, european.call_vomma, epsilon = 1e-3);
+        assert_abs_diff_eq!(greeks.charm, european.call_charm, epsilon = 1e-5);
+    }
+    #[test]
+    fn finite_difference_greeks_are_one_sided_at_expiry() {
+        let (s, k, sigma, rate) = (150.0, 100.0, 0.25, 0.05);
+        let greeks = finite_difference_greeks(
+            |s, sigma, rate, maturity| crate::call(s, k, rate, sigma, maturity),
+            s,
+            sigma,
+            rate,
+            0.0,
+        );
+        // deep in the money the call is S - K·e^{-rT}
+        assert_abs_diff_eq!(greeks.theta, -rate * k, epsilon = 1e-3);
+        assert_abs_diff_eq!(greeks.charm, 0.0, epsilon = 1e-6);
+        let american = baw_compute_all(100.0, 100.0, 0.2, 0.05, 0.0, 0.0).prices_and_greeks;
+        assert!(american.call_theta.is_finite() && american.put_theta.is_finite());
+        assert!(american.call_charm.is_finite() && american.put_charm.is_finite());
+    }
+    #[test]
+    fn bs2002_is_a_lower_bound_close_to_lattice_values() {
+        // 2000 step binomial tree values of the American options
+        let call = bs2002_call(42.0, 40.0, 0.35, 0.04, -0.04, 0.75);
+        assert!(call < 5.3093 && call > 5.28);
+        let put = bs2002_put(100.0, 100.0, 0.2, 0.08, 0.08, 0.25);
+        assert!(put < 3.2247 && put > 3.17);
+    }
+    #[test]
+    fn bs2002_put_call_transformation() {
+        assert_abs_diff_eq!(
+            bs2002_put(40.0, 42.0, 0.35, 0.08, 0.04, 0.75),
+            bs2002_call(42.0, 40.0, 0.35, 0.04, -0.04, 0.75),
+            epsilon = 1e-14
+        );
+    }
+    #[test]
+    fn bs2002_call_without_dividends_is_european() {
+        let american = bs2002_compute_all(105.0, 100.0, 0.25, 0.04, 0.04, 0.5);
+        let european = bsm_compute_all(105.0, 100.0, 0.25, 0.04, 0.0, 0.5);
+        assert!(american.call_exercise_boundary.is_infinite());
+        let american = american.prices_and_greeks;
+        assert_abs_diff_eq!(american.call_price, european.call_price, epsilon = 1e-12);
+        assert_abs_diff_eq!(american.call_delta, european.call_delta, epsilon = 1e-7);
+        assert_abs_diff_eq!(american.call_gamma, european.call_gamma, epsilon = 1e-5);
+        assert_abs_diff_eq!(american.call_theta, european.call_theta, epsilon = 1e-5);
+        assert_abs_diff_eq!(american.call_vega, european.call_vega, epsilon = 1e-4);
+        assert_abs_diff_eq!(american.call_rho, european.call_rho, epsilon = 1e-5);
+    }
+    #[test]
+    fn bs2002_is_exercised_beyond_boundary() {
+        let american = bs2002_compute_all(100.0, 100.0, 0.3, 0.08, 0.08, 1.0);
+        let boundary = american.put_exercise_boundary;
+        assert!(boundary > 0.0 && boundary < 100.0);
+        assert_abs_diff_eq!(
+            bs2002_put(0.99 * boundary, 100.0, 0.3, 0.08, 0.08, 1.0),
+            100.0 - 0.99 * boundary,
+            epsilon = 1e-12
+        );
+        assert!(bs2002_put(1.01 * boundary, 100.0, 0.3, 0.08, 0.08, 1.0) > 100.0 - 1.01 * boundary);
+        let american = bs2002_compute_all(100.0, 100.0, 0.3, 0.05, -0.03, 1.0);
+        let boundary = american.call_exercise_boundary;
+        assert_abs_diff_eq!(
+            bs2002_call(1.01 * boundary, 100.0, 0.3, 0.05, -0.03, 1.0),
+            1.01 * boundary - 100.0,
+            epsilon = 1e-12
+        );
+    }
+    #[test]
+    fn bs2002_iv_round_trips() {
+        for &(s, k) in &[(100.0, 100.0), (72.0, 100.0), (80.0, 100.0), (120.0, 100.0)] {
+            let put = bs2002_put(s, k, 0.3, 0.06, 0.02, 1.0);
+            assert!(put > k - s);
+            assert_abs_diff_eq!(
+                bs2002_put_iv(put, s, k, 0.06, 0.02, 1.0).unwrap(),
+                0.3,
+                epsilon = 1e-8
+            );
+            let call = bs2002_call(k, s, 0.3, 0.06, 0.02, 1.0);
+            assert_abs_diff_eq!(
+                bs2002_call_iv(call, k, s, 0.06, 0.02, 1.0).unwrap(),
+                0.3,
+                epsilon = 1e-8
+            );
+        }
+    }
+    #[test]
+    fn bs2002_iv_rejects_price_below_intrinsic() {
+        assert_eq!(
+            bs2002_put_iv(19.0, 80.0, 100.0, 0.05, 0.05, 1.0),
+            Err(IvError::PriceBelowIntrinsic {
+                price: 19.0,
+                intrinsic: 20.0
+            })
+        );
+        assert_eq!(
+            bs2002_call_iv(1.0, 80.0, 100.0, 0.05, 0.05, 0.0),
+            Err(IvError::NonPositiveMaturity(0.0))
+        );
+    }
+}
@@ -0,0 +1,700 @@
+//! Average rate (Asian) options, settling at maturity on the average stock
+//! price against a fixed strike.
+//!
+//! Geometric averages are lognormal and have closed forms.  Arithmetic
+//! averages are priced by fitting a lognormal to the first two moments of
+//! the average still to be observed, after which the fixings already
+//! observed only shift the strike.
+use crate::{call_discount, put_discount};
+use serde::Serialize;
+
+/// The fixings that make up the average, as times in years from today.
+///
+/// Fixings at or before today, at times of zero or less, are already
+/// observed, and enter the average through the running average passed to
+/// the pricing functions.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum Averaging {
+    /// Continuous averaging from `start` until maturity.
+    Continuous { start: f64 },
+    /// Equally weighted fixings at the given times, in any order and all
+    /// at or before maturity.  The prices are NaN without any fixings.
+    Discrete(Vec<f64>),
+}
+
+// the part of the average still to be observed, with discrete fixings in
+// increasing order
+enum Remaining {
+    Continuous { start: f64, end: f64 },
+    Discrete(Vec<f64>),
+}
+
+impl Averaging {
+    // Returns the weight of the observed fixings in the average and the
+    // fixings still to come, or None without any fixings
+    fn split(&self, maturity: f64) -> Option<(f64, Option<Remaining>)> {
+        match self {
+            Averaging::Continuous { start } => {
+                let observed = (-start).max(0.0) / (maturity - start);
+                let remaining = (maturity > 0.0).then_some(Remaining::Continuous {
+                    start: start.max(0.0),
+                    end: maturity,
+                });
+                Some((observed, remaining))
+            }
+            Averaging::Discrete(times) if times.is_empty() => None,
+            Averaging::Discrete(times) => {
+                let mut remaining: Vec<f64> = times.iter().copied().filter(|&t| t > 0.0).collect();
+                remaining.sort_by(f64::total_cmp);
+                let observed = times.len() - remaining.len();
+                Some((
+                    observed as f64 / times.len() as f64,
+                    (!remaining.is_empty()).then_some(Remaining::Discrete(remaining)),
+                ))
+            }
+        }
+    }
+}
+
+// ∫_a^b e^{kt} dt
+fn exp_integral(k: f64, a: f64, b: f64) -> f64 {
+    if k == 0.0 {
+        b - a
+    } else {
+        (k * a).exp() * (k * (b - a)).exp_m1() / k
+    }
+}
+
+impl Remaining {
+    // Mean and variance of the log of the geometric average
+    fn log_moments(&self, stock: f64, sigma: f64, carry: f64) -> (f64, f64) {
+        let variance = sigma * sigma;
+        let log_drift = carry - 0.5 * variance;
+        match *self {
+            Remaining::Continuous { start, end } => (
+                stock.ln() + log_drift * 0.5 * (start + end),
+                variance * (start + (end - start) / 3.0),
+            ),
+            Remaining::Discrete(ref times) => {
+                let n = times.len() as f64;
+                let mean_time = times.iter().sum::<f64>() / n;
+                // sorted fixings: Σᵢ Σⱼ min(tᵢ, tⱼ) = Σᵢ (2(n - i) - 1) tᵢ
+                let covariance: f64 = times
+                    .iter()
+                    .enumerate()
+                    .map(|(i, t)| (2.0 * (n - i as f64) - 1.0) * t)
+                    .sum();
+                (
+                    stock.ln() + log_drift * mean_time,
+                    variance * covariance / (n * n),
+                )
+            }
+        }
+    }
+
+    // First two moments of the arithmetic average
+    fn moments(&self, stock: f64, sigma: f64, carry: f64) -> (f64, f64) {
+        let variance = sigma * sigma;
+        match *self {
+            Remaining::Continuous { start, end } => {
+                let length = end - start;
+                if length <= f64::EPSILON * end {
+                    let forward = stock * (carry * end).exp();
+                    return (forward, forward * forward * (variance * end).exp());
+                }
+                let first = stock * exp_integral(carry, start, end) / length;
+                let k = carry + variance;
+                let second = 2.0 * stock * stock / (length * length * k)
+                    * (exp_integral(carry + k, start, end)
+                        - (k * start).exp() * exp_integral(carry, start, end));
+                (first, second)
+            }
+            Remaining::Discrete(ref times) => {
+                let n = times.len() as f64;
+                let forwards: Vec<f64> = times.iter().map(|t| stock * (carry * t).exp()).collect();
+                let first = forwards.iter().sum::<f64>() / n;
+                // sorted fixings: E[SᵢSⱼ] = FᵢFⱼe^{σ²min(tᵢ,tⱼ)}
+                let second: f64 = forwards
+                    .iter()
+                    .zip(times)
+                    .enumerate()
+                    .map(|(i, (f_i, t_i))| {
+                        let later: f64 = forwards[i + 1..].iter().sum();
+                        f_i * (f_i + 2.0 * later) * (variance * t_i).exp()
+                    })
+                    .sum();
+                (first, second / (n * n))
+            }
+        }
+    }
+}
+
+// Prices (call, put) of options on observed · running_average + (1 -
+// observed) · A where A is lognormal with the given forward and log variance
+fn lognormal_average(
+    observed: f64,
+    running_average: f64,
+    forward: f64,
+    log_variance: f64,
+    strike: f64,
+    discount: f64,
+) -> (f64, f64) {
+    let weight = 1.0 - observed;
+    let known = if observed > 0.0 {
+        observed * running_average
+    } else {
+        0.0
+    };
+    let adjusted_strike = (strike - known) / weight;
+    if adjusted_strike <= 0.0 {
+        return (discount * (known + weight * forward - strike), 0.0);
+    }
+    let sqrt_variance = log_variance.max(0.0).sqrt();
+    let discounted_forward = discount * forward;
+    (
+        weight * call_discount(discounted_forward, adjusted_strike, discount, sqrt_variance),
+        weight * put_discount(discounted_forward, adjusted_strike, discount, sqrt_variance),
+    )
+}
+
+fn intrinsic(running_average: f64, strike: f64, discount: f64) -> (f64, f64) {
+    (
+        discount * (running_average - strike).max(0.0),
+        discount * (strike - running_average).max(0.0),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn geometric(
+    averaging: &Averaging,
+    running_average: f64,
+    stock: f64,
+    strike: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> (f64, f64) {
+    let discount = (-risk_free_rate * maturity).exp();
+    let Some((observed, remaining)) = averaging.split(maturity) else {
+        return (f64::NAN, f64::NAN);
+    };
+    let Some(remaining) = remaining else {
+        return intrinsic(running_average, strike, discount);
+    };
+    let (mean, variance) = remaining.log_moments(stock, sigma, risk_free_rate - dividend_yield);
+    let weight = 1.0 - observed;
+    let known = if observed > 0.0 {
+        observed * running_average.ln()
+    } else {
+        0.0
+    };
+    let log_mean = known + weight * mean;
+    let log_variance = weight * weight * variance;
+    let forward = (log_mean + 0.5 * log_variance).exp();
+    let sqrt_variance = log_variance.sqrt();
+    (
+        call_discount(discount * forward, strike, discount, sqrt_variance),
+        put_discount(discount * forward, strike, discount, sqrt_variance),
+    )
+}
+
+// Turnbull and Wakeman treat the remaining fixings as a continuous average
+// between the first and the last of them
+fn continuous_window(remaining: Remaining) -> Remaining {
+    match remaining {
+        Remaining::Discrete(times) => Remaining::Continuous {
+            start: times[0],
+            end: times[times.len() - 1],
+        },
+        continuous => continuous,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn arithmetic(
+    averaging: &Averaging,
+    running_average: f64,
+    stock: f64,
+    strike: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+    window: impl Fn(Remaining) -> Remaining,
+) -> (f64, f64) {
+    let discount = (-risk_free_rate * maturity).exp();
+    let Some((observed, remaining)) = averaging.split(maturity) else {
+        return (f64::NAN, f64::NAN);
+    };
+    let Some(remaining) = remaining else {
+        return intrinsic(running_average, strike, discount);
+    };
+    let (first, second) = window(remaining).moments(stock, sigma, risk_free_rate - dividend_yield);
+    lognormal_average(
+        observed,
+        running_average,
+        first,
+        (second / (first * first)).ln(),
+        strike,
+        discount,
+    )
+}
+
+/// Returns the price of a call on the geometric average, where
+/// `running_average` is the geometric average of the fixings already
+/// observed.  With continuous averaging starting today this is the Kemna
+/// and Vorst (1990) formula.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::asian::{geometric_call, Averaging};
+/// let monthly: Vec<f64> = (1..=12).map(|month| month as f64 / 12.0).collect();
+/// let stock = 100.0;
+/// let strike = 100.0;
+/// let sigma = 0.3;
+/// let risk_free_rate = 0.05;
+/// let dividend_yield = 0.02;
+/// let maturity = 1.0;
+/// let price = geometric_call(
+///     &Averaging::Discrete(monthly), 0.0, stock, strike,
+///     sigma, risk_free_rate, dividend_yield, maturity
+/// );
+/// assert!(price < black_scholes::call(stock, strike, risk_free_rate, sigma, maturity));
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn geometric_call(
+    averaging: &Averaging,
+    running_average: f64,
+    stock: f64,
+    strike: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    geometric(
+        averaging,
+        running_average,
+        stock,
+        strike,
+        sigma,
+        risk_free_rate,
+        dividend_yield,
+        maturity,
+    )
+    .0
+}
+
+/// Returns the price of a put on the geometric average, where
+/// `running_average` is the geometric average of the fixings already
+/// observed.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::asian::{geometric_put, Averaging};
+/// let price = geometric_put(
+///     &Averaging::Continuous { start: 0.0 }, 0.0, 100.0, 100.0, 0.3, 0.05, 0.02, 1.0
+/// );
+/// assert!(price > 0.0);
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn geometric_put(
+    averaging: &Averaging,
+    running_average: f64,
+    stock: f64,
+    strike: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    geometric(
+        averaging,
+        running_average,
+        stock,
+        strike,
+        sigma,
+        risk_free_rate,
+        dividend_yield,
+        maturity,
+    )
+    .1
+}
+
+/// Returns the Turnbull and Wakeman (1991) approximation to a call on the
+/// arithmetic average, where `running_average` is the arithmetic average
+/// of the fixings already observed.  Discrete fixings still to come are
+/// treated as a continuous average between the first and the last of them.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::asian::{turnbull_wakeman_call, Averaging};
+/// // half way through a six month averaging period
+/// let averaging = Averaging::Continuous { start: -0.25 };
+/// let price = turnbull_wakeman_call(&averaging, 98.0, 100.0, 100.0, 0.3, 0.05, 0.02, 0.25);
+/// assert!(price > 0.0);
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn turnbull_wakeman_call(
+    averaging: &Averaging,
+    running_average: f64,
+    stock: f64,
+    strike: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    arithmetic(
+        averaging,
+        running_average,
+        stock,
+        strike,
+        sigma,
+        risk_free_rate,
+        dividend_yield,
+        maturity,
+        continuous_window,
+    )
+    .0
+}
+
+/// Returns the Turnbull and Wakeman (1991) approximation to a put on the
+/// arithmetic average, where `running_average` is the arithmetic average
+/// of the fixings already observed.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::asian::{turnbull_wakeman_put, Averaging};
+/// let averaging = Averaging::Continuous { start: -0.25 };
+/// let price = turnbull_wakeman_put(&averaging, 98.0, 100.0, 100.0, 0.3, 0.05, 0.02, 0.25);
+/// assert!(price > 0.0);
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn turnbull_wakeman_put(
+    averaging: &Averaging,
+    running_average: f64,
+    stock: f64,
+    strike: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    arithmetic(
+        averaging,
+        running_average,
+        stock,
+        strike,
+        sigma,
+        risk_free_rate,
+        dividend_yield,
+        maturity,
+        continuous_window,
+    )
+    .1
+}
+
+/// Returns the Levy (1992) approximation to a call on the arithmetic
+/// average, matching the exact moments of the discrete or continuous
+/// average.  `running_average` is the arithmetic average of the fixings
+/// already observed.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::asian::{levy_call, Averaging};
+/// // three of twelve monthly fixings observed
+/// let fixings: Vec<f64> = (-2..=9).map(|month| month as f64 / 12.0).collect();
+/// let price = levy_call(
+///     &Averaging::Discrete(fixings), 101.5, 100.0, 100.0, 0.3, 0.05, 0.02, 0.75
+/// );
+/// assert!(price > 0.0);
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn levy_call(
+    averaging: &Averaging,
+    running_average: f64,
+    stock: f64,
+    strike: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    arithmetic(
+        averaging,
+        running_average,
+        stock,
+        strike,
+        sigma,
+        risk_free_rate,
+        dividend_yield,
+        maturity,
+        |remaining| remaining,
+    )
+    .0
+}
+
+/// Returns the Levy (1992) approximation to a put on the arithmetic
+/// average, matching the exact moments of the discrete or continuous
+/// average.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::asian::{levy_put, Averaging};
+/// let fixings: Vec<f64> = (-2..=9).map(|month| month as f64 / 12.0).collect();
+/// let price = levy_put(
+///     &Averaging::Discrete(fixings), 101.5, 100.0, 100.0, 0.3, 0.05, 0.02, 0.75
+/// );
+/// assert!(price > 0.0);
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn levy_put(
+    averaging: &Averaging,
+    running_average: f64,
+    stock: f64,
+    strike: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    arithmetic(
+        averaging,
+        running_average,
+        stock,
+        strike,
+        sigma,
+        risk_free_rate,
+        dividend_yield,
+        maturity,
+        |remaining| remaining,
+    )
+    .1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monte_carlo::MonteCarlo;
+    use approx::*;
+
+    fn monthly(first: i32, last: i32) -> Vec<f64> {
+        (first..=last).map(|month| month as f64 / 12.0).collect()
+    }
+
+    #[test]
+    fn geometric_continuous_matches_reference() {
+        // Haug, The Complete Guide to Option Pricing Formulas, section 4.20.1
+        let averaging = Averaging::Continuous { start: 0.0 };
+        let (s, strike, rate, carry, sigma, maturity) = (80.0, 85.0, 0.05, 0.08, 0.2, 0.25);
+        let put = geometric_put(
+            &averaging,
+            0.0,
+            s,
+            strike,
+            sigma,
+            rate,
+            rate - carry,
+            maturity,
+        );
+        assert_abs_diff_eq!(put, 4.6922, epsilon = 1e-4);
+    }
+    #[test]
+    fn geometric_discrete_matches_simulation() {
+        let (s, strike, sigma, rate, q, maturity) = (100.0, 95.0, 0.3, 0.05, 0.02, 1.0);
+        let fixings = monthly(1, 12);
+        let simulated = MonteCarlo::new(50_000, 12, 7)
+            .with_antithetic_variates()
+            .price(
+                |path| {
+                    let log_average = path.iter().map(|x| x.ln()).sum::<f64>() / 12.0;
+                    (log_average.exp() - strike).max(0.0)
+                },
+                s,
+                sigma,
+                rate,
+                q,
+                maturity,
+            );
+        let price = geometric_call(
+            &Averaging::Discrete(fixings),
+            0.0,
+            s,
+            strike,
+            sigma,
+            rate,
+            q,
+            maturity,
+        );
+        assert!((price - simulated.price).abs() < 4.0 * simulated.standard_error);
+    }
+    #[test]
+    fn daily_fixings_approach_continuous_averaging() {
+        let (s, strike, sigma, rate, q, maturity) = (100.0, 100.0, 0.25, 0.04, 0.01, 1.0);
+        let daily =
+            Averaging::Discrete((1..=2520).map(|day| (day as f64 - 0.5) / 2520.0).collect());
+        let continuous = Averaging::Continuous { start: 0.0 };
+        assert_abs_diff_eq!(
+            geometric_call(&daily, 0.0, s, strike, sigma, rate, q, maturity),
+            geometric_call(&continuous, 0.0, s, strike, sigma, rate, q, maturity),
+            epsilon = 1e-3
+        );
+        assert_abs_diff_eq!(
+            levy_put(&daily, 0.0, s, strike, sigma, rate, q, maturity),
+            turnbull_wakeman_put(&continuous, 0.0, s, strike, sigma, rate, q, maturity),
+            epsilon = 1e-3
+        );
+    }
+    #[test]
+    fn arithmetic_matches_simulation() {
+        let (s, sigma, rate, q, maturity) = (100.0, 0.25, 0.05, 0.02, 0.75);
+        // four of twelve monthly fixings observed, averaging 103
+        let (observed, running_average) = (4.0, 103.0);
+        let fixings = monthly(-3, 8);
+        let simulation = MonteCarlo::new(50_000, 9, 11).with_antithetic_variates();
+        for strike in [90.0, 100.0, 110.0] {
+            let simulated = simulation.price(
+                |path| {
+                    let total = observed * running_average + path[..8].iter().sum::<f64>();
+                    (total / 12.0 - strike).max(0.0)
+                },
+                s,
+                sigma,
+                rate,
+                q,
+                maturity,
+            );
+            let levy = levy_call(
+                &Averaging::Discrete(fixings.clone()),
+                running_average,
+                s,
+                strike,
+                sigma,
+                rate,
+                q,
+                maturity,
+            );
+            let turnbull_wakeman = turnbull_wakeman_call(
+                &Averaging::Discrete(fixings.clone()),
+                running_average,
+                s,
+                strike,
+                sigma,
+                rate,
+                q,
+                maturity,
+            );
+            let tolerance = 4.0 * simulated.standard_error + 0.02;
+            assert!((levy - simulated.price).abs() < tolerance);
+            assert!((turnbull_wakeman - simulated.price).abs() < tolerance + 0.05);
+            assert!(
+                levy > geometric_call(
+                    &Averaging::Discrete(fixings.clone()),
+                    running_average,
+                    s,
+                    strike,
+                    sigma,
+                    rate,
+                    q,
+                    maturity,
+                )
+            );
+        }
+    }
+    #[test]
+    fn put_call_parity() {
+        let (s, strike, sigma, rate, q, maturity): (f64, f64, f64, f64, f64, f64) =
+            (100.0, 105.0, 0.2, 0.03, 0.05, 0.5);
+        let averaging = Averaging::Continuous { start: -0.5 };
+        let running_average = 97.0;
+        let discount = (-rate * maturity).exp();
+        let carry = rate - q;
+        let forward_average =
+            0.5 * running_average + 0.5 * s * (carry * maturity).exp_m1() / (carry * maturity);
+        let call = levy_call(
+            &averaging,
+            running_average,
+            s,
+            strike,
+            sigma,
+            rate,
+            q,
+            maturity,
+        );
+        let put = levy_put(
+            &averaging,
+            running_average,
+            s,
+            strike,
+            sigma,
+            rate,
+            q,
+            maturity,
+        );
+        assert_abs_diff_eq!(
+            call - put,
+            discount * (forward_average - strike),
+            epsilon = 1e-10
+        );
+    }
+    #[test]
+    fn fixed_average_is_intrinsic() {
+        let (s, sigma, rate, q) = (100.0, 0.2, 0.03, 0.0);
+        let averaging = Averaging::Discrete(monthly(-11, 0));
+        let discount = (-rate * 0.1f64).exp();
+        assert_abs_diff_eq!(
+            levy_call(&averaging, 104.0, s, 100.0, sigma, rate, q, 0.1),
+            4.0 * discount
+        );
+        assert_eq!(
+            geometric_put(&averaging, 104.0, s, 100.0, sigma, rate, q, 0.1),
+            0.0
+        );
+        // the observed fixings alone put the call in the money
+        let averaging = Averaging::Discrete(monthly(-10, 1));
+        let call = turnbull_wakeman_call(&averaging, 120.0, s, 50.0, sigma, rate, q, 1.0 / 12.0);
+        let forward_average = (11.0 * 120.0 + s * (rate / 12.0f64).exp()) / 12.0;
+        assert_abs_diff_eq!(
+            call,
+            (-rate / 12.0f64).exp() * (forward_average - 50.0),
+            epsilon = 1e-10
+        );
+    }
+    #[test]
+    fn fixing_order_does_not_matter() {
+        let (s, strike, sigma, rate, q, maturity) = (100.0, 95.0, 0.3, 0.04, 0.01, 1.0);
+        let sorted = Averaging::Discrete(monthly(-2, 12));
+        let mut times = monthly(-2, 12);
+        times.reverse();
+        times.swap(3, 9);
+        let shuffled = Averaging::Discrete(times);
+        for pricer in [geometric_call, turnbull_wakeman_call, levy_call] {
+            assert_eq!(
+                pricer(&shuffled, 98.0, s, strike, sigma, rate, q, maturity),
+                pricer(&sorted, 98.0, s, strike, sigma, rate, q, maturity)
+            );
+        }
+    }
+    #[test]
+    fn no_fixings_is_nan() {
+        let averaging = Averaging::Discrete(vec![]);
+        for pricer in [
+            geometric_call,
+            geometric_put,
+            turnbull_wakeman_call,
+            turnbull_wakeman_put,
+            levy_call,
+            levy_put,
+        ] {
+            assert!(pricer(&averaging, 100.0, 100.0, 100.0, 0.2, 0.05, 0.0, 1.0).is_nan());
+        }
+    }
+}
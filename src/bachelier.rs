@@ -0,0 +1,665 @@
+//! Bachelier (normal) model for options on a forward.
+//!
+//! The forward follows `dF = σ dW` with an absolute (normal) volatility `σ`,
+//! so forwards and strikes may be zero or negative.  This is the standard
+//! quoting model for rate and spread options.
+use crate::{IvError, PricesAndGreeks, cum_norm, inc_norm, lets_be_rational, max_or_zero, solver};
+use special::Error;
+use std::f64::consts::FRAC_1_SQRT_2;
+
+// Undiscounted time value σ√T·(φ(u) - u·N(-u)) with u = |F - K|/(σ√T), which is
+// the same for calls and puts.  N(-u) is computed from erfc to keep the tail accurate.
+fn otm_time_value(abs_moneyness: f64, sqrt_maturity_sigma: f64) -> f64 {
+    if sqrt_maturity_sigma <= 0.0 {
+        return 0.0;
+    }
+    let u = abs_moneyness / sqrt_maturity_sigma;
+    let tail = 0.5 * (u * FRAC_1_SQRT_2).compl_error();
+    sqrt_maturity_sigma * (inc_norm(u) - u * tail)
+}
+
+/// Returns Bachelier call price on a forward.
+///
+/// # Examples
+///
+/// ```
+/// let forward = -0.002;
+/// let strike = 0.001;
+/// let rate = 0.01;
+/// let sigma = 0.008;
+/// let maturity = 2.0;
+/// let call = black_scholes::bachelier::call(forward, strike, rate, sigma, maturity);
+/// ```
+pub fn call(forward_price: f64, strike: f64, rate: f64, sigma: f64, maturity: f64) -> f64 {
+    let discount = (-rate * maturity).exp();
+    let sqrt_maturity_sigma = maturity.sqrt() * sigma;
+    let moneyness = forward_price - strike;
+    discount * (max_or_zero(moneyness) + otm_time_value(moneyness.abs(), sqrt_maturity_sigma))
+}
+
+/// Returns Bachelier put price on a forward.
+///
+/// # Examples
+///
+/// ```
+/// let forward = -0.002;
+/// let strike = 0.001;
+/// let rate = 0.01;
+/// let sigma = 0.008;
+/// let maturity = 2.0;
+/// let put = black_scholes::bachelier::put(forward, strike, rate, sigma, maturity);
+/// ```
+pub fn put(forward_price: f64, strike: f64, rate: f64, sigma: f64, maturity: f64) -> f64 {
+    let discount = (-rate * maturity).exp();
+    let sqrt_maturity_sigma = maturity.sqrt() * sigma;
+    let moneyness = strike - forward_price;
+    discount * (max_or_zero(moneyness) + otm_time_value(moneyness.abs(), sqrt_maturity_sigma))
+}
+
+/// Returns call and put prices and greeks under the Bachelier model.
+/// Greeks are with respect to the forward price and the absolute
+/// volatility `sigma`.
+///
+/// # Examples
+///
+/// ```
+/// let forward = -0.002;
+/// let strike = 0.001;
+/// let rate = 0.01;
+/// let sigma = 0.008;
+/// let maturity = 2.0;
+/// let all_prices_and_greeks = black_scholes::bachelier::compute_all(
+///     forward,
+///     strike,
+///     rate,
+///     sigma,
+///     maturity,
+/// );
+/// ```
+pub fn compute_all(
+    forward_price: f64,
+    strike: f64,
+    rate: f64,
+    sigma: f64,
+    maturity: f64,
+) -> PricesAndGreeks {
+    let discount = (-rate * maturity).exp();
+    let sqrt_maturity = maturity.sqrt();
+    let sqrt_maturity_sigma = sqrt_maturity * sigma;
+    if sqrt_maturity_sigma > 0.0 {
+        let moneyness = forward_price - strike;
+        let d = moneyness / sqrt_maturity_sigma;
+        let cdf_d = cum_norm(d);
+        let pdf_d = inc_norm(d);
+
+        let time_value = discount * otm_time_value(moneyness.abs(), sqrt_maturity_sigma);
+        let call_price = discount * max_or_zero(moneyness) + time_value;
+        let call_delta = discount * cdf_d;
+        let call_gamma = discount * pdf_d / sqrt_maturity_sigma;
+        let theta_part = discount * sigma * pdf_d / (2.0 * sqrt_maturity);
+        let call_theta = rate * call_price - theta_part;
+        let call_vega = discount * sqrt_maturity * pdf_d;
+        let call_rho = -maturity * call_price;
+        let call_vanna = -discount * pdf_d * d / sigma;
+        let call_vomma = call_vega * d * d / sigma;
+        let charm_part = discount * pdf_d * d / (2.0 * maturity);
+        let call_charm = -rate * call_delta - charm_part;
+
+        let put_price = discount * max_or_zero(-moneyness) + time_value;
+        let put_delta = discount * (cdf_d - 1.0);
+        let put_gamma = call_gamma;
+        let put_theta = rate * put_price - theta_part;
+        let put_vega = call_vega;
+        let put_rho = -maturity * put_price;
+        let put_vanna = call_vanna;
+        let put_vomma = call_vomma;
+        let put_charm = -rate * put_delta - charm_part;
+        PricesAndGreeks {
+            call_price,
+            call_delta,
+            call_gamma,
+            call_theta,
+            call_vega,
+            call_rho,
+            call_vanna,
+            call_vomma,
+            call_charm,
+            put_price,
+            put_delta,
+            put_gamma,
+            put_theta,
+            put_vega,
+            put_rho,
+            put_vanna,
+            put_vomma,
+            put_charm,
+        }
+    } else {
+        let call_price = discount * max_or_zero(forward_price - strike);
+        let call_delta = if forward_price > strike {
+            discount
+        } else {
+            0.0
+        };
+        let put_price = discount * max_or_zero(strike - forward_price);
+        let put_delta = if strike > forward_price {
+            -discount
+        } else {
+            0.0
+        };
+        PricesAndGreeks {
+            call_price,
+            call_delta,
+            call_gamma: 0.0,
+            call_theta: rate * call_price,
+            call_vega: 0.0,
+            call_rho: -maturity * call_price,
+            call_vanna: 0.0,
+            call_vomma: 0.0,
+            call_charm: -rate * call_delta,
+            put_price,
+            put_delta,
+            put_gamma: 0.0,
+            put_theta: rate * put_price,
+            put_vega: 0.0,
+            put_rho: -maturity * put_price,
+            put_vanna: 0.0,
+            put_vomma: 0.0,
+            put_charm: -rate * put_delta,
+        }
+    }
+}
+
+const IV_PRECISION: f64 = 1.0e-15;
+const IV_ITERATIONS: usize = 100;
+
+// Solves for the normal volatility from the undiscounted out-of-the-money time value
+fn implied_normal_vol(
+    price: f64,
+    forward_price: f64,
+    strike: f64,
+    rate: f64,
+    maturity: f64,
+    is_call: bool,
+) -> Result<f64, IvError> {
+    if [price, forward_price, strike, rate, maturity]
+        .iter()
+        .any(|v| v.is_nan())
+        || price < 0.0
+    {
+        return Err(IvError::InvalidInput);
+    }
+    if maturity <= 0.0 {
+        return Err(IvError::NonPositiveMaturity(maturity));
+    }
+    let discount = (-rate * maturity).exp();
+    let moneyness = forward_price - strike;
+    let intrinsic = discount * max_or_zero(if is_call { moneyness } else { -moneyness });
+    if price < intrinsic {
+        return Err(IvError::PriceBelowIntrinsic { price, intrinsic });
+    }
+    let time_value = (price - intrinsic) / discount;
+    if time_value <= 0.0 {
+        return Ok(0.0);
+    }
+    let abs_moneyness = moneyness.abs();
+    let sqrt_maturity = maturity.sqrt();
+    // The time value is convex in u with slope -1/2 at u = 0, so
+    // σ√T·φ(0) - |F - K|/2 bounds it from below.
+    let upper = (time_value + 0.5 * abs_moneyness) / (crate::FRAC_1_SQRT_2PI * sqrt_maturity);
+    let obj_fn = |sigma: f64| {
+        let sqrt_maturity_sigma = sqrt_maturity * sigma;
+        let vega = if sqrt_maturity_sigma > 0.0 {
+            sqrt_maturity * inc_norm(abs_moneyness / sqrt_maturity_sigma)
+        } else {
+            0.0
+        };
+        (
+            otm_time_value(abs_moneyness, sqrt_maturity_sigma) - time_value,
+            vega,
+        )
+    };
+    solver::newton_bisection(
+        obj_fn,
+        0.0,
+        upper,
+        upper,
+        IV_PRECISION * upper,
+        IV_ITERATIONS,
+    )
+    .map_err(|sigma| IvError::NoConvergence {
+        iterations: IV_ITERATIONS,
+        residual: obj_fn(sigma).0,
+    })
+}
+
+/// Returns the implied normal volatility from a Bachelier call price.
+/// Forwards and strikes may be negative.
+///
+/// # Examples
+///
+/// ```
+/// let price = 0.002;
+/// let forward = -0.002;
+/// let strike = 0.001;
+/// let rate = 0.01;
+/// let maturity = 2.0;
+/// let iv = black_scholes::bachelier::call_iv(
+///     price, forward, strike, rate, maturity
+/// ).unwrap();
+/// ```
+pub fn call_iv(
+    price: f64,
+    forward_price: f64,
+    strike: f64,
+    rate: f64,
+    maturity: f64,
+) -> Result<f64, IvError> {
+    implied_normal_vol(price, forward_price, strike, rate, maturity, true)
+}
+
+/// Returns the implied normal volatility from a Bachelier put price.
+/// Forwards and strikes may be negative.
+///
+/// # Examples
+///
+/// ```
+/// let price = 0.005;
+/// let forward = -0.002;
+/// let strike = 0.001;
+/// let rate = 0.01;
+/// let maturity = 2.0;
+/// let iv = black_scholes::bachelier::put_iv(
+///     price, forward, strike, rate, maturity
+/// ).unwrap();
+/// ```
+pub fn put_iv(
+    price: f64,
+    forward_price: f64,
+    strike: f64,
+    rate: f64,
+    maturity: f64,
+) -> Result<f64, IvError> {
+    implied_normal_vol(price, forward_price, strike, rate, maturity, false)
+}
+
+fn check_conversion_inputs(
+    forward_price: f64,
+    strike: f64,
+    sigma: f64,
+    maturity: f64,
+) -> Result<(), IvError> {
+    if [forward_price, strike, sigma, maturity]
+        .iter()
+        .any(|v| v.is_nan())
+        || forward_price <= 0.0
+        || strike <= 0.0
+        || sigma < 0.0
+    {
+        return Err(IvError::InvalidInput);
+    }
+    if maturity <= 0.0 {
+        return Err(IvError::NonPositiveMaturity(maturity));
+    }
+    Ok(())
+}
+
+/// Returns the normal volatility that gives the same price as a Black-76
+/// (lognormal) volatility for the given forward, strike and maturity.
+/// The conversion is exact: the out-of-the-money option is repriced under
+/// Black-76 and the normal volatility implied from that price.  The
+/// forward and strike must be positive.
+///
+/// # Examples
+///
+/// ```
+/// let forward = 0.03;
+/// let strike = 0.035;
+/// let black_vol = 0.2;
+/// let maturity = 5.0;
+/// let normal_vol = black_scholes::bachelier::normal_vol_from_black(
+///     forward, strike, black_vol, maturity
+/// ).unwrap();
+/// ```
+pub fn normal_vol_from_black(
+    forward_price: f64,
+    strike: f64,
+    black_vol: f64,
+    maturity: f64,
+) -> Result<f64, IvError> {
+    check_conversion_inputs(forward_price, strike, black_vol, maturity)?;
+    let x = (forward_price / strike).ln();
+    let time_value = forward_price.sqrt()
+        * strike.sqrt()
+        * lets_be_rational::normalised_black_call(-x.abs(), black_vol * maturity.sqrt());
+    implied_normal_vol(
+        time_value,
+        forward_price,
+        strike,
+        0.0,
+        maturity,
+        strike >= forward_price,
+    )
+}
+
+/// Returns the Black-76 (lognormal) volatility that gives the same price as
+/// a normal volatility for the given forward, strike and maturity.
+/// The conversion is exact: the out-of-the-money option is repriced under
+/// Bachelier and the Black volatility implied from that price.  The
+/// forward and strike must be positive.
+///
+/// # Examples
+///
+/// ```
+/// let forward = 0.03;
+/// let strike = 0.035;
+/// let normal_vol = 0.0065;
+/// let maturity = 5.0;
+/// let black_vol = black_scholes::bachelier::black_vol_from_normal(
+///     forward, strike, normal_vol, maturity
+/// ).unwrap();
+/// ```
+pub fn black_vol_from_normal(
+    forward_price: f64,
+    strike: f64,
+    normal_vol: f64,
+    maturity: f64,
+) -> Result<f64, IvError> {
+    check_conversion_inputs(forward_price, strike, normal_vol, maturity)?;
+    let time_value = otm_time_value((forward_price - strike).abs(), normal_vol * maturity.sqrt());
+    lets_be_rational::implied_black_volatility(
+        time_value,
+        forward_price,
+        strike,
+        maturity,
+        strike >= forward_price,
+    )
+}
+
+// (F - K)/ln(F/K) = √(FK)·sinh(l/2)/(l/2) with l = ln(F/K), which stays
+// accurate at the money.
+fn log_mean(forward_price: f64, strike: f64) -> f64 {
+    let half_log_moneyness = 0.5 * (forward_price / strike).ln();
+    let ratio = if half_log_moneyness == 0.0 {
+        1.0
+    } else {
+        half_log_moneyness.sinh() / half_log_moneyness
+    };
+    forward_price.sqrt() * strike.sqrt() * ratio
+}
+
+// Denominator of Hagan's expansion, 1 + (1 - l²/120)·σ²T/24 + σ⁴T²/5760
+fn hagan_denominator(forward_price: f64, strike: f64, black_vol: f64, maturity: f64) -> f64 {
+    let log_moneyness = (forward_price / strike).ln();
+    let variance = black_vol * black_vol * maturity;
+    1.0 + (1.0 - log_moneyness * log_moneyness / 120.0) * variance / 24.0
+        + variance * variance / 5760.0
+}
+
+/// Returns an approximate normal volatility from a Black-76 volatility using
+/// Hagan's expansion
+/// `σ_N ≈ σ_B·(F - K)/ln(F/K) / (1 + (1 - l²/120)·σ_B²T/24 + σ_B⁴T²/5760)`
+/// with `l = ln(F/K)`.  Accurate to a few basis points of the normal vol
+/// for typical rate volatilities; use [`normal_vol_from_black`] when an
+/// exact conversion is needed.  Returns NaN if the forward or strike is
+/// not positive.
+///
+/// # Examples
+///
+/// ```
+/// let forward = 0.03;
+/// let strike = 0.035;
+/// let black_vol = 0.2;
+/// let maturity = 5.0;
+/// let normal_vol = black_scholes::bachelier::normal_vol_from_black_approx(
+///     forward, strike, black_vol, maturity
+/// );
+/// ```
+pub fn normal_vol_from_black_approx(
+    forward_price: f64,
+    strike: f64,
+    black_vol: f64,
+    maturity: f64,
+) -> f64 {
+    if forward_price <= 0.0 || strike <= 0.0 {
+        return f64::NAN;
+    }
+    black_vol * log_mean(forward_price, strike)
+        / hagan_denominator(forward_price, strike, black_vol, maturity)
+}
+
+const APPROX_ITERATIONS: usize = 20;
+
+/// Returns an approximate Black-76 volatility from a normal volatility by
+/// inverting Hagan's expansion (see [`normal_vol_from_black_approx`]) with
+/// fixed-point iteration.  Use [`black_vol_from_normal`] when an exact
+/// conversion is needed.  Returns NaN if the forward or strike is not
+/// positive.
+///
+/// # Examples
+///
+/// ```
+/// let forward = 0.03;
+/// let strike = 0.035;
+/// let normal_vol = 0.0065;
+/// let maturity = 5.0;
+/// let black_vol = black_scholes::bachelier::black_vol_from_normal_approx(
+///     forward, strike, normal_vol, maturity
+/// );
+/// ```
+pub fn black_vol_from_normal_approx(
+    forward_price: f64,
+    strike: f64,
+    normal_vol: f64,
+    maturity: f64,
+) -> f64 {
+    if forward_price <= 0.0 || strike <= 0.0 {
+        return f64::NAN;
+    }
+    let leading_order = normal_vol / log_mean(forward_price, strike);
+    let mut black_vol = leading_order;
+    for _ in 0..APPROX_ITERATIONS {
+        let next = leading_order * hagan_denominator(forward_price, strike, black_vol, maturity);
+        if (next - black_vol).abs() <= f64::EPSILON * next {
+            return next;
+        }
+        black_vol = next;
+    }
+    black_vol
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::*;
+
+    macro_rules! assert_approx_eq {
+        ($a:expr, $b:expr) => {{
+            let (a, b) = (&$a, &$b);
+            assert!(
+                (*a - *b).abs() < 1.0e-6,
+                "{} is not approximately equal to {}",
+                *a,
+                *b
+            );
+        }};
+    }
+
+    #[test]
+    fn atm_call_is_sigma_sqrt_t_over_sqrt_two_pi() {
+        let sigma = 20.0;
+        let maturity = 1.0;
+        assert_approx_eq!(
+            call(100.0, 100.0, 0.0, sigma, maturity),
+            sigma * crate::FRAC_1_SQRT_2PI
+        );
+    }
+    #[test]
+    fn prices_are_finite_for_negative_forward_and_strike() {
+        let result = compute_all(-0.005, -0.002, 0.01, 0.007, 1.5);
+        assert!(result.call_price.is_finite() && result.call_price > 0.0);
+        assert!(result.put_price.is_finite() && result.put_price > 0.0);
+        assert_approx_eq!(result.call_price, call(-0.005, -0.002, 0.01, 0.007, 1.5));
+        assert_approx_eq!(result.put_price, put(-0.005, -0.002, 0.01, 0.007, 1.5));
+    }
+    #[test]
+    fn compute_all_greeks_match_finite_differences() {
+        let f = 0.3;
+        let k = 0.5;
+        let rate = 0.03;
+        let sigma = 0.4;
+        let maturity = 1.2;
+        let h = 1.0e-5;
+        let r = compute_all(f, k, rate, sigma, maturity);
+        let fd = |g: &dyn Fn(f64) -> f64, x: f64| (g(x + h) - g(x - h)) / (2.0 * h);
+        let call_f = |f: f64| call(f, k, rate, sigma, maturity);
+        let put_f = |f: f64| put(f, k, rate, sigma, maturity);
+        assert_abs_diff_eq!(r.call_delta, fd(&call_f, f), epsilon = 1e-8);
+        assert_abs_diff_eq!(r.put_delta, fd(&put_f, f), epsilon = 1e-8);
+        let delta_f = |f: f64| compute_all(f, k, rate, sigma, maturity).call_delta;
+        assert_abs_diff_eq!(r.call_gamma, fd(&delta_f, f), epsilon = 1e-6);
+        let call_s = |s: f64| call(f, k, rate, s, maturity);
+        assert_abs_diff_eq!(r.call_vega, fd(&call_s, sigma), epsilon = 1e-8);
+        let call_t = |t: f64| call(f, k, rate, sigma, t);
+        assert_abs_diff_eq!(r.call_theta, -fd(&call_t, maturity), epsilon = 1e-8);
+        let put_t = |t: f64| put(f, k, rate, sigma, t);
+        assert_abs_diff_eq!(r.put_theta, -fd(&put_t, maturity), epsilon = 1e-8);
+        let call_r = |r: f64| call(f, k, r, sigma, maturity);
+        assert_abs_diff_eq!(r.call_rho, fd(&call_r, rate), epsilon = 1e-8);
+        let vega_f = |f: f64| compute_all(f, k, rate, sigma, maturity).call_vega;
+        assert_abs_diff_eq!(r.call_vanna, fd(&vega_f, f), epsilon = 1e-6);
+        let vega_s = |s: f64| compute_all(f, k, rate, s, maturity).call_vega;
+        assert_abs_diff_eq!(r.call_vomma, fd(&vega_s, sigma), epsilon = 1e-6);
+        let delta_t = |t: f64| compute_all(f, k, rate, sigma, t).call_delta;
+        assert_abs_diff_eq!(r.call_charm, fd(&delta_t, maturity), epsilon = 1e-6);
+        let put_delta_t = |t: f64| compute_all(f, k, rate, sigma, t).put_delta;
+        assert_abs_diff_eq!(r.put_charm, fd(&put_delta_t, maturity), epsilon = 1e-6);
+    }
+    #[test]
+    fn zero_volatility_is_discounted_intrinsic() {
+        let (f, k, rate, maturity): (f64, f64, f64, f64) = (0.03, 0.01, 0.05, 2.0);
+        let discount = (-rate * maturity).exp();
+        let r = compute_all(f, k, rate, 0.0, maturity);
+        assert_approx_eq!(r.call_price, call(f, k, rate, 0.0, maturity));
+        assert_approx_eq!(r.call_price, discount * (f - k));
+        assert_approx_eq!(r.call_delta, discount);
+        assert_approx_eq!(r.call_theta, rate * r.call_price);
+        assert_approx_eq!(r.call_rho, -maturity * r.call_price);
+        let r = compute_all(k, f, rate, 0.0, maturity);
+        assert_approx_eq!(r.put_price, put(k, f, rate, 0.0, maturity));
+        assert_approx_eq!(r.put_delta, -discount);
+        assert_approx_eq!(r.put_charm, rate * discount);
+    }
+    #[test]
+    fn call_and_put_iv_round_trip() {
+        let rate = 0.02;
+        let maturity = 2.0;
+        for &(f, k, sigma) in &[
+            (-0.004, 0.001, 0.006),
+            (0.01, 0.01, 0.008),
+            (0.03, -0.01, 0.005),
+            (0.0, 0.02, 0.004),
+        ] {
+            let c = call(f, k, rate, sigma, maturity);
+            let p = put(f, k, rate, sigma, maturity);
+            assert_relative_eq!(
+                call_iv(c, f, k, rate, maturity).unwrap(),
+                sigma,
+                max_relative = 1e-8
+            );
+            assert_relative_eq!(
+                put_iv(p, f, k, rate, maturity).unwrap(),
+                sigma,
+                max_relative = 1e-8
+            );
+        }
+    }
+    #[test]
+    fn call_iv_works_deep_out_of_the_money() {
+        let price = call(0.0, 0.05, 0.02, 0.004, 2.0);
+        assert!(price < 1e-15);
+        assert_relative_eq!(
+            call_iv(price, 0.0, 0.05, 0.02, 2.0).unwrap(),
+            0.004,
+            max_relative = 1e-10
+        );
+    }
+    #[test]
+    fn iv_returns_price_below_intrinsic() {
+        assert!(matches!(
+            call_iv(0.001, 0.01, 0.0, 0.0, 1.0),
+            Err(IvError::PriceBelowIntrinsic { .. })
+        ));
+    }
+    #[test]
+    fn iv_returns_non_positive_maturity() {
+        assert_eq!(
+            put_iv(0.001, 0.01, 0.0, 0.0, 0.0),
+            Err(IvError::NonPositiveMaturity(0.0))
+        );
+    }
+    #[test]
+    fn normal_vol_from_black_reprices_black76() {
+        let rate = 0.01;
+        let maturity = 5.0;
+        let black_vol = 0.25;
+        for &(forward, strike) in &[(0.03, 0.03), (0.03, 0.045), (0.03, 0.015), (0.002, 0.0005)] {
+            let normal_vol = normal_vol_from_black(forward, strike, black_vol, maturity).unwrap();
+            let lognormal = crate::black76(forward, strike, rate, black_vol, maturity);
+            assert_relative_eq!(
+                call(forward, strike, rate, normal_vol, maturity),
+                lognormal.call_price,
+                max_relative = 1e-10
+            );
+            assert_relative_eq!(
+                put(forward, strike, rate, normal_vol, maturity),
+                lognormal.put_price,
+                max_relative = 1e-10
+            );
+        }
+    }
+    #[test]
+    fn vol_conversion_round_trips() {
+        for &(forward, strike) in &[(0.03, 0.03), (0.03, 0.045), (0.03, 0.015), (0.05, 0.001)] {
+            let normal_vol = normal_vol_from_black(forward, strike, 0.3, 10.0).unwrap();
+            assert_relative_eq!(
+                black_vol_from_normal(forward, strike, normal_vol, 10.0).unwrap(),
+                0.3,
+                max_relative = 1e-12
+            );
+        }
+    }
+    #[test]
+    fn approximate_vol_conversion_is_close_to_exact() {
+        let maturity = 5.0;
+        for &(forward, strike) in &[(0.03, 0.03), (0.03, 0.045), (0.03, 0.02)] {
+            let exact = normal_vol_from_black(forward, strike, 0.2, maturity).unwrap();
+            let approx = normal_vol_from_black_approx(forward, strike, 0.2, maturity);
+            assert_relative_eq!(approx, exact, max_relative = 1e-4);
+            let exact = black_vol_from_normal(forward, strike, 0.006, maturity).unwrap();
+            let approx = black_vol_from_normal_approx(forward, strike, 0.006, maturity);
+            assert_relative_eq!(approx, exact, max_relative = 1e-4);
+        }
+    }
+    #[test]
+    fn approximate_vol_conversion_is_consistent() {
+        let black_vol = black_vol_from_normal_approx(0.03, 0.04, 0.008, 2.0);
+        assert_relative_eq!(
+            normal_vol_from_black_approx(0.03, 0.04, black_vol, 2.0),
+            0.008,
+            max_relative = 1e-13
+        );
+    }
+    #[test]
+    fn vol_conversion_needs_positive_forward_and_strike() {
+        assert_eq!(
+            normal_vol_from_black(-0.01, 0.01, 0.2, 1.0),
+            Err(IvError::InvalidInput)
+        );
+        assert_eq!(
+            black_vol_from_normal(0.01, 0.0, 0.005, 1.0),
+            Err(IvError::InvalidInput)
+        );
+        assert!(normal_vol_from_black_approx(0.01, -0.01, 0.2, 1.0).is_nan());
+    }
+}
@@ -0,0 +1,625 @@
+//! Single barrier options (Reiner and Rubinstein, 1991) with a continuous
+//! dividend yield and a cash rebate.
+//!
+//! Knock-out options pay the rebate as soon as the barrier is hit, and
+//! knock-in options pay it at maturity if the barrier was never hit.  The
+//! formulas assume continuous monitoring; for discrete monitoring shift the
+//! barrier with [`discrete_monitoring_barrier`].
+use crate::american::finite_difference_greeks;
+use crate::stats::log_cum_norm;
+use crate::{PricesAndGreeks, bsm_compute_all, cum_norm, max_or_zero};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum BarrierKind {
+    DownAndIn,
+    DownAndOut,
+    UpAndIn,
+    UpAndOut,
+}
+
+impl BarrierKind {
+    fn is_down(self) -> bool {
+        matches!(self, BarrierKind::DownAndIn | BarrierKind::DownAndOut)
+    }
+    fn is_knock_in(self) -> bool {
+        matches!(self, BarrierKind::DownAndIn | BarrierKind::UpAndIn)
+    }
+}
+
+// Broadie, Glasserman and Kou (1997): -ζ(1/2)/√(2π)
+pub(crate) const BGK_BETA: f64 = 0.582_597_157_939_010_6;
+
+/// Returns the continuously monitored barrier that approximately prices an
+/// option monitored every `monitoring_interval` years, shifting the barrier
+/// away from the stock by `e^{0.5826σ√Δt}` (Broadie, Glasserman and Kou).
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::barrier::{discrete_monitoring_barrier, BarrierKind};
+/// let daily = discrete_monitoring_barrier(BarrierKind::UpAndOut, 120.0, 0.2, 1.0 / 252.0);
+/// assert!(daily > 120.0);
+/// ```
+pub fn discrete_monitoring_barrier(
+    kind: BarrierKind,
+    barrier: f64,
+    sigma: f64,
+    monitoring_interval: f64,
+) -> f64 {
+    let shift = BGK_BETA * sigma * monitoring_interval.sqrt();
+    if kind.is_down() {
+        barrier * (-shift).exp()
+    } else {
+        barrier * shift.exp()
+    }
+}
+
+// omega is 1 for calls and -1 for puts
+#[allow(clippy::too_many_arguments)]
+fn price(
+    omega: f64,
+    kind: BarrierKind,
+    stock: f64,
+    strike: f64,
+    barrier: f64,
+    rebate: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    let down = kind.is_down();
+    let knock_in = kind.is_knock_in();
+    let hit = if down {
+        stock <= barrier
+    } else {
+        stock >= barrier
+    };
+    if hit {
+        return if knock_in {
+            let vanilla = bsm_compute_all(
+                stock,
+                strike,
+                sigma,
+                risk_free_rate,
+                dividend_yield,
+                maturity,
+            );
+            if omega > 0.0 {
+                vanilla.call_price
+            } else {
+                vanilla.put_price
+            }
+        } else {
+            rebate
+        };
+    }
+    let sqrt_maturity_sigma = sigma * maturity.sqrt();
+    if sqrt_maturity_sigma <= 0.0 {
+        // the stock follows its forward, which crosses the barrier only if
+        // it finishes beyond it
+        let carry = risk_free_rate - dividend_yield;
+        let forward = stock * (carry * maturity).exp();
+        let crossed = if down {
+            forward <= barrier
+        } else {
+            forward >= barrier
+        };
+        let discount = (-risk_free_rate * maturity).exp();
+        let vanilla = discount * max_or_zero(omega * (forward - strike));
+        return match (knock_in, crossed) {
+            (true, true) | (false, false) => vanilla,
+            (true, false) => rebate * discount,
+            // the knock-out rebate is paid when the forward reaches the barrier
+            (false, true) => rebate * (-risk_free_rate * (barrier / stock).ln() / carry).exp(),
+        };
+    }
+
+    let phi = omega;
+    let eta = if down { 1.0 } else { -1.0 };
+    let carry = risk_free_rate - dividend_yield;
+    let mu = (carry - 0.5 * sigma * sigma) / (sigma * sigma);
+    let lambda = (mu * mu + 2.0 * risk_free_rate / (sigma * sigma)).sqrt();
+    let drift = (1.0 + mu) * sqrt_maturity_sigma;
+    let x1 = (stock / strike).ln() / sqrt_maturity_sigma + drift;
+    let x2 = (stock / barrier).ln() / sqrt_maturity_sigma + drift;
+    let y1 = (barrier * barrier / (stock * strike)).ln() / sqrt_maturity_sigma + drift;
+    let y2 = (barrier / stock).ln() / sqrt_maturity_sigma + drift;
+    let z = (barrier / stock).ln() / sqrt_maturity_sigma + lambda * sqrt_maturity_sigma;
+    let forward_stock = stock * (-dividend_yield * maturity).exp();
+    let k_discount = strike * (-risk_free_rate * maturity).exp();
+    // powers of H/S overflow at low volatility, so they are multiplied by
+    // the normal CDFs in log space
+    let log_ratio = (barrier / stock).ln();
+    let scaled_cum_norm = |exponent: f64, x: f64| (exponent * log_ratio + log_cum_norm(x)).exp();
+    let reflection = 2.0 * mu;
+
+    let a = phi * forward_stock * cum_norm(phi * x1)
+        - phi * k_discount * cum_norm(phi * (x1 - sqrt_maturity_sigma));
+    let b = phi * forward_stock * cum_norm(phi * x2)
+        - phi * k_discount * cum_norm(phi * (x2 - sqrt_maturity_sigma));
+    let c = phi * forward_stock * scaled_cum_norm(2.0 + reflection, eta * y1)
+        - phi * k_discount * scaled_cum_norm(reflection, eta * (y1 - sqrt_maturity_sigma));
+    let d = phi * forward_stock * scaled_cum_norm(2.0 + reflection, eta * y2)
+        - phi * k_discount * scaled_cum_norm(reflection, eta * (y2 - sqrt_maturity_sigma));
+    // rebates paid at maturity if not knocked in, or when knocked out
+    let e = rebate
+        * (-risk_free_rate * maturity).exp()
+        * (cum_norm(eta * (x2 - sqrt_maturity_sigma))
+            - scaled_cum_norm(reflection, eta * (y2 - sqrt_maturity_sigma)));
+    let f = rebate
+        * (scaled_cum_norm(mu + lambda, eta * z)
+            + scaled_cum_norm(mu - lambda, eta * (z - 2.0 * lambda * sqrt_maturity_sigma)));
+
+    let strike_above = strike > barrier;
+    match (omega > 0.0, kind, strike_above) {
+        (true, BarrierKind::DownAndIn, true) => c + e,
+        (true, BarrierKind::DownAndIn, false) => a - b + d + e,
+        (true, BarrierKind::UpAndIn, true) => a + e,
+        (true, BarrierKind::UpAndIn, false) => b - c + d + e,
+        (true, BarrierKind::DownAndOut, true) => a - c + f,
+        (true, BarrierKind::DownAndOut, false) => b - d + f,
+        (true, BarrierKind::UpAndOut, true) => f,
+        (true, BarrierKind::UpAndOut, false) => a - b + c - d + f,
+        (false, BarrierKind::DownAndIn, true) => b - c + d + e,
+        (false, BarrierKind::DownAndIn, false) => a + e,
+        (false, BarrierKind::UpAndIn, true) => a - b + d + e,
+        (false, BarrierKind::UpAndIn, false) => c + e,
+        (false, BarrierKind::DownAndOut, true) => a - b + c - d + f,
+        (false, BarrierKind::DownAndOut, false) => f,
+        (false, BarrierKind::UpAndOut, true) => b - d + f,
+        (false, BarrierKind::UpAndOut, false) => a - c + f,
+    }
+}
+
+/// Returns the price of a barrier call.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::barrier::BarrierKind;
+/// let stock = 100.0;
+/// let strike = 100.0;
+/// let barrier = 95.0;
+/// let rebate = 3.0;
+/// let sigma = 0.25;
+/// let risk_free_rate = 0.08;
+/// let dividend_yield = 0.04;
+/// let maturity = 0.5;
+/// let price = black_scholes::barrier::call(
+///     BarrierKind::DownAndOut,
+///     stock, strike, barrier, rebate, sigma, risk_free_rate, dividend_yield, maturity
+/// );
+/// assert!((price - 6.7924).abs() < 1e-4);
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn call(
+    kind: BarrierKind,
+    stock: f64,
+    strike: f64,
+    barrier: f64,
+    rebate: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    price(
+        1.0,
+        kind,
+        stock,
+        strike,
+        barrier,
+        rebate,
+        sigma,
+        risk_free_rate,
+        dividend_yield,
+        maturity,
+    )
+}
+
+/// Returns the price of a barrier put.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::barrier::BarrierKind;
+/// let stock = 100.0;
+/// let strike = 100.0;
+/// let barrier = 105.0;
+/// let rebate = 3.0;
+/// let sigma = 0.25;
+/// let risk_free_rate = 0.08;
+/// let dividend_yield = 0.04;
+/// let maturity = 0.5;
+/// let price = black_scholes::barrier::put(
+///     BarrierKind::UpAndOut,
+///     stock, strike, barrier, rebate, sigma, risk_free_rate, dividend_yield, maturity
+/// );
+/// assert!((price - 5.4932).abs() < 1e-4);
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn put(
+    kind: BarrierKind,
+    stock: f64,
+    strike: f64,
+    barrier: f64,
+    rebate: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    price(
+        -1.0,
+        kind,
+        stock,
+        strike,
+        barrier,
+        rebate,
+        sigma,
+        risk_free_rate,
+        dividend_yield,
+        maturity,
+    )
+}
+
+/// Returns barrier call and put prices with greeks by central finite
+/// differences of the closed forms, with the same conventions as
+/// `bsm_compute_all`.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::barrier::BarrierKind;
+/// let stock = 100.0;
+/// let strike = 100.0;
+/// let barrier = 120.0;
+/// let rebate = 0.0;
+/// let sigma = 0.25;
+/// let risk_free_rate = 0.05;
+/// let dividend_yield = 0.02;
+/// let maturity = 1.0;
+/// let all = black_scholes::barrier::compute_all(
+///     BarrierKind::UpAndOut,
+///     stock, strike, barrier, rebate, sigma, risk_free_rate, dividend_yield, maturity
+/// );
+/// // the up-and-out call loses value as the stock rises towards the barrier
+/// assert!(all.call_gamma < 0.0);
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn compute_all(
+    kind: BarrierKind,
+    stock: f64,
+    strike: f64,
+    barrier: f64,
+    rebate: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> PricesAndGreeks {
+    let greeks = |omega: f64| {
+        finite_difference_greeks(
+            |s, sigma, rate, maturity| {
+                price(
+                    omega,
+                    kind,
+                    s,
+                    strike,
+                    barrier,
+                    rebate,
+                    sigma,
+                    rate,
+                    dividend_yield,
+                    maturity,
+                )
+            },
+            stock,
+            sigma,
+            risk_free_rate,
+            maturity,
+        )
+    };
+    let call_greeks = greeks(1.0);
+    let put_greeks = greeks(-1.0);
+    PricesAndGreeks {
+        call_price: call(
+            kind,
+            stock,
+            strike,
+            barrier,
+            rebate,
+            sigma,
+            risk_free_rate,
+            dividend_yield,
+            maturity,
+        ),
+        call_delta: call_greeks.delta,
+        call_gamma: call_greeks.gamma,
+        call_theta: call_greeks.theta,
+        call_vega: call_greeks.vega,
+        call_rho: call_greeks.rho,
+        call_vanna: call_greeks.vanna,
+        call_vomma: call_greeks.vomma,
+        call_charm: call_greeks.charm,
+        put_price: put(
+            kind,
+            stock,
+            strike,
+            barrier,
+            rebate,
+            sigma,
+            risk_free_rate,
+            dividend_yield,
+            maturity,
+        ),
+        put_delta: put_greeks.delta,
+        put_gamma: put_greeks.gamma,
+        put_theta: put_greeks.theta,
+        put_vega: put_greeks.vega,
+        put_rho: put_greeks.rho,
+        put_vanna: put_greeks.vanna,
+        put_vomma: put_greeks.vomma,
+        put_charm: put_greeks.charm,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monte_carlo::MonteCarlo;
+    use approx::*;
+
+    const STRIKES: [f64; 3] = [90.0, 100.0, 110.0];
+
+    // Haug (2007) table 4-13: S = 100, rebate 3, r = 0.08, q = 0.04,
+    // T = 0.5 and σ = 0.25, for strikes 90, 100 and 110
+    #[test]
+    fn matches_reference_values() {
+        let cases = [
+            (BarrierKind::DownAndOut, 1.0, 95.0, [9.0246, 6.7924, 4.8759]),
+            (BarrierKind::DownAndOut, 1.0, 100.0, [3.0, 3.0, 3.0]),
+            (BarrierKind::UpAndOut, 1.0, 105.0, [2.6789, 2.3580, 2.3453]),
+            (BarrierKind::DownAndIn, 1.0, 95.0, [7.7627, 4.0109, 2.0576]),
+            (
+                BarrierKind::DownAndIn,
+                1.0,
+                100.0,
+                [13.8333, 7.8494, 3.9795],
+            ),
+            (BarrierKind::UpAndIn, 1.0, 105.0, [14.1112, 8.4482, 4.5910]),
+            (
+                BarrierKind::DownAndIn,
+                -1.0,
+                95.0,
+                [2.9586, 6.5677, 11.9752],
+            ),
+            (
+                BarrierKind::DownAndIn,
+                -1.0,
+                100.0,
+                [2.2845, 5.9085, 11.6465],
+            ),
+            (BarrierKind::UpAndIn, -1.0, 105.0, [1.4653, 3.3721, 7.0846]),
+            (
+                BarrierKind::DownAndOut,
+                -1.0,
+                95.0,
+                [2.2798, 2.2947, 2.6252],
+            ),
+            (BarrierKind::DownAndOut, -1.0, 100.0, [3.0, 3.0, 3.0]),
+            (BarrierKind::UpAndOut, -1.0, 105.0, [3.7760, 5.4932, 7.5187]),
+        ];
+        for (kind, omega, barrier, expected) in cases {
+            for (strike, expected) in STRIKES.iter().zip(expected) {
+                assert_abs_diff_eq!(
+                    price(
+                        omega, kind, 100.0, *strike, barrier, 3.0, 0.25, 0.08, 0.04, 0.5
+                    ),
+                    expected,
+                    epsilon = 1e-4
+                );
+            }
+        }
+    }
+    #[test]
+    fn in_plus_out_is_vanilla() {
+        let (s, sigma, rate, q, maturity) = (100.0, 0.3, 0.05, 0.02, 0.75);
+        for strike in STRIKES {
+            let vanilla = bsm_compute_all(s, strike, sigma, rate, q, maturity);
+            for (knock_in, knock_out, barrier) in [
+                (BarrierKind::DownAndIn, BarrierKind::DownAndOut, 92.0),
+                (BarrierKind::UpAndIn, BarrierKind::UpAndOut, 108.0),
+            ] {
+                let knocked_in =
+                    compute_all(knock_in, s, strike, barrier, 0.0, sigma, rate, q, maturity);
+                let knocked_out =
+                    compute_all(knock_out, s, strike, barrier, 0.0, sigma, rate, q, maturity);
+                assert_abs_diff_eq!(
+                    knocked_in.call_price + knocked_out.call_price,
+                    vanilla.call_price,
+                    epsilon = 1e-10
+                );
+                assert_abs_diff_eq!(
+                    knocked_in.put_price + knocked_out.put_price,
+                    vanilla.put_price,
+                    epsilon = 1e-10
+                );
+                assert_abs_diff_eq!(
+                    knocked_in.call_delta + knocked_out.call_delta,
+                    vanilla.call_delta,
+                    epsilon = 1e-6
+                );
+                assert_abs_diff_eq!(
+                    knocked_in.put_gamma + knocked_out.put_gamma,
+                    vanilla.put_gamma,
+                    epsilon = 1e-5
+                );
+                assert_abs_diff_eq!(
+                    knocked_in.call_theta + knocked_out.call_theta,
+                    vanilla.call_theta,
+                    epsilon = 1e-4
+                );
+            }
+        }
+    }
+    #[test]
+    fn distant_barrier_is_vanilla() {
+        let (s, strike, sigma, rate, q, maturity) = (100.0, 100.0, 0.2, 0.05, 0.01, 1.0);
+        let vanilla = bsm_compute_all(s, strike, sigma, rate, q, maturity);
+        let knocked_out = compute_all(
+            BarrierKind::DownAndOut,
+            s,
+            strike,
+            10.0,
+            0.0,
+            sigma,
+            rate,
+            q,
+            maturity,
+        );
+        assert_abs_diff_eq!(knocked_out.call_price, vanilla.call_price, epsilon = 1e-10);
+        assert_abs_diff_eq!(knocked_out.call_delta, vanilla.call_delta, epsilon = 1e-7);
+        assert_abs_diff_eq!(knocked_out.call_gamma, vanilla.call_gamma, epsilon = 1e-5);
+        assert_abs_diff_eq!(knocked_out.call_rho, vanilla.call_rho, epsilon = 1e-5);
+    }
+    #[test]
+    fn knocked_out_pays_rebate() {
+        let price = put(
+            BarrierKind::UpAndOut,
+            121.0,
+            100.0,
+            120.0,
+            2.5,
+            0.2,
+            0.05,
+            0.0,
+            1.0,
+        );
+        assert_eq!(price, 2.5);
+    }
+    #[test]
+    fn zero_volatility_knock_in_pays_discounted_rebate() {
+        let price = call(
+            BarrierKind::DownAndIn,
+            100.0,
+            100.0,
+            90.0,
+            2.5,
+            0.0,
+            0.05,
+            0.0,
+            1.0,
+        );
+        assert_abs_diff_eq!(price, 2.5 * (-0.05f64).exp());
+    }
+    #[test]
+    fn zero_volatility_follows_the_forward() {
+        let kinds = [
+            BarrierKind::DownAndIn,
+            BarrierKind::DownAndOut,
+            BarrierKind::UpAndIn,
+            BarrierKind::UpAndOut,
+        ];
+        // (strike, barrier, risk free rate, dividend yield)
+        let cases = [
+            (100.0, 90.0, 0.05, 0.0),
+            (95.0, 90.0, 0.0, 0.05),
+            (90.0, 95.0, 0.0, 0.05),
+            (100.0, 110.0, 0.05, 0.0),
+            (110.0, 105.0, 0.05, 0.0),
+            (105.0, 110.0, 0.0, 0.05),
+        ];
+        for (strike, barrier, rate, q) in cases {
+            for kind in kinds {
+                if kind.is_down() != (barrier < 100.0) {
+                    continue;
+                }
+                for price in [call, put] {
+                    let deterministic = price(kind, 100.0, strike, barrier, 2.0, 0.0, rate, q, 1.0);
+                    let nearly = price(kind, 100.0, strike, barrier, 2.0, 1e-6, rate, q, 1.0);
+                    assert_abs_diff_eq!(deterministic, nearly, epsilon = 1e-6);
+                }
+            }
+        }
+        // the forward rises through the barrier, knocking out at t = ln(1.05)/r
+        let knocked_out = call(
+            BarrierKind::UpAndOut,
+            100.0,
+            110.0,
+            105.0,
+            2.0,
+            0.0,
+            0.05,
+            0.0,
+            1.0,
+        );
+        assert_abs_diff_eq!(knocked_out, 2.0 / 1.05, epsilon = 1e-12);
+    }
+    #[test]
+    fn low_volatility_is_finite() {
+        for kind in [BarrierKind::UpAndIn, BarrierKind::UpAndOut] {
+            for price in [call, put] {
+                for sigma in [1e-3, 1e-4] {
+                    assert!(
+                        price(kind, 100.0, 100.0, 110.0, 2.0, sigma, 0.05, 0.0, 1.0).is_finite()
+                    );
+                    assert!(
+                        price(kind, 100.0, 100.0, 110.0, 2.0, sigma, 0.0, 0.05, 1.0).is_finite()
+                    );
+                }
+            }
+        }
+    }
+    #[test]
+    fn discrete_monitoring_correction_matches_simulation() {
+        let (s, strike, barrier, sigma, rate, maturity) = (100.0, 100.0, 90.0, 0.3, 0.05, 0.5);
+        let steps = 25;
+        let simulated = MonteCarlo::new(100_000, steps, 9).price(
+            |path| {
+                if path.iter().any(|&s| s <= barrier) {
+                    0.0
+                } else {
+                    max_or_zero(path[steps - 1] - strike)
+                }
+            },
+            s,
+            sigma,
+            rate,
+            0.0,
+            maturity,
+        );
+        let shifted = discrete_monitoring_barrier(
+            BarrierKind::DownAndOut,
+            barrier,
+            sigma,
+            maturity / steps as f64,
+        );
+        let corrected = call(
+            BarrierKind::DownAndOut,
+            s,
+            strike,
+            shifted,
+            0.0,
+            sigma,
+            rate,
+            0.0,
+            maturity,
+        );
+        let continuous = call(
+            BarrierKind::DownAndOut,
+            s,
+            strike,
+            barrier,
+            0.0,
+            sigma,
+            rate,
+            0.0,
+            maturity,
+        );
+        assert!((corrected - simulated.price).abs() < 4.0 * simulated.standard_error);
+        assert!((continuous - simulated.price).abs() > 10.0 * simulated.standard_error);
+    }
+}
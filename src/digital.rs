@@ -0,0 +1,604 @@
+//! Cash-or-nothing and asset-or-nothing digital options with a continuous
+//! dividend yield.
+//!
+//! A cash-or-nothing call pays `cash` if the stock finishes above the
+//! strike, worth `cash·e^{-rT}N(d2)`; an asset-or-nothing call delivers the
+//! stock, worth `S·e^{-qT}N(d1)`.  A vanilla call is the asset-or-nothing
+//! call less `strike` cash-or-nothing calls with unit cash.
+use crate::{PricesAndGreeks, cum_norm, inc_norm};
+
+// d1, d2 and their derivatives with respect to maturity
+struct Moneyness {
+    d1: f64,
+    d2: f64,
+    d1_maturity: f64,
+    d2_maturity: f64,
+}
+
+fn moneyness(
+    stock: f64,
+    strike: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> Moneyness {
+    let sqrt_maturity_sigma = sigma * maturity.sqrt();
+    let carry = risk_free_rate - dividend_yield;
+    let d1 =
+        ((stock / strike).ln() + (carry + 0.5 * sigma * sigma) * maturity) / sqrt_maturity_sigma;
+    let d2 = d1 - sqrt_maturity_sigma;
+    Moneyness {
+        d1,
+        d2,
+        d1_maturity: (carry + 0.5 * sigma * sigma) / sqrt_maturity_sigma - d1 / (2.0 * maturity),
+        d2_maturity: (carry - 0.5 * sigma * sigma) / sqrt_maturity_sigma - d2 / (2.0 * maturity),
+    }
+}
+
+// whether the forward finishes above the strike, for zero volatility
+fn forward_above_strike(
+    stock: f64,
+    strike: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> bool {
+    stock * ((risk_free_rate - dividend_yield) * maturity).exp() > strike
+}
+
+/// Returns the price of a call paying `cash` if the stock finishes above
+/// `strike`.
+///
+/// # Examples
+///
+/// ```
+/// let stock = 100.0;
+/// let strike = 105.0;
+/// let cash = 10.0;
+/// let sigma = 0.2;
+/// let risk_free_rate = 0.05;
+/// let dividend_yield = 0.02;
+/// let maturity = 1.0;
+/// let price = black_scholes::digital::cash_or_nothing_call(
+///     stock, strike, cash, sigma, risk_free_rate, dividend_yield, maturity
+/// );
+/// ```
+pub fn cash_or_nothing_call(
+    stock: f64,
+    strike: f64,
+    cash: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    cash_or_nothing_compute_all(
+        stock,
+        strike,
+        cash,
+        sigma,
+        risk_free_rate,
+        dividend_yield,
+        maturity,
+    )
+    .call_price
+}
+
+/// Returns the price of a put paying `cash` if the stock finishes below
+/// `strike`.
+///
+/// # Examples
+///
+/// ```
+/// let stock = 100.0;
+/// let strike = 105.0;
+/// let cash = 10.0;
+/// let sigma = 0.2;
+/// let risk_free_rate = 0.05;
+/// let dividend_yield = 0.02;
+/// let maturity = 1.0;
+/// let price = black_scholes::digital::cash_or_nothing_put(
+///     stock, strike, cash, sigma, risk_free_rate, dividend_yield, maturity
+/// );
+/// ```
+pub fn cash_or_nothing_put(
+    stock: f64,
+    strike: f64,
+    cash: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    cash_or_nothing_compute_all(
+        stock,
+        strike,
+        cash,
+        sigma,
+        risk_free_rate,
+        dividend_yield,
+        maturity,
+    )
+    .put_price
+}
+
+/// Returns the price of a call delivering the stock if it finishes above
+/// `strike`.
+///
+/// # Examples
+///
+/// ```
+/// let stock = 100.0;
+/// let strike = 105.0;
+/// let sigma = 0.2;
+/// let risk_free_rate = 0.05;
+/// let dividend_yield = 0.02;
+/// let maturity = 1.0;
+/// let price = black_scholes::digital::asset_or_nothing_call(
+///     stock, strike, sigma, risk_free_rate, dividend_yield, maturity
+/// );
+/// ```
+pub fn asset_or_nothing_call(
+    stock: f64,
+    strike: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    asset_or_nothing_compute_all(
+        stock,
+        strike,
+        sigma,
+        risk_free_rate,
+        dividend_yield,
+        maturity,
+    )
+    .call_price
+}
+
+/// Returns the price of a put delivering the stock if it finishes below
+/// `strike`.
+///
+/// # Examples
+///
+/// ```
+/// let stock = 100.0;
+/// let strike = 105.0;
+/// let sigma = 0.2;
+/// let risk_free_rate = 0.05;
+/// let dividend_yield = 0.02;
+/// let maturity = 1.0;
+/// let price = black_scholes::digital::asset_or_nothing_put(
+///     stock, strike, sigma, risk_free_rate, dividend_yield, maturity
+/// );
+/// ```
+pub fn asset_or_nothing_put(
+    stock: f64,
+    strike: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    asset_or_nothing_compute_all(
+        stock,
+        strike,
+        sigma,
+        risk_free_rate,
+        dividend_yield,
+        maturity,
+    )
+    .put_price
+}
+
+/// Returns cash-or-nothing call and put prices and greeks, with the same
+/// conventions as `bsm_compute_all`.
+///
+/// # Examples
+///
+/// ```
+/// let stock = 100.0;
+/// let strike = 105.0;
+/// let cash = 1.0;
+/// let sigma = 0.2;
+/// let risk_free_rate = 0.05;
+/// let dividend_yield = 0.02;
+/// let maturity = 1.0;
+/// let all = black_scholes::digital::cash_or_nothing_compute_all(
+///     stock, strike, cash, sigma, risk_free_rate, dividend_yield, maturity
+/// );
+/// let discount = (-risk_free_rate * maturity).exp();
+/// assert!((all.call_price + all.put_price - discount).abs() < 1e-12);
+/// ```
+pub fn cash_or_nothing_compute_all(
+    stock: f64,
+    strike: f64,
+    cash: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> PricesAndGreeks {
+    let cash_discount = cash * (-risk_free_rate * maturity).exp();
+    let sqrt_maturity = maturity.sqrt();
+    let sqrt_maturity_sigma = sigma * sqrt_maturity;
+    if sqrt_maturity_sigma > 0.0 {
+        let Moneyness {
+            d1,
+            d2,
+            d2_maturity,
+            ..
+        } = moneyness(
+            stock,
+            strike,
+            sigma,
+            risk_free_rate,
+            dividend_yield,
+            maturity,
+        );
+        let density = cash_discount * inc_norm(d2);
+
+        let call_price = cash_discount * cum_norm(d2);
+        let call_delta = density / (stock * sqrt_maturity_sigma);
+        let call_gamma =
+            -density * d1 / (stock * stock * sqrt_maturity_sigma * sqrt_maturity_sigma);
+        let call_theta = risk_free_rate * call_price - density * d2_maturity;
+        let call_vega = -density * d1 / sigma;
+        let call_rho = density * sqrt_maturity / sigma - maturity * call_price;
+        let call_vanna = density * (d1 * d2 - 1.0) / (stock * sigma * sqrt_maturity_sigma);
+        let call_vomma = density * (d1 + d2 - d1 * d1 * d2) / (sigma * sigma);
+        let call_charm = call_delta * (risk_free_rate + d2 * d2_maturity + 0.5 / maturity);
+        PricesAndGreeks {
+            call_price,
+            call_delta,
+            call_gamma,
+            call_theta,
+            call_vega,
+            call_rho,
+            call_vanna,
+            call_vomma,
+            call_charm,
+            put_price: cash_discount - call_price,
+            put_delta: -call_delta,
+            put_gamma: -call_gamma,
+            put_theta: risk_free_rate * cash_discount - call_theta,
+            put_vega: -call_vega,
+            put_rho: -maturity * cash_discount - call_rho,
+            put_vanna: -call_vanna,
+            put_vomma: -call_vomma,
+            put_charm: -call_charm,
+        }
+    } else {
+        let (call_price, put_price) =
+            if forward_above_strike(stock, strike, risk_free_rate, dividend_yield, maturity) {
+                (cash_discount, 0.0)
+            } else {
+                (0.0, cash_discount)
+            };
+        PricesAndGreeks {
+            call_price,
+            call_delta: 0.0,
+            call_gamma: 0.0,
+            call_theta: risk_free_rate * call_price,
+            call_vega: 0.0,
+            call_rho: -maturity * call_price,
+            call_vanna: 0.0,
+            call_vomma: 0.0,
+            call_charm: 0.0,
+            put_price,
+            put_delta: 0.0,
+            put_gamma: 0.0,
+            put_theta: risk_free_rate * put_price,
+            put_vega: 0.0,
+            put_rho: -maturity * put_price,
+            put_vanna: 0.0,
+            put_vomma: 0.0,
+            put_charm: 0.0,
+        }
+    }
+}
+
+/// Returns asset-or-nothing call and put prices and greeks, with the same
+/// conventions as `bsm_compute_all`.
+///
+/// # Examples
+///
+/// ```
+/// let stock = 100.0;
+/// let strike = 105.0;
+/// let sigma = 0.2;
+/// let risk_free_rate = 0.05;
+/// let dividend_yield = 0.02;
+/// let maturity = 1.0;
+/// let all = black_scholes::digital::asset_or_nothing_compute_all(
+///     stock, strike, sigma, risk_free_rate, dividend_yield, maturity
+/// );
+/// let dividend = (-dividend_yield * maturity).exp();
+/// assert!((all.call_price + all.put_price - stock * dividend).abs() < 1e-12);
+/// ```
+pub fn asset_or_nothing_compute_all(
+    stock: f64,
+    strike: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> PricesAndGreeks {
+    let dividend = (-dividend_yield * maturity).exp();
+    let sqrt_maturity = maturity.sqrt();
+    let sqrt_maturity_sigma = sigma * sqrt_maturity;
+    if sqrt_maturity_sigma > 0.0 {
+        let Moneyness {
+            d1,
+            d2,
+            d1_maturity,
+            ..
+        } = moneyness(
+            stock,
+            strike,
+            sigma,
+            risk_free_rate,
+            dividend_yield,
+            maturity,
+        );
+        let cdf_d1 = cum_norm(d1);
+        let density = dividend * inc_norm(d1);
+
+        let call_price = stock * dividend * cdf_d1;
+        let call_delta = dividend * cdf_d1 + density / sqrt_maturity_sigma;
+        let call_gamma = -density * d2 / (stock * sqrt_maturity_sigma * sqrt_maturity_sigma);
+        let call_theta = dividend_yield * call_price - stock * density * d1_maturity;
+        let call_vega = -stock * density * d2 / sigma;
+        let call_rho = stock * density * sqrt_maturity / sigma;
+        let call_vanna =
+            density * (d1 * d2 - 1.0 - sqrt_maturity_sigma * d2) / (sigma * sqrt_maturity_sigma);
+        let call_vomma = stock * density * (d1 + d2 - d1 * d2 * d2) / (sigma * sigma);
+        let call_charm = dividend_yield * dividend * cdf_d1 - density * d1_maturity
+            + density / sqrt_maturity_sigma * (dividend_yield + d1 * d1_maturity + 0.5 / maturity);
+        PricesAndGreeks {
+            call_price,
+            call_delta,
+            call_gamma,
+            call_theta,
+            call_vega,
+            call_rho,
+            call_vanna,
+            call_vomma,
+            call_charm,
+            put_price: stock * dividend - call_price,
+            put_delta: dividend - call_delta,
+            put_gamma: -call_gamma,
+            put_theta: dividend_yield * stock * dividend - call_theta,
+            put_vega: -call_vega,
+            put_rho: -call_rho,
+            put_vanna: -call_vanna,
+            put_vomma: -call_vomma,
+            put_charm: dividend_yield * dividend - call_charm,
+        }
+    } else {
+        let in_the_money =
+            if forward_above_strike(stock, strike, risk_free_rate, dividend_yield, maturity) {
+                1.0
+            } else {
+                0.0
+            };
+        let call_delta = dividend * in_the_money;
+        let put_delta = dividend - call_delta;
+        PricesAndGreeks {
+            call_price: stock * call_delta,
+            call_delta,
+            call_gamma: 0.0,
+            call_theta: dividend_yield * stock * call_delta,
+            call_vega: 0.0,
+            call_rho: 0.0,
+            call_vanna: 0.0,
+            call_vomma: 0.0,
+            call_charm: dividend_yield * call_delta,
+            put_price: stock * put_delta,
+            put_delta,
+            put_gamma: 0.0,
+            put_theta: dividend_yield * stock * put_delta,
+            put_vega: 0.0,
+            put_rho: 0.0,
+            put_vanna: 0.0,
+            put_vomma: 0.0,
+            put_charm: dividend_yield * put_delta,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bsm_compute_all;
+    use approx::*;
+
+    // (stock, sigma, rate, maturity) -> price
+    fn check_greeks(
+        price: impl Fn(f64, f64, f64, f64) -> f64,
+        all: impl Fn(f64, f64, f64, f64) -> [f64; 9],
+        stock: f64,
+        sigma: f64,
+        rate: f64,
+        maturity: f64,
+    ) {
+        let [p, delta, gamma, theta, vega, rho, vanna, vomma, charm] =
+            all(stock, sigma, rate, maturity);
+        let hs = 1e-4 * stock;
+        let hv = 1e-4;
+        let ht = 1e-5;
+        let delta_at = |s: f64, v: f64, t: f64| all(s, v, rate, t)[1];
+        let vega_at = |v: f64| all(stock, v, rate, maturity)[4];
+        assert_abs_diff_eq!(p, price(stock, sigma, rate, maturity), epsilon = 1e-12);
+        let fd_delta = (price(stock + hs, sigma, rate, maturity)
+            - price(stock - hs, sigma, rate, maturity))
+            / (2.0 * hs);
+        assert_relative_eq!(delta, fd_delta, epsilon = 1e-8, max_relative = 1e-5);
+        let fd_gamma = (delta_at(stock + hs, sigma, maturity)
+            - delta_at(stock - hs, sigma, maturity))
+            / (2.0 * hs);
+        assert_relative_eq!(gamma, fd_gamma, epsilon = 1e-8, max_relative = 1e-5);
+        let fd_theta = -(price(stock, sigma, rate, maturity + ht)
+            - price(stock, sigma, rate, maturity - ht))
+            / (2.0 * ht);
+        assert_relative_eq!(theta, fd_theta, epsilon = 1e-8, max_relative = 1e-5);
+        let fd_vega = (price(stock, sigma + hv, rate, maturity)
+            - price(stock, sigma - hv, rate, maturity))
+            / (2.0 * hv);
+        assert_relative_eq!(vega, fd_vega, epsilon = 1e-8, max_relative = 1e-5);
+        let fd_rho = (price(stock, sigma, rate + hv, maturity)
+            - price(stock, sigma, rate - hv, maturity))
+            / (2.0 * hv);
+        assert_relative_eq!(rho, fd_rho, epsilon = 1e-8, max_relative = 1e-5);
+        let fd_vanna = (delta_at(stock, sigma + hv, maturity)
+            - delta_at(stock, sigma - hv, maturity))
+            / (2.0 * hv);
+        assert_relative_eq!(vanna, fd_vanna, epsilon = 1e-8, max_relative = 1e-5);
+        let fd_vomma = (vega_at(sigma + hv) - vega_at(sigma - hv)) / (2.0 * hv);
+        assert_relative_eq!(vomma, fd_vomma, epsilon = 1e-8, max_relative = 1e-5);
+        let fd_charm = -(delta_at(stock, sigma, maturity + ht)
+            - delta_at(stock, sigma, maturity - ht))
+            / (2.0 * ht);
+        assert_relative_eq!(charm, fd_charm, epsilon = 1e-8, max_relative = 1e-5);
+    }
+
+    fn call_greeks(all: PricesAndGreeks) -> [f64; 9] {
+        [
+            all.call_price,
+            all.call_delta,
+            all.call_gamma,
+            all.call_theta,
+            all.call_vega,
+            all.call_rho,
+            all.call_vanna,
+            all.call_vomma,
+            all.call_charm,
+        ]
+    }
+    fn put_greeks(all: PricesAndGreeks) -> [f64; 9] {
+        [
+            all.put_price,
+            all.put_delta,
+            all.put_gamma,
+            all.put_theta,
+            all.put_vega,
+            all.put_rho,
+            all.put_vanna,
+            all.put_vomma,
+            all.put_charm,
+        ]
+    }
+
+    const Q: f64 = 0.03;
+    const CASES: [(f64, f64); 3] = [(95.0, 100.0), (100.0, 100.0), (110.0, 100.0)];
+
+    #[test]
+    fn cash_or_nothing_greeks_match_finite_differences() {
+        for (stock, strike) in CASES {
+            let all = |s, v, r, t| cash_or_nothing_compute_all(s, strike, 2.0, v, r, Q, t);
+            check_greeks(
+                |s, v, r, t| cash_or_nothing_call(s, strike, 2.0, v, r, Q, t),
+                |s, v, r, t| call_greeks(all(s, v, r, t)),
+                stock,
+                0.25,
+                0.05,
+                0.75,
+            );
+            check_greeks(
+                |s, v, r, t| cash_or_nothing_put(s, strike, 2.0, v, r, Q, t),
+                |s, v, r, t| put_greeks(all(s, v, r, t)),
+                stock,
+                0.25,
+                0.05,
+                0.75,
+            );
+        }
+    }
+    #[test]
+    fn asset_or_nothing_greeks_match_finite_differences() {
+        for (stock, strike) in CASES {
+            let all = |s, v, r, t| asset_or_nothing_compute_all(s, strike, v, r, Q, t);
+            check_greeks(
+                |s, v, r, t| asset_or_nothing_call(s, strike, v, r, Q, t),
+                |s, v, r, t| call_greeks(all(s, v, r, t)),
+                stock,
+                0.25,
+                0.05,
+                0.75,
+            );
+            check_greeks(
+                |s, v, r, t| asset_or_nothing_put(s, strike, v, r, Q, t),
+                |s, v, r, t| put_greeks(all(s, v, r, t)),
+                stock,
+                0.25,
+                0.05,
+                0.75,
+            );
+        }
+    }
+    #[test]
+    fn vanilla_is_asset_less_cash() {
+        let (stock, strike, sigma, rate, maturity) = (100.0, 95.0, 0.3, 0.04, 0.5);
+        let vanilla = bsm_compute_all(stock, strike, sigma, rate, Q, maturity);
+        let asset = asset_or_nothing_compute_all(stock, strike, sigma, rate, Q, maturity);
+        let cash = cash_or_nothing_compute_all(stock, strike, strike, sigma, rate, Q, maturity);
+        assert_abs_diff_eq!(
+            asset.call_price - cash.call_price,
+            vanilla.call_price,
+            epsilon = 1e-12
+        );
+        assert_abs_diff_eq!(
+            asset.put_price - cash.put_price,
+            -vanilla.put_price,
+            epsilon = 1e-12
+        );
+        assert_abs_diff_eq!(
+            asset.call_delta - cash.call_delta,
+            vanilla.call_delta,
+            epsilon = 1e-12
+        );
+        assert_abs_diff_eq!(
+            asset.call_gamma - cash.call_gamma,
+            vanilla.call_gamma,
+            epsilon = 1e-12
+        );
+        assert_abs_diff_eq!(
+            asset.call_theta - cash.call_theta,
+            vanilla.call_theta,
+            epsilon = 1e-10
+        );
+        assert_abs_diff_eq!(
+            asset.call_rho - cash.call_rho,
+            vanilla.call_rho,
+            epsilon = 1e-10
+        );
+        assert_abs_diff_eq!(
+            asset.call_charm - cash.call_charm,
+            vanilla.call_charm,
+            epsilon = 1e-12
+        );
+    }
+    #[test]
+    fn call_spread_converges_to_cash_or_nothing() {
+        let (stock, strike, sigma, rate, maturity) = (100.0, 105.0, 0.2, 0.05, 1.0);
+        let width = 1e-3;
+        let call = |k| bsm_compute_all(stock, k, sigma, rate, Q, maturity).call_price;
+        let spread = (call(strike - width) - call(strike + width)) / (2.0 * width);
+        assert_abs_diff_eq!(
+            cash_or_nothing_call(stock, strike, 1.0, sigma, rate, Q, maturity),
+            spread,
+            epsilon = 1e-7
+        );
+    }
+    #[test]
+    fn zero_volatility_pays_on_the_forward() {
+        // the forward is above the strike although the spot is below it
+        let all = cash_or_nothing_compute_all(99.0, 100.0, 1.0, 0.0, 0.05, 0.0, 1.0);
+        assert_abs_diff_eq!(all.call_price, (-0.05f64).exp(), epsilon = 1e-15);
+        assert_eq!(all.put_price, 0.0);
+        let all = asset_or_nothing_compute_all(99.0, 100.0, 0.0, 0.05, 0.0, 1.0);
+        assert_abs_diff_eq!(all.call_price, 99.0, epsilon = 1e-12);
+    }
+}
@@ -0,0 +1,646 @@
+//! Double barrier options by the Ikeda and Kunitomo (1992) series.
+//!
+//! The barriers may curve exponentially in time, `L·e^{δ₂t}` and
+//! `U·e^{δ₁t}`, and are monitored continuously.  Knock-out options and
+//! no-touch binaries pay at maturity only if the stock stays strictly
+//! between the barriers; knock-in options and one-touch binaries are their
+//! complements and also pay at maturity.
+use crate::bsm_compute_all;
+use crate::stats::log_cum_norm;
+use serde::Serialize;
+
+const DEFAULT_TOLERANCE: f64 = 1e-12;
+const MAX_TERMS: i32 = 100;
+
+/// Lower and upper barriers with their curvatures.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::double_barrier::DoubleBarrier;
+/// let corridor = DoubleBarrier::new(80.0, 120.0)
+///     .unwrap()
+///     .with_curvature(0.05, -0.05);
+/// assert!(DoubleBarrier::new(120.0, 80.0).is_none());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct DoubleBarrier {
+    lower: f64,
+    upper: f64,
+    lower_curvature: f64,
+    upper_curvature: f64,
+    tolerance: f64,
+}
+
+impl DoubleBarrier {
+    /// Returns flat barriers, with the series truncated once a pair of
+    /// terms adds less than 1e-12.  Returns `None` unless
+    /// `0 < lower < upper`.
+    pub fn new(lower: f64, upper: f64) -> Option<Self> {
+        if !(lower > 0.0 && lower < upper) {
+            return None;
+        }
+        Some(DoubleBarrier {
+            lower,
+            upper,
+            lower_curvature: 0.0,
+            upper_curvature: 0.0,
+            tolerance: DEFAULT_TOLERANCE,
+        })
+    }
+    /// Curves the barriers to `lower·e^{lower_curvature·t}` and
+    /// `upper·e^{upper_curvature·t}`.
+    pub fn with_curvature(self, lower_curvature: f64, upper_curvature: f64) -> Self {
+        DoubleBarrier {
+            lower_curvature,
+            upper_curvature,
+            ..self
+        }
+    }
+    /// Truncates the series once the terms for `n` and `-n` together add
+    /// less than `tolerance` (per unit of stock or cash).
+    pub fn with_tolerance(self, tolerance: f64) -> Self {
+        DoubleBarrier { tolerance, ..self }
+    }
+
+    fn is_breached(&self, stock: f64) -> bool {
+        stock <= self.lower || stock >= self.upper
+    }
+
+    // Values at maturity of receiving the stock and one unit of cash if it
+    // never leaves the barriers and finishes between `low` and `high`
+    #[allow(clippy::too_many_arguments)]
+    fn asset_and_cash(
+        &self,
+        stock: f64,
+        low: f64,
+        high: f64,
+        sigma: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        maturity: f64,
+    ) -> (f64, f64) {
+        if low >= high {
+            return (0.0, 0.0);
+        }
+        let carry = risk_free_rate - dividend_yield;
+        let sqrt_maturity_sigma = sigma * maturity.sqrt();
+        if sqrt_maturity_sigma <= 0.0 {
+            // the stock follows its forward, and its log distance to each
+            // barrier is linear in time, so it stays inside if it finishes
+            // inside
+            let forward = stock * (carry * maturity).exp();
+            let (lower, upper) = self.at_maturity(maturity);
+            let discount = (-risk_free_rate * maturity).exp();
+            return if forward > lower && forward < upper && forward > low && forward <= high {
+                (discount * forward, discount)
+            } else {
+                (0.0, 0.0)
+            };
+        }
+        let (l, u) = (self.lower, self.upper);
+        let (delta_lower, delta_upper) = (self.lower_curvature, self.upper_curvature);
+        let variance = sigma * sigma;
+        let drift = (carry + 0.5 * variance) * maturity;
+        let d =
+            |numerator: f64, bound: f64| ((numerator / bound).ln() + drift) / sqrt_maturity_sigma;
+        // e^{log_scale}·(Φ(x_low - shift) - Φ(x_high - shift)), with the
+        // difference taken in the nearer tail and the powers of the barrier
+        // ratios, which overflow at low volatility, applied in log space
+        let band = |log_scale: f64, x_low: f64, x_high: f64, shift: f64| {
+            let (below, above) = if x_low + x_high > 2.0 * shift {
+                (shift - x_high, shift - x_low)
+            } else {
+                (x_low - shift, x_high - shift)
+            };
+            (log_scale + log_cum_norm(below)).exp() - (log_scale + log_cum_norm(above)).exp()
+        };
+        let (log_barrier_ratio, log_lower_ratio) = ((u / l).ln(), (l / stock).ln());
+        let term = |n: i32| -> (f64, f64) {
+            let n_f = n as f64;
+            let mu1 =
+                2.0 * (carry - delta_lower - n_f * (delta_upper - delta_lower)) / variance + 1.0;
+            let mu2 = 2.0 * n_f * (delta_upper - delta_lower) / variance;
+            let mu3 =
+                2.0 * (carry - delta_lower + n_f * (delta_upper - delta_lower)) / variance + 1.0;
+            let ratio = (u / l).powi(n);
+            let direct = stock * ratio * ratio;
+            let reflected = l * l / (stock * ratio * ratio);
+            let (direct_low, direct_high) = (d(direct, low), d(direct, high));
+            let (reflected_low, reflected_high) = (d(reflected, low), d(reflected, high));
+            let log_ratio = n_f * log_barrier_ratio;
+            let log_direct_weight = mu2 * log_lower_ratio;
+            let log_reflected_base = log_lower_ratio - log_ratio;
+            let asset = band(
+                mu1 * log_ratio + log_direct_weight,
+                direct_low,
+                direct_high,
+                0.0,
+            ) - band(mu3 * log_reflected_base, reflected_low, reflected_high, 0.0);
+            let cash = band(
+                (mu1 - 2.0) * log_ratio + log_direct_weight,
+                direct_low,
+                direct_high,
+                sqrt_maturity_sigma,
+            ) - band(
+                (mu3 - 2.0) * log_reflected_base,
+                reflected_low,
+                reflected_high,
+                sqrt_maturity_sigma,
+            );
+            (asset, cash)
+        };
+        let (mut asset, mut cash) = term(0);
+        for n in 1..=MAX_TERMS {
+            let (asset_up, cash_up) = term(n);
+            let (asset_down, cash_down) = term(-n);
+            let asset_change = asset_up + asset_down;
+            let cash_change = cash_up + cash_down;
+            asset += asset_change;
+            cash += cash_change;
+            if asset_change.abs() < self.tolerance && cash_change.abs() < self.tolerance {
+                break;
+            }
+        }
+        (
+            stock * ((carry - risk_free_rate) * maturity).exp() * asset,
+            (-risk_free_rate * maturity).exp() * cash,
+        )
+    }
+
+    fn at_maturity(&self, maturity: f64) -> (f64, f64) {
+        (
+            self.lower * (self.lower_curvature * maturity).exp(),
+            self.upper * (self.upper_curvature * maturity).exp(),
+        )
+    }
+}
+
+/// Returns the price of a call that is knocked out if the stock touches
+/// either barrier.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::double_barrier::{knock_out_call, DoubleBarrier};
+/// let barrier = DoubleBarrier::new(80.0, 130.0).unwrap();
+/// let stock = 100.0;
+/// let strike = 100.0;
+/// let sigma = 0.2;
+/// let risk_free_rate = 0.05;
+/// let dividend_yield = 0.0;
+/// let maturity = 0.5;
+/// let price = knock_out_call(
+///     &barrier, stock, strike, sigma, risk_free_rate, dividend_yield, maturity
+/// );
+/// assert!(price < black_scholes::call(stock, strike, risk_free_rate, sigma, maturity));
+/// ```
+pub fn knock_out_call(
+    barrier: &DoubleBarrier,
+    stock: f64,
+    strike: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    if barrier.is_breached(stock) {
+        return 0.0;
+    }
+    let (lower, upper) = barrier.at_maturity(maturity);
+    let (asset, cash) = barrier.asset_and_cash(
+        stock,
+        strike.max(lower),
+        upper,
+        sigma,
+        risk_free_rate,
+        dividend_yield,
+        maturity,
+    );
+    asset - strike * cash
+}
+
+/// Returns the price of a put that is knocked out if the stock touches
+/// either barrier.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::double_barrier::{knock_out_put, DoubleBarrier};
+/// let barrier = DoubleBarrier::new(80.0, 130.0).unwrap();
+/// let stock = 100.0;
+/// let strike = 100.0;
+/// let sigma = 0.2;
+/// let risk_free_rate = 0.05;
+/// let dividend_yield = 0.0;
+/// let maturity = 0.5;
+/// let price = knock_out_put(
+///     &barrier, stock, strike, sigma, risk_free_rate, dividend_yield, maturity
+/// );
+/// assert!(price < black_scholes::put(stock, strike, risk_free_rate, sigma, maturity));
+/// ```
+pub fn knock_out_put(
+    barrier: &DoubleBarrier,
+    stock: f64,
+    strike: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    if barrier.is_breached(stock) {
+        return 0.0;
+    }
+    let (lower, upper) = barrier.at_maturity(maturity);
+    let (asset, cash) = barrier.asset_and_cash(
+        stock,
+        lower,
+        strike.min(upper),
+        sigma,
+        risk_free_rate,
+        dividend_yield,
+        maturity,
+    );
+    strike * cash - asset
+}
+
+/// Returns the price of a call that is knocked in if the stock touches
+/// either barrier: the vanilla call less the knock-out call.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::double_barrier::{knock_in_call, DoubleBarrier};
+/// let barrier = DoubleBarrier::new(80.0, 130.0).unwrap();
+/// let price = knock_in_call(&barrier, 100.0, 100.0, 0.2, 0.05, 0.0, 0.5);
+/// assert!(price > 0.0);
+/// ```
+pub fn knock_in_call(
+    barrier: &DoubleBarrier,
+    stock: f64,
+    strike: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    bsm_compute_all(
+        stock,
+        strike,
+        sigma,
+        risk_free_rate,
+        dividend_yield,
+        maturity,
+    )
+    .call_price
+        - knock_out_call(
+            barrier,
+            stock,
+            strike,
+            sigma,
+            risk_free_rate,
+            dividend_yield,
+            maturity,
+        )
+}
+
+/// Returns the price of a put that is knocked in if the stock touches
+/// either barrier: the vanilla put less the knock-out put.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::double_barrier::{knock_in_put, DoubleBarrier};
+/// let barrier = DoubleBarrier::new(80.0, 130.0).unwrap();
+/// let price = knock_in_put(&barrier, 100.0, 100.0, 0.2, 0.05, 0.0, 0.5);
+/// assert!(price > 0.0);
+/// ```
+pub fn knock_in_put(
+    barrier: &DoubleBarrier,
+    stock: f64,
+    strike: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    bsm_compute_all(
+        stock,
+        strike,
+        sigma,
+        risk_free_rate,
+        dividend_yield,
+        maturity,
+    )
+    .put_price
+        - knock_out_put(
+            barrier,
+            stock,
+            strike,
+            sigma,
+            risk_free_rate,
+            dividend_yield,
+            maturity,
+        )
+}
+
+/// Returns the price of a binary paying `cash` at maturity if the stock
+/// never touches either barrier.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::double_barrier::{no_touch, DoubleBarrier};
+/// let barrier = DoubleBarrier::new(1.05, 1.15).unwrap();
+/// let spot = 1.10;
+/// let cash = 1.0;
+/// let sigma = 0.08;
+/// let domestic_rate = 0.03;
+/// let foreign_rate = 0.01;
+/// let maturity = 0.25;
+/// let price = no_touch(&barrier, spot, cash, sigma, domestic_rate, foreign_rate, maturity);
+/// assert!(price > 0.0 && price < cash);
+/// ```
+pub fn no_touch(
+    barrier: &DoubleBarrier,
+    stock: f64,
+    cash: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    if barrier.is_breached(stock) {
+        return 0.0;
+    }
+    let (lower, upper) = barrier.at_maturity(maturity);
+    let (_, no_touch) = barrier.asset_and_cash(
+        stock,
+        lower,
+        upper,
+        sigma,
+        risk_free_rate,
+        dividend_yield,
+        maturity,
+    );
+    cash * no_touch
+}
+
+/// Returns the price of a binary paying `cash` at maturity if the stock
+/// touches either barrier.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::double_barrier::{no_touch, one_touch, DoubleBarrier};
+/// let barrier = DoubleBarrier::new(1.05, 1.15).unwrap();
+/// let one_touch = one_touch(&barrier, 1.10, 1.0, 0.08, 0.03, 0.01, 0.25);
+/// let no_touch = no_touch(&barrier, 1.10, 1.0, 0.08, 0.03, 0.01, 0.25);
+/// assert!((one_touch + no_touch - (-0.03f64 * 0.25).exp()).abs() < 1e-12);
+/// ```
+pub fn one_touch(
+    barrier: &DoubleBarrier,
+    stock: f64,
+    cash: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    cash * (-risk_free_rate * maturity).exp()
+        - no_touch(
+            barrier,
+            stock,
+            cash,
+            sigma,
+            risk_free_rate,
+            dividend_yield,
+            maturity,
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::barrier::{self, BarrierKind};
+    use crate::monte_carlo::MonteCarlo;
+    use crate::pde::{Barrier, PdeSolver, uniform_grid};
+    use approx::*;
+
+    fn corridor_solver(lower: f64, upper: f64) -> PdeSolver {
+        PdeSolver::new(uniform_grid(lower, upper, 801).unwrap(), 400)
+            .unwrap()
+            .with_lower_barrier(Barrier {
+                level: lower,
+                rebate: 0.0,
+            })
+            .with_upper_barrier(Barrier {
+                level: upper,
+                rebate: 0.0,
+            })
+    }
+
+    #[test]
+    fn flat_barriers_match_pde() {
+        let (s, sigma, rate, q, maturity) = (100.0, 0.25, 0.05, 0.02, 0.5);
+        let (lower, upper) = (80.0, 125.0);
+        let barrier = DoubleBarrier::new(lower, upper).unwrap();
+        let solver = corridor_solver(lower, upper);
+        for strike in [75.0, 95.0, 110.0, 130.0] {
+            let call = solver
+                .price(
+                    |x| (x - strike).max(0.0),
+                    s,
+                    |_| rate,
+                    q,
+                    |_| sigma,
+                    maturity,
+                )
+                .price;
+            let put = solver
+                .price(
+                    |x| (strike - x).max(0.0),
+                    s,
+                    |_| rate,
+                    q,
+                    |_| sigma,
+                    maturity,
+                )
+                .price;
+            assert_abs_diff_eq!(
+                knock_out_call(&barrier, s, strike, sigma, rate, q, maturity),
+                call,
+                epsilon = 2e-3
+            );
+            assert_abs_diff_eq!(
+                knock_out_put(&barrier, s, strike, sigma, rate, q, maturity),
+                put,
+                epsilon = 2e-3
+            );
+        }
+        let no_touch_pde = solver
+            .price(|_| 1.0, s, |_| rate, q, |_| sigma, maturity)
+            .price;
+        assert_abs_diff_eq!(
+            no_touch(&barrier, s, 1.0, sigma, rate, q, maturity),
+            no_touch_pde,
+            epsilon = 1e-4
+        );
+    }
+    #[test]
+    fn curved_barriers_match_simulation() {
+        let (s, strike, sigma, rate, q, maturity) = (100.0, 100.0, 0.2, 0.05, 0.0, 1.0);
+        let (lower, upper, lower_curvature, upper_curvature) = (85.0, 120.0, 0.1, -0.05);
+        let steps = 500;
+        let dt = maturity / steps as f64;
+        let simulated = MonteCarlo::new(20_000, steps, 10).price(
+            |path| {
+                let touched = path.iter().enumerate().any(|(i, &x)| {
+                    let t = (i + 1) as f64 * dt;
+                    x <= lower * (lower_curvature * t).exp()
+                        || x >= upper * (upper_curvature * t).exp()
+                });
+                if touched {
+                    0.0
+                } else {
+                    (path[steps - 1] - strike).max(0.0)
+                }
+            },
+            s,
+            sigma,
+            rate,
+            q,
+            maturity,
+        );
+        // shift the barriers for discrete monitoring
+        let lower = barrier::discrete_monitoring_barrier(BarrierKind::DownAndOut, lower, sigma, dt);
+        let upper = barrier::discrete_monitoring_barrier(BarrierKind::UpAndOut, upper, sigma, dt);
+        let curved = DoubleBarrier::new(lower, upper)
+            .unwrap()
+            .with_curvature(lower_curvature, upper_curvature);
+        let price = knock_out_call(&curved, s, strike, sigma, rate, q, maturity);
+        assert!((price - simulated.price).abs() < 4.0 * simulated.standard_error);
+        let flat = knock_out_call(
+            &DoubleBarrier::new(lower, upper).unwrap(),
+            s,
+            strike,
+            sigma,
+            rate,
+            q,
+            maturity,
+        );
+        assert!((flat - simulated.price).abs() > 4.0 * simulated.standard_error);
+    }
+    #[test]
+    fn distant_barrier_is_single_barrier() {
+        let (s, strike, sigma, rate, q, maturity) = (100.0, 100.0, 0.3, 0.05, 0.02, 0.5);
+        let barrier = DoubleBarrier::new(85.0, 1e4).unwrap();
+        assert_abs_diff_eq!(
+            knock_out_call(&barrier, s, strike, sigma, rate, q, maturity),
+            barrier::call(
+                BarrierKind::DownAndOut,
+                s,
+                strike,
+                85.0,
+                0.0,
+                sigma,
+                rate,
+                q,
+                maturity
+            ),
+            epsilon = 1e-10
+        );
+        assert_abs_diff_eq!(
+            knock_in_put(&barrier, s, strike, sigma, rate, q, maturity),
+            barrier::put(
+                BarrierKind::DownAndIn,
+                s,
+                strike,
+                85.0,
+                0.0,
+                sigma,
+                rate,
+                q,
+                maturity
+            ),
+            epsilon = 1e-10
+        );
+    }
+    #[test]
+    fn touched_barrier_knocks_in_and_out() {
+        let barrier = DoubleBarrier::new(90.0, 110.0).unwrap();
+        let vanilla = bsm_compute_all(110.0, 100.0, 0.2, 0.05, 0.0, 1.0);
+        assert_eq!(
+            knock_out_call(&barrier, 110.0, 100.0, 0.2, 0.05, 0.0, 1.0),
+            0.0
+        );
+        assert_abs_diff_eq!(
+            knock_in_call(&barrier, 110.0, 100.0, 0.2, 0.05, 0.0, 1.0),
+            vanilla.call_price
+        );
+        assert_eq!(no_touch(&barrier, 90.0, 1.0, 0.2, 0.05, 0.0, 1.0), 0.0);
+        assert_abs_diff_eq!(
+            one_touch(&barrier, 90.0, 1.0, 0.2, 0.05, 0.0, 1.0),
+            (-0.05f64).exp()
+        );
+    }
+    #[test]
+    fn tolerance_controls_truncation() {
+        // a narrow corridor needs many terms
+        let (s, sigma, rate, q, maturity) = (1.0, 0.3, 0.02, 0.01, 2.0);
+        let exact = no_touch(
+            &DoubleBarrier::new(0.9, 1.1).unwrap(),
+            s,
+            1.0,
+            sigma,
+            rate,
+            q,
+            maturity,
+        );
+        let rough = no_touch(
+            &DoubleBarrier::new(0.9, 1.1).unwrap().with_tolerance(1e-3),
+            s,
+            1.0,
+            sigma,
+            rate,
+            q,
+            maturity,
+        );
+        assert!(exact > 0.0);
+        assert_abs_diff_eq!(rough, exact, epsilon = 1e-3);
+    }
+    #[test]
+    fn zero_volatility_follows_the_forward() {
+        let barrier = DoubleBarrier::new(80.0, 120.0).unwrap();
+        let (s, strike, rate, q, maturity): (f64, f64, f64, f64, f64) =
+            (100.0, 95.0, 0.05, 0.0, 1.0);
+        let forward_value = s * (-q * maturity).exp();
+        let discount = (-rate * maturity).exp();
+        assert_abs_diff_eq!(
+            knock_out_call(&barrier, s, strike, 0.0, rate, q, maturity),
+            forward_value - strike * discount,
+            epsilon = 1e-12
+        );
+        for sigma in [0.0, 1e-6] {
+            assert_abs_diff_eq!(
+                knock_out_call(&barrier, s, strike, sigma, rate, q, maturity),
+                knock_out_call(&barrier, s, strike, 1e-6, rate, q, maturity),
+                epsilon = 1e-6
+            );
+            // the forward rises through the upper barrier
+            assert_eq!(
+                knock_out_call(&barrier, s, strike, sigma, 0.25, q, maturity),
+                0.0
+            );
+            assert_abs_diff_eq!(
+                one_touch(&barrier, s, 1.0, sigma, 0.25, q, maturity),
+                (-0.25f64).exp(),
+                epsilon = 1e-12
+            );
+        }
+        // at expiry the options are worth their intrinsic value
+        assert_eq!(knock_out_call(&barrier, s, s, 0.2, rate, q, 0.0), 0.0);
+        assert_eq!(knock_out_put(&barrier, s, 110.0, 0.2, rate, q, 0.0), 10.0);
+        assert_eq!(no_touch(&barrier, s, 1.0, 0.2, rate, q, 0.0), 1.0);
+    }
+}
@@ -0,0 +1,414 @@
+//! Garman-Kohlhagen model for FX options.
+//!
+//! The spot `S` is the price of one unit of foreign currency in domestic
+//! currency.  The foreign interest rate plays the part of a dividend yield,
+//! so prices match `bsm_compute_all`, but the greeks are reported in FX
+//! terms with a rho for each currency.
+use crate::{cum_norm, inc_norm, max_or_zero};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct FxPricesAndGreeks {
+    pub call_price: f64,
+    pub call_delta: f64,
+    pub call_gamma: f64,
+    pub call_theta: f64,
+    pub call_vega: f64,
+    pub call_rho_domestic: f64,
+    pub call_rho_foreign: f64,
+    pub call_vanna: f64,
+    pub call_vomma: f64,
+    pub call_charm: f64,
+    pub put_price: f64,
+    pub put_delta: f64,
+    pub put_gamma: f64,
+    pub put_theta: f64,
+    pub put_vega: f64,
+    pub put_rho_domestic: f64,
+    pub put_rho_foreign: f64,
+    pub put_vanna: f64,
+    pub put_vomma: f64,
+    pub put_charm: f64,
+}
+
+/// Returns call and put prices and greeks with the premium paid in domestic
+/// currency, per unit of foreign notional.  Delta is the amount of foreign
+/// currency to hold as a hedge.
+///
+/// - `spot` (aka `S`): domestic currency per unit of foreign currency
+/// - `strike` (aka `K`): strike in the same units as `spot`
+/// - `sigma` (aka `σ`): volatility of the exchange rate (% p.a.)
+/// - `domestic_rate` (aka `r_d`): continuously compounded domestic interest rate (% p.a.)
+/// - `foreign_rate` (aka `r_f`): continuously compounded foreign interest rate (% p.a.)
+/// - `maturity` (aka `T`): time to maturity (% of years)
+///
+/// # Examples
+///
+/// ```
+/// let spot = 1.0850;
+/// let strike = 1.10;
+/// let sigma = 0.08;
+/// let domestic_rate = 0.05;
+/// let foreign_rate = 0.03;
+/// let maturity = 0.5;
+/// let all_prices_and_greeks = black_scholes::garman_kohlhagen::compute_all(
+///     spot,
+///     strike,
+///     sigma,
+///     domestic_rate,
+///     foreign_rate,
+///     maturity,
+/// );
+/// ```
+pub fn compute_all(
+    spot: f64,
+    strike: f64,
+    sigma: f64,
+    domestic_rate: f64,
+    foreign_rate: f64,
+    maturity: f64,
+) -> FxPricesAndGreeks {
+    let foreign_discount = (-foreign_rate * maturity).exp();
+    let domestic_discount = (-domestic_rate * maturity).exp();
+    let sqrt_maturity = maturity.sqrt();
+    let sqrt_maturity_sigma = sqrt_maturity * sigma;
+    let s_discount = spot * foreign_discount;
+    let k_discount = strike * domestic_discount;
+    if sqrt_maturity_sigma > 0.0 {
+        let d1 = (s_discount / k_discount).ln() / sqrt_maturity_sigma + 0.5 * sqrt_maturity_sigma;
+        let d2 = d1 - sqrt_maturity_sigma;
+        let cdf_d1 = cum_norm(d1);
+        let cdf_d2 = cum_norm(d2);
+        let pdf_d1 = inc_norm(d1);
+
+        let call_price = s_discount * cdf_d1 - k_discount * cdf_d2;
+        let call_delta = foreign_discount * cdf_d1;
+        let call_gamma = foreign_discount * pdf_d1 / (spot * sqrt_maturity_sigma);
+        let time_decay = -s_discount * pdf_d1 * sigma / (2.0 * sqrt_maturity);
+        let call_theta =
+            time_decay - domestic_rate * k_discount * cdf_d2 + foreign_rate * s_discount * cdf_d1;
+        let call_vega = s_discount * pdf_d1 * sqrt_maturity;
+        let call_rho_domestic = k_discount * maturity * cdf_d2;
+        let call_rho_foreign = -s_discount * maturity * cdf_d1;
+        let call_vanna = -foreign_discount * pdf_d1 * d2 / sigma;
+        let call_vomma = call_vega * d1 * d2 / sigma;
+        let charm_part = foreign_discount
+            * pdf_d1
+            * (2.0 * (domestic_rate - foreign_rate) * maturity - d2 * sqrt_maturity_sigma)
+            / (2.0 * maturity * sqrt_maturity_sigma);
+        let call_charm = foreign_rate * foreign_discount * cdf_d1 - charm_part;
+
+        let put_price = call_price + k_discount - s_discount;
+        let put_delta = foreign_discount * (cdf_d1 - 1.0);
+        let put_theta = time_decay + domestic_rate * k_discount * (1.0 - cdf_d2)
+            - foreign_rate * s_discount * (1.0 - cdf_d1);
+        let put_rho_domestic = -k_discount * maturity * (1.0 - cdf_d2);
+        let put_rho_foreign = s_discount * maturity * (1.0 - cdf_d1);
+        let put_charm = -foreign_rate * foreign_discount * (1.0 - cdf_d1) - charm_part;
+        FxPricesAndGreeks {
+            call_price,
+            call_delta,
+            call_gamma,
+            call_theta,
+            call_vega,
+            call_rho_domestic,
+            call_rho_foreign,
+            call_vanna,
+            call_vomma,
+            call_charm,
+            put_price,
+            put_delta,
+            put_gamma: call_gamma,
+            put_theta,
+            put_vega: call_vega,
+            put_rho_domestic,
+            put_rho_foreign,
+            put_vanna: call_vanna,
+            put_vomma: call_vomma,
+            put_charm,
+        }
+    } else {
+        FxPricesAndGreeks {
+            call_price: max_or_zero(s_discount - k_discount),
+            call_delta: if s_discount > k_discount {
+                foreign_discount
+            } else {
+                0.0
+            },
+            call_gamma: 0.0,
+            call_theta: 0.0,
+            call_vega: 0.0,
+            call_rho_domestic: 0.0,
+            call_rho_foreign: 0.0,
+            call_vanna: 0.0,
+            call_vomma: 0.0,
+            call_charm: 0.0,
+            put_price: max_or_zero(k_discount - s_discount),
+            put_delta: if k_discount > s_discount {
+                -foreign_discount
+            } else {
+                0.0
+            },
+            put_gamma: 0.0,
+            put_theta: 0.0,
+            put_vega: 0.0,
+            put_rho_domestic: 0.0,
+            put_rho_foreign: 0.0,
+            put_vanna: 0.0,
+            put_vomma: 0.0,
+            put_charm: 0.0,
+        }
+    }
+}
+
+/// Returns call and put prices and greeks with the premium paid in foreign
+/// currency, per unit of foreign notional, i.e. `V/S` where `V` is the
+/// domestic premium from [`compute_all`].
+///
+/// Delta is the premium-adjusted delta `Δ - V/S`: the amount of foreign
+/// currency to hold as a hedge once the foreign premium is accounted for.
+/// Gamma, vanna and charm are the sensitivities of that delta to spot,
+/// volatility and time; theta, vega, rhos and vomma are those of `V/S`.
+///
+/// # Examples
+///
+/// ```
+/// let spot = 1.0850;
+/// let strike = 1.10;
+/// let sigma = 0.08;
+/// let domestic_rate = 0.05;
+/// let foreign_rate = 0.03;
+/// let maturity = 0.5;
+/// let all_prices_and_greeks = black_scholes::garman_kohlhagen::compute_all_foreign_premium(
+///     spot,
+///     strike,
+///     sigma,
+///     domestic_rate,
+///     foreign_rate,
+///     maturity,
+/// );
+/// ```
+pub fn compute_all_foreign_premium(
+    spot: f64,
+    strike: f64,
+    sigma: f64,
+    domestic_rate: f64,
+    foreign_rate: f64,
+    maturity: f64,
+) -> FxPricesAndGreeks {
+    let domestic = compute_all(spot, strike, sigma, domestic_rate, foreign_rate, maturity);
+    let FxPricesAndGreeks {
+        call_price,
+        call_delta,
+        call_gamma,
+        call_theta,
+        call_vega,
+        call_rho_domestic,
+        call_rho_foreign,
+        call_vanna,
+        call_vomma,
+        call_charm,
+        put_price,
+        put_delta,
+        put_gamma,
+        put_theta,
+        put_vega,
+        put_rho_domestic,
+        put_rho_foreign,
+        put_vanna,
+        put_vomma,
+        put_charm,
+    } = domestic;
+    FxPricesAndGreeks {
+        call_price: call_price / spot,
+        call_delta: call_delta - call_price / spot,
+        call_gamma: call_gamma - call_delta / spot + call_price / (spot * spot),
+        call_theta: call_theta / spot,
+        call_vega: call_vega / spot,
+        call_rho_domestic: call_rho_domestic / spot,
+        call_rho_foreign: call_rho_foreign / spot,
+        call_vanna: call_vanna - call_vega / spot,
+        call_vomma: call_vomma / spot,
+        call_charm: call_charm - call_theta / spot,
+        put_price: put_price / spot,
+        put_delta: put_delta - put_price / spot,
+        put_gamma: put_gamma - put_delta / spot + put_price / (spot * spot),
+        put_theta: put_theta / spot,
+        put_vega: put_vega / spot,
+        put_rho_domestic: put_rho_domestic / spot,
+        put_rho_foreign: put_rho_foreign / spot,
+        put_vanna: put_vanna - put_vega / spot,
+        put_vomma: put_vomma / spot,
+        put_charm: put_charm - put_theta / spot,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PricesAndGreeks, bsm_compute_all};
+    use approx::*;
+
+    const SPOT: f64 = 1.085;
+    const STRIKE: f64 = 1.1;
+    const SIGMA: f64 = 0.08;
+    const DOMESTIC_RATE: f64 = 0.05;
+    const FOREIGN_RATE: f64 = 0.03;
+    const MATURITY: f64 = 0.5;
+    const H: f64 = 1e-5;
+
+    fn prices(
+        f: fn(f64, f64, f64, f64, f64, f64) -> FxPricesAndGreeks,
+        spot: f64,
+        sigma: f64,
+        domestic_rate: f64,
+        foreign_rate: f64,
+        maturity: f64,
+    ) -> [f64; 4] {
+        let r = f(spot, STRIKE, sigma, domestic_rate, foreign_rate, maturity);
+        [r.call_price, r.put_price, r.call_delta, r.put_delta]
+    }
+
+    // Central differences of [call price, put price, call delta, put delta]
+    // with respect to spot, sigma, domestic rate, foreign rate and maturity
+    fn finite_differences(
+        f: fn(f64, f64, f64, f64, f64, f64) -> FxPricesAndGreeks,
+    ) -> [[f64; 4]; 5] {
+        let bump = |up: [f64; 4], down: [f64; 4], h: f64| {
+            [0, 1, 2, 3].map(|i| (up[i] - down[i]) / (2.0 * h))
+        };
+        let h_spot = H * SPOT;
+        [
+            bump(
+                prices(
+                    f,
+                    SPOT + h_spot,
+                    SIGMA,
+                    DOMESTIC_RATE,
+                    FOREIGN_RATE,
+                    MATURITY,
+                ),
+                prices(
+                    f,
+                    SPOT - h_spot,
+                    SIGMA,
+                    DOMESTIC_RATE,
+                    FOREIGN_RATE,
+                    MATURITY,
+                ),
+                h_spot,
+            ),
+            bump(
+                prices(f, SPOT, SIGMA + H, DOMESTIC_RATE, FOREIGN_RATE, MATURITY),
+                prices(f, SPOT, SIGMA - H, DOMESTIC_RATE, FOREIGN_RATE, MATURITY),
+                H,
+            ),
+            bump(
+                prices(f, SPOT, SIGMA, DOMESTIC_RATE + H, FOREIGN_RATE, MATURITY),
+                prices(f, SPOT, SIGMA, DOMESTIC_RATE - H, FOREIGN_RATE, MATURITY),
+                H,
+            ),
+            bump(
+                prices(f, SPOT, SIGMA, DOMESTIC_RATE, FOREIGN_RATE + H, MATURITY),
+                prices(f, SPOT, SIGMA, DOMESTIC_RATE, FOREIGN_RATE - H, MATURITY),
+                H,
+            ),
+            // theta and charm are minus the derivative with respect to maturity
+            bump(
+                prices(f, SPOT, SIGMA, DOMESTIC_RATE, FOREIGN_RATE, MATURITY - H),
+                prices(f, SPOT, SIGMA, DOMESTIC_RATE, FOREIGN_RATE, MATURITY + H),
+                H,
+            ),
+        ]
+    }
+
+    fn assert_greeks_match_finite_differences(
+        f: fn(f64, f64, f64, f64, f64, f64) -> FxPricesAndGreeks,
+        delta_scale: f64,
+    ) {
+        let r = f(SPOT, STRIKE, SIGMA, DOMESTIC_RATE, FOREIGN_RATE, MATURITY);
+        let [spot, sigma, domestic_rate, foreign_rate, time] = finite_differences(f);
+        let expected = [
+            (r.call_delta, delta_scale * spot[0]),
+            (r.put_delta, delta_scale * spot[1]),
+            (r.call_gamma, spot[2]),
+            (r.put_gamma, spot[3]),
+            (r.call_vega, sigma[0]),
+            (r.put_vega, sigma[1]),
+            (r.call_vanna, sigma[2]),
+            (r.put_vanna, sigma[3]),
+            (r.call_rho_domestic, domestic_rate[0]),
+            (r.put_rho_domestic, domestic_rate[1]),
+            (r.call_rho_foreign, foreign_rate[0]),
+            (r.put_rho_foreign, foreign_rate[1]),
+            (r.call_theta, time[0]),
+            (r.put_theta, time[1]),
+            (r.call_charm, time[2]),
+            (r.put_charm, time[3]),
+        ];
+        for (greek, finite_difference) in expected {
+            assert_abs_diff_eq!(greek, finite_difference, epsilon = 1e-6);
+        }
+        let vega =
+            |sigma: f64| f(SPOT, STRIKE, sigma, DOMESTIC_RATE, FOREIGN_RATE, MATURITY).call_vega;
+        assert_abs_diff_eq!(
+            r.call_vomma,
+            (vega(SIGMA + H) - vega(SIGMA - H)) / (2.0 * H),
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    fn domestic_greeks_match_finite_differences() {
+        assert_greeks_match_finite_differences(compute_all, 1.0);
+    }
+    #[test]
+    fn foreign_premium_greeks_match_finite_differences() {
+        // premium-adjusted delta is S·∂(V/S)/∂S
+        assert_greeks_match_finite_differences(compute_all_foreign_premium, SPOT);
+    }
+    #[test]
+    fn prices_match_bsm_with_foreign_rate_as_dividend_yield() {
+        let fx = compute_all(SPOT, STRIKE, SIGMA, DOMESTIC_RATE, FOREIGN_RATE, MATURITY);
+        let PricesAndGreeks {
+            call_price,
+            call_delta,
+            call_gamma,
+            call_theta,
+            call_rho,
+            put_price,
+            put_delta,
+            put_theta,
+            put_rho,
+            call_charm,
+            put_charm,
+            ..
+        } = bsm_compute_all(SPOT, STRIKE, SIGMA, DOMESTIC_RATE, FOREIGN_RATE, MATURITY);
+        assert_abs_diff_eq!(fx.call_price, call_price, epsilon = 1e-12);
+        assert_abs_diff_eq!(fx.put_price, put_price, epsilon = 1e-12);
+        assert_abs_diff_eq!(fx.call_delta, call_delta, epsilon = 1e-12);
+        assert_abs_diff_eq!(fx.put_delta, put_delta, epsilon = 1e-12);
+        assert_abs_diff_eq!(fx.call_gamma, call_gamma, epsilon = 1e-12);
+        assert_abs_diff_eq!(fx.call_theta, call_theta, epsilon = 1e-12);
+        assert_abs_diff_eq!(fx.put_theta, put_theta, epsilon = 1e-12);
+        assert_abs_diff_eq!(fx.call_rho_domestic, call_rho, epsilon = 1e-12);
+        assert_abs_diff_eq!(fx.put_rho_domestic, put_rho, epsilon = 1e-12);
+        assert_abs_diff_eq!(fx.call_charm, call_charm, epsilon = 1e-12);
+        assert_abs_diff_eq!(fx.put_charm, put_charm, epsilon = 1e-12);
+    }
+    #[test]
+    fn foreign_premium_is_domestic_premium_over_spot() {
+        let domestic = compute_all(SPOT, STRIKE, SIGMA, DOMESTIC_RATE, FOREIGN_RATE, MATURITY);
+        let foreign =
+            compute_all_foreign_premium(SPOT, STRIKE, SIGMA, DOMESTIC_RATE, FOREIGN_RATE, MATURITY);
+        assert_abs_diff_eq!(foreign.call_price, domestic.call_price / SPOT);
+        assert_abs_diff_eq!(foreign.put_price, domestic.put_price / SPOT);
+    }
+    #[test]
+    fn zero_volatility_gives_discounted_forward_intrinsic() {
+        let r = compute_all(SPOT, 1.0, 0.0, DOMESTIC_RATE, FOREIGN_RATE, 1.0);
+        let forward_value = SPOT * (-FOREIGN_RATE).exp() - (-DOMESTIC_RATE).exp();
+        assert_abs_diff_eq!(r.call_price, forward_value, epsilon = 1e-15);
+        assert_abs_diff_eq!(r.put_price, 0.0);
+    }
+}
@@ -0,0 +1,445 @@
+//! Binomial and trinomial trees for European, American and Bermudan options.
+//!
+//! Discrete cash dividends use the escrowed dividend model: the tree is
+//! built on the stock price less the present value of the dividends paid
+//! before maturity, which is added back at each node.
+use crate::max_or_zero;
+use serde::Serialize;
+
+/// Tree parameterisation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum TreeKind {
+    /// Cox-Ross-Rubinstein: `u = e^{σ√Δt}`, `d = 1/u`.
+    CoxRossRubinstein,
+    /// Jarrow-Rudd: equal probabilities, drift in the moves.
+    JarrowRudd,
+    /// Tian: matches the first three moments of the stock price.
+    Tian,
+    /// Leisen-Reimer: Peizer-Pratt inversion centred on the strike, with
+    /// second order convergence.  The number of steps is made odd.
+    LeisenReimer,
+    /// Boyle's trinomial tree with `u = e^{σ√(2Δt)}`.
+    Trinomial,
+}
+
+/// When the option may be exercised.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum Exercise {
+    European,
+    American,
+    /// Exercise at maturity and at these times (in years), each rounded to
+    /// the nearest tree step.
+    Bermudan(Vec<f64>),
+}
+
+/// A discrete cash dividend.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct CashDividend {
+    /// Time of payment (in years)
+    pub time: f64,
+    pub amount: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TreePriceAndGreeks {
+    pub price: f64,
+    pub delta: f64,
+    pub gamma: f64,
+    pub theta: f64,
+}
+
+/// An N-step tree.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::lattice::{CashDividend, Exercise, Tree, TreeKind};
+/// let tree = Tree::new(TreeKind::LeisenReimer, 201)
+///     .with_exercise(Exercise::American)
+///     .with_dividends(vec![CashDividend { time: 0.25, amount: 1.5 }]);
+/// let stock = 100.0;
+/// let strike = 100.0;
+/// let sigma = 0.25;
+/// let rate = 0.05;
+/// let dividend_yield = 0.0;
+/// let maturity = 1.0;
+/// let put = tree.put(stock, strike, sigma, rate, dividend_yield, maturity);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Tree {
+    kind: TreeKind,
+    steps: usize,
+    exercise: Exercise,
+    dividends: Vec<CashDividend>,
+}
+
+// Peizer-Pratt method 2 inversion of the binomial distribution
+fn peizer_pratt(z: f64, steps: usize) -> f64 {
+    let n = steps as f64;
+    let ratio = z / (n + 1.0 / 3.0 + 0.1 / (n + 1.0));
+    0.5 + 0.5 * z.signum() * (1.0 - (-ratio * ratio * (n + 1.0 / 6.0)).exp()).sqrt()
+}
+
+impl Tree {
+    /// Returns a European tree with `steps` time steps (at least 2) and no
+    /// discrete dividends.
+    pub fn new(kind: TreeKind, steps: usize) -> Self {
+        let steps = steps.max(2);
+        Tree {
+            kind,
+            steps: if kind == TreeKind::LeisenReimer {
+                steps | 1
+            } else {
+                steps
+            },
+            exercise: Exercise::European,
+            dividends: Vec::new(),
+        }
+    }
+    pub fn with_exercise(self, exercise: Exercise) -> Self {
+        Tree { exercise, ..self }
+    }
+    pub fn with_dividends(self, dividends: Vec<CashDividend>) -> Self {
+        Tree { dividends, ..self }
+    }
+
+    /// Returns the call price with delta, gamma and theta from the first
+    /// two levels of the tree.  `dividend_yield` is a continuous yield,
+    /// paid in addition to any discrete dividends.
+    pub fn call(
+        &self,
+        stock: f64,
+        strike: f64,
+        sigma: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        maturity: f64,
+    ) -> TreePriceAndGreeks {
+        self.price(
+            1.0,
+            stock,
+            strike,
+            sigma,
+            risk_free_rate,
+            dividend_yield,
+            maturity,
+        )
+    }
+
+    /// Returns the put price with delta, gamma and theta from the first
+    /// two levels of the tree.  `dividend_yield` is a continuous yield,
+    /// paid in addition to any discrete dividends.
+    pub fn put(
+        &self,
+        stock: f64,
+        strike: f64,
+        sigma: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        maturity: f64,
+    ) -> TreePriceAndGreeks {
+        self.price(
+            -1.0,
+            stock,
+            strike,
+            sigma,
+            risk_free_rate,
+            dividend_yield,
+            maturity,
+        )
+    }
+
+    // whether early exercise is allowed at step i (before maturity)
+    fn exercisable(&self, step: usize, dt: f64) -> bool {
+        match &self.exercise {
+            Exercise::European => false,
+            Exercise::American => true,
+            Exercise::Bermudan(times) => times
+                .iter()
+                .any(|time| (time / dt).round() as usize == step),
+        }
+    }
+
+    // omega is 1 for calls and -1 for puts
+    #[allow(clippy::too_many_arguments)]
+    fn price(
+        &self,
+        omega: f64,
+        stock: f64,
+        strike: f64,
+        sigma: f64,
+        rate: f64,
+        dividend_yield: f64,
+        maturity: f64,
+    ) -> TreePriceAndGreeks {
+        let n = self.steps;
+        let dt = maturity / n as f64;
+        let carry = rate - dividend_yield;
+        let discount = (-rate * dt).exp();
+        let pv_dividends = |t: f64| -> f64 {
+            self.dividends
+                .iter()
+                .filter(|dividend| dividend.time > t && dividend.time <= maturity)
+                .map(|dividend| dividend.amount * (-rate * (dividend.time - t)).exp())
+                .sum()
+        };
+        let escrowed_stock = stock - pv_dividends(0.0);
+        let payoff = |s: f64| max_or_zero(omega * (s - strike));
+
+        // up factor, down factor and branch probabilities (up, middle, down);
+        // node j at step i is escrowed_stock·u^j·d^(i-j), or u^(j-i) for the
+        // trinomial tree
+        let growth = (carry * dt).exp();
+        let (u, d, probabilities) = match self.kind {
+            TreeKind::CoxRossRubinstein => {
+                let u = (sigma * dt.sqrt()).exp();
+                let d = 1.0 / u;
+                let p = (growth - d) / (u - d);
+                (u, d, [p, 0.0, 1.0 - p])
+            }
+            TreeKind::JarrowRudd => {
+                let drift = (carry - 0.5 * sigma * sigma) * dt;
+                let u = (drift + sigma * dt.sqrt()).exp();
+                let d = (drift - sigma * dt.sqrt()).exp();
+                (u, d, [0.5, 0.0, 0.5])
+            }
+            TreeKind::Tian => {
+                let v = (sigma * sigma * dt).exp();
+                let root = (v * v + 2.0 * v - 3.0).sqrt();
+                let u = 0.5 * growth * v * (v + 1.0 + root);
+                let d = 0.5 * growth * v * (v + 1.0 - root);
+                let p = (growth - d) / (u - d);
+                (u, d, [p, 0.0, 1.0 - p])
+            }
+            TreeKind::LeisenReimer => {
+                let sqrt_maturity_sigma = sigma * maturity.sqrt();
+                let d1 = ((escrowed_stock / strike).ln()
+                    + (carry + 0.5 * sigma * sigma) * maturity)
+                    / sqrt_maturity_sigma;
+                let d2 = d1 - sqrt_maturity_sigma;
+                let p_star = peizer_pratt(d1, n);
+                let p = peizer_pratt(d2, n);
+                let u = growth * p_star / p;
+                let d = (growth - p * u) / (1.0 - p);
+                (u, d, [p, 0.0, 1.0 - p])
+            }
+            TreeKind::Trinomial => {
+                let u = (sigma * (2.0 * dt).sqrt()).exp();
+                let half_up = (sigma * (0.5 * dt).sqrt()).exp();
+                let half_growth = (0.5 * carry * dt).exp();
+                let p_up = ((half_growth - 1.0 / half_up) / (half_up - 1.0 / half_up)).powi(2);
+                let p_down = ((half_up - half_growth) / (half_up - 1.0 / half_up)).powi(2);
+                (u, 1.0 / u, [p_up, 1.0 - p_up - p_down, p_down])
+            }
+        };
+        let trinomial = self.kind == TreeKind::Trinomial;
+        let width = |step: usize| if trinomial { 2 * step + 1 } else { step + 1 };
+        let node_stock = |step: usize, j: usize| {
+            let escrowed = if trinomial {
+                escrowed_stock * u.powi(j as i32 - step as i32)
+            } else {
+                escrowed_stock * u.powi(j as i32) * d.powi((step - j) as i32)
+            };
+            escrowed + pv_dividends(step as f64 * dt)
+        };
+
+        let mut values: Vec<f64> = (0..width(n)).map(|j| payoff(node_stock(n, j))).collect();
+        // option values at the first three levels, for the greeks
+        let mut levels: [Vec<f64>; 3] = Default::default();
+        for step in (0..n).rev() {
+            let exercisable = self.exercisable(step, dt);
+            values = (0..width(step))
+                .map(|j| {
+                    let continuation = if trinomial {
+                        probabilities[0] * values[j + 2]
+                            + probabilities[1] * values[j + 1]
+                            + probabilities[2] * values[j]
+                    } else {
+                        probabilities[0] * values[j + 1] + probabilities[2] * values[j]
+                    };
+                    let continuation = discount * continuation;
+                    if exercisable {
+                        continuation.max(payoff(node_stock(step, j)))
+                    } else {
+                        continuation
+                    }
+                })
+                .collect();
+            if step <= 2 {
+                levels[step] = values.clone();
+            }
+        }
+
+        // delta and gamma from the nodes either side of the stock price
+        // one level in for the trinomial tree and two levels in otherwise
+        let (gamma_step, value_at, stock_at) = if trinomial {
+            (1, &levels[1], [0, 1, 2].map(|j| node_stock(1, j)))
+        } else {
+            (2, &levels[2], [0, 1, 2].map(|j| node_stock(2, j)))
+        };
+        let delta_down = (value_at[1] - value_at[0]) / (stock_at[1] - stock_at[0]);
+        let delta_up = (value_at[2] - value_at[1]) / (stock_at[2] - stock_at[1]);
+        let gamma = (delta_up - delta_down) / (0.5 * (stock_at[2] - stock_at[0]));
+        let delta = if trinomial {
+            (value_at[2] - value_at[0]) / (stock_at[2] - stock_at[0])
+        } else {
+            (levels[1][1] - levels[1][0]) / (node_stock(1, 1) - node_stock(1, 0))
+        };
+        // the middle node is not exactly at the stock price unless u·d = 1,
+        // so correct for the move in the stock price before differencing in time
+        let price = levels[0][0];
+        let theta =
+            (value_at[1] - delta * (stock_at[1] - stock) - price) / (gamma_step as f64 * dt);
+        TreePriceAndGreeks {
+            price,
+            delta,
+            gamma,
+            theta,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bsm_compute_all, call, put};
+    use approx::*;
+
+    const KINDS: [TreeKind; 5] = [
+        TreeKind::CoxRossRubinstein,
+        TreeKind::JarrowRudd,
+        TreeKind::Tian,
+        TreeKind::LeisenReimer,
+        TreeKind::Trinomial,
+    ];
+
+    #[test]
+    fn european_trees_converge_to_black_scholes() {
+        let (s, k, sigma, rate, maturity) = (100.0, 95.0, 0.25, 0.05, 1.0);
+        let exact_call = call(s, k, rate, sigma, maturity);
+        let exact_put = put(s, k, rate, sigma, maturity);
+        for kind in KINDS {
+            let coarse = Tree::new(kind, 100);
+            let fine = Tree::new(kind, 1000);
+            let coarse_error =
+                (coarse.call(s, k, sigma, rate, 0.0, maturity).price - exact_call).abs();
+            let fine_error = (fine.call(s, k, sigma, rate, 0.0, maturity).price - exact_call).abs();
+            assert!(fine_error < 5e-3, "{:?} {}", kind, fine_error);
+            assert!(fine_error < coarse_error, "{:?}", kind);
+            assert_abs_diff_eq!(
+                fine.put(s, k, sigma, rate, 0.0, maturity).price,
+                exact_put,
+                epsilon = 5e-3
+            );
+        }
+        // second order convergence
+        let leisen_reimer = Tree::new(TreeKind::LeisenReimer, 201);
+        assert_abs_diff_eq!(
+            leisen_reimer.call(s, k, sigma, rate, 0.0, maturity).price,
+            exact_call,
+            epsilon = 1e-4
+        );
+    }
+    #[test]
+    fn european_tree_greeks_match_black_scholes() {
+        let (s, k, sigma, rate, q, maturity) = (100.0, 105.0, 0.3, 0.04, 0.02, 0.5);
+        let exact = bsm_compute_all(s, k, sigma, rate, q, maturity);
+        for kind in [
+            TreeKind::LeisenReimer,
+            TreeKind::Trinomial,
+            TreeKind::JarrowRudd,
+        ] {
+            let tree = Tree::new(kind, 2001);
+            let call = tree.call(s, k, sigma, rate, q, maturity);
+            let put = tree.put(s, k, sigma, rate, q, maturity);
+            assert_abs_diff_eq!(call.price, exact.call_price, epsilon = 1e-2);
+            assert_abs_diff_eq!(call.delta, exact.call_delta, epsilon = 1e-3);
+            assert_abs_diff_eq!(call.gamma, exact.call_gamma, epsilon = 1e-4);
+            assert_abs_diff_eq!(call.theta, exact.call_theta, epsilon = 2e-2);
+            assert_abs_diff_eq!(put.delta, exact.put_delta, epsilon = 1e-3);
+            assert_abs_diff_eq!(put.theta, exact.put_theta, epsilon = 2e-2);
+        }
+    }
+    #[test]
+    fn american_put_matches_reference_tree() {
+        // 2000 step CRR tree computed independently
+        let tree = Tree::new(TreeKind::CoxRossRubinstein, 2000).with_exercise(Exercise::American);
+        assert_abs_diff_eq!(
+            tree.put(100.0, 100.0, 0.2, 0.08, 0.0, 0.25).price,
+            3.224691975178157,
+            epsilon = 1e-10
+        );
+    }
+    #[test]
+    fn bermudan_lies_between_european_and_american() {
+        let (s, k, sigma, rate, maturity) = (100.0, 110.0, 0.3, 0.06, 1.0);
+        let european = Tree::new(TreeKind::LeisenReimer, 301);
+        let american = european.clone().with_exercise(Exercise::American);
+        let bermudan = european
+            .clone()
+            .with_exercise(Exercise::Bermudan(vec![0.25, 0.5, 0.75]));
+        let european = european.put(s, k, sigma, rate, 0.0, maturity).price;
+        let american = american.put(s, k, sigma, rate, 0.0, maturity).price;
+        let bermudan = bermudan.put(s, k, sigma, rate, 0.0, maturity).price;
+        assert!(european < bermudan && bermudan < american);
+        // exercising at every step is American
+        let steps = 100;
+        let every_step = (0..steps).map(|i| i as f64 / steps as f64).collect();
+        let tree = Tree::new(TreeKind::CoxRossRubinstein, steps);
+        assert_abs_diff_eq!(
+            tree.clone()
+                .with_exercise(Exercise::Bermudan(every_step))
+                .put(s, k, sigma, rate, 0.0, maturity)
+                .price,
+            tree.with_exercise(Exercise::American)
+                .put(s, k, sigma, rate, 0.0, maturity)
+                .price,
+            epsilon = 1e-12
+        );
+    }
+    #[test]
+    fn discrete_dividends_reduce_the_stock_price() {
+        let (s, k, sigma, rate, maturity) = (100.0, 100.0, 0.25, 0.05, 1.0);
+        let dividends = vec![
+            CashDividend {
+                time: 0.25,
+                amount: 2.0,
+            },
+            CashDividend {
+                time: 0.75,
+                amount: 2.0,
+            },
+        ];
+        let pv: f64 = dividends
+            .iter()
+            .map(|dividend| dividend.amount * (-rate * dividend.time).exp())
+            .sum();
+        let tree = Tree::new(TreeKind::LeisenReimer, 201).with_dividends(dividends);
+        assert_abs_diff_eq!(
+            tree.call(s, k, sigma, rate, 0.0, maturity).price,
+            call(s - pv, k, rate, sigma, maturity),
+            epsilon = 1e-4
+        );
+        // early exercise just before a dividend has value
+        let european = tree.call(s, k, sigma, rate, 0.0, maturity).price;
+        let american = tree
+            .with_exercise(Exercise::American)
+            .call(s, k, sigma, rate, 0.0, maturity)
+            .price;
+        assert!(american > european);
+    }
+    #[test]
+    fn american_call_without_dividends_is_european() {
+        for kind in KINDS {
+            let tree = Tree::new(kind, 200);
+            assert_abs_diff_eq!(
+                tree.clone()
+                    .with_exercise(Exercise::American)
+                    .call(100.0, 90.0, 0.2, 0.05, 0.0, 1.0)
+                    .price,
+                tree.call(100.0, 90.0, 0.2, 0.05, 0.0, 1.0).price,
+                epsilon = 1e-12
+            );
+        }
+    }
+}
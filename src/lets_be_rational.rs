@@ -0,0 +1,845 @@
+//! Implied Black volatility following Peter Jäckel, "Let's Be Rational" (2015).
+//!
+//! Prices are normalised to `b(x, s) = Φ(x/s + s/2)·e^{x/2} - Φ(x/s - s/2)·e^{-x/2}`
+//! with `x = ln(F/K)` and `s = σ√T`.  A rational cubic initial guess on one of
+//! four branches is refined by Householder(3) steps on a branch-specific
+//! objective function, which reaches machine precision in two iterations.
+use crate::stats::cum_norm;
+use crate::{FRAC_1_SQRT_2PI, IvError};
+use special::Error;
+use std::f64::consts::{FRAC_1_SQRT_2, FRAC_PI_6, PI};
+
+#[allow(clippy::excessive_precision)]
+const SQRT_PI_OVER_TWO: f64 = 1.253314137315500251207882642405522626503493370304969158314961;
+#[allow(clippy::excessive_precision)]
+const SQRT_THREE: f64 = 1.732050807568877293527446341505872366942805253810380628055806;
+#[allow(clippy::excessive_precision)]
+const SQRT_ONE_OVER_THREE: f64 = 0.577350269189625764509148780501957455647601751270126876018602;
+#[allow(clippy::excessive_precision)]
+const TWO_PI_OVER_SQRT_TWENTY_SEVEN: f64 =
+    1.209199576156145233729385505094770488189377498728473263848;
+
+/// ε^(1/4)
+const FOURTH_ROOT_DBL_EPSILON: f64 = 0.0001220703125;
+/// ε^(1/16)
+#[allow(clippy::excessive_precision)]
+const SIXTEENTH_ROOT_DBL_EPSILON: f64 = 0.10511205190671431;
+/// √(f64::MIN_POSITIVE)
+const SQRT_DBL_MIN: f64 = 1.4916681462400413e-154;
+/// √(f64::MAX)
+const SQRT_DBL_MAX: f64 = 1.3407807929942596e154;
+
+const ASYMPTOTIC_EXPANSION_ACCURACY_THRESHOLD: f64 = -10.0;
+const SMALL_T_EXPANSION_OF_NORMALISED_BLACK_THRESHOLD: f64 = 2.0 * SIXTEENTH_ROOT_DBL_EPSILON;
+
+const MINIMUM_RATIONAL_CUBIC_CONTROL_PARAMETER_VALUE: f64 = -(1.0 - 1.4901161193847656e-8);
+const MAXIMUM_RATIONAL_CUBIC_CONTROL_PARAMETER_VALUE: f64 = 2.0 / (f64::EPSILON * f64::EPSILON);
+
+/// Number of Householder iterations after the rational initial guess
+const MAXIMUM_ITERATIONS: usize = 2;
+
+#[inline(always)]
+fn is_below_horizon(x: f64) -> bool {
+    x.abs() < f64::MIN_POSITIVE
+}
+
+fn norm_pdf(z: f64) -> f64 {
+    FRAC_1_SQRT_2PI * (-0.5 * z * z).exp()
+}
+
+// Scaled complementary error function exp(x²)·erfc(x).
+// x² is split so that the exponential does not amplify its rounding error.
+pub(crate) fn erfcx(x: f64) -> f64 {
+    if x < 26.0 {
+        let x_hi = (x * 16.0).trunc() / 16.0;
+        let delta = (x - x_hi) * (x + x_hi);
+        x.compl_error() * (x_hi * x_hi).exp() * delta.exp()
+    } else {
+        // asymptotic series, erfc underflows beyond this point
+        let w = 0.5 / (x * x);
+        let series = 1.0
+            - w * (1.0
+                - 3.0 * w * (1.0 - 5.0 * w * (1.0 - 7.0 * w * (1.0 - 9.0 * w * (1.0 - 11.0 * w)))));
+        series / (x * PI.sqrt())
+    }
+}
+
+// Wichura (1988), algorithm AS241 (PPND16)
+#[allow(clippy::excessive_precision)]
+pub(crate) fn inverse_norm_cdf(u: f64) -> f64 {
+    if u <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if u >= 1.0 {
+        return f64::INFINITY;
+    }
+    let q = u - 0.5;
+    if q.abs() <= 0.425 {
+        let r = 0.180625 - q * q;
+        return q
+            * (((((((2.5090809287301226727e3 * r + 3.3430575583588128105e4) * r
+                + 6.7265770927008700853e4)
+                * r
+                + 4.5921953931549871457e4)
+                * r
+                + 1.3731693765509461125e4)
+                * r
+                + 1.9715909503065514427e3)
+                * r
+                + 1.3314166789178437745e2)
+                * r
+                + 3.3871328727963666080e0)
+            / (((((((5.2264952788528545610e3 * r + 2.8729085735721942674e4) * r
+                + 3.9307895800092710610e4)
+                * r
+                + 2.1213794301586595867e4)
+                * r
+                + 5.3941960214247511077e3)
+                * r
+                + 6.8718700749205790830e2)
+                * r
+                + 4.2313330701600911252e1)
+                * r
+                + 1.0);
+    }
+    let r = if q < 0.0 { u } else { 1.0 - u };
+    let r = (-r.ln()).sqrt();
+    let value = if r <= 5.0 {
+        let r = r - 1.6;
+        (((((((7.74545014278341407640e-4 * r + 2.27238449892691845833e-2) * r
+            + 2.41780725177450611770e-1)
+            * r
+            + 1.27045825245236838258e0)
+            * r
+            + 3.64784832476320460504e0)
+            * r
+            + 5.76949722146069140550e0)
+            * r
+            + 4.63033784615654529590e0)
+            * r
+            + 1.42343711074968357734e0)
+            / (((((((1.05075007164441684324e-9 * r + 5.47593808499534494600e-4) * r
+                + 1.51986665636164571966e-2)
+                * r
+                + 1.48103976427480074590e-1)
+                * r
+                + 6.89767334985100004550e-1)
+                * r
+                + 1.67638483018380384940e0)
+                * r
+                + 2.05319162663775882187e0)
+                * r
+                + 1.0)
+    } else {
+        let r = r - 5.0;
+        (((((((2.01033439929228813265e-7 * r + 2.71155556874348757815e-5) * r
+            + 1.24266094738807843860e-3)
+            * r
+            + 2.65321895265761230930e-2)
+            * r
+            + 2.96560571828504891230e-1)
+            * r
+            + 1.78482653991729133580e0)
+            * r
+            + 5.46378491116411436990e0)
+            * r
+            + 6.65790464350110377720e0)
+            / (((((((2.04426310338993978564e-15 * r + 1.42151175831644588870e-7) * r
+                + 1.84631831751005468180e-5)
+                * r
+                + 7.86869131145613259100e-4)
+                * r
+                + 1.48753612908506148525e-2)
+                * r
+                + 1.36929880922735805310e-1)
+                * r
+                + 5.99832206555887937690e-1)
+                * r
+                + 1.0)
+    };
+    if q < 0.0 { -value } else { value }
+}
+
+fn householder_factor(newton: f64, halley: f64, hh3: f64) -> f64 {
+    (1.0 + 0.5 * halley * newton) / (1.0 + newton * (halley + hh3 * newton / 6.0))
+}
+
+// Normalised intrinsic value `θ·(e^{x/2} - e^{-x/2})`, floored at zero
+fn normalised_intrinsic(x: f64, theta: f64) -> f64 {
+    if theta * x <= 0.0 {
+        return 0.0;
+    }
+    let x2 = x * x;
+    if x2 < 98.0 * FOURTH_ROOT_DBL_EPSILON {
+        // Taylor series of 2·sinh(x/2) to avoid cancellation
+        return (theta
+            * x
+            * (1.0
+                + x2 * (1.0 / 24.0
+                    + x2 * (1.0 / 1920.0 + x2 * (1.0 / 322560.0 + x2 / 92897280.0)))))
+            .max(0.0);
+    }
+    let b_max = (0.5 * x).exp();
+    (theta * (b_max - 1.0 / b_max)).max(0.0)
+}
+
+// Region I: h < -10 and t small relative to |h|.
+// Uses Φ(z)/φ(z) ~ -Σ_k (-1)^k (2k-1)!! z^{-(2k+1)} for both terms of the
+// normalised Black formula, with their difference expanded in e = (t/h)²
+// and q = (h/r)² with r = h² - t² so no cancellation occurs.
+fn asymptotic_expansion_of_normalised_black_call(h: f64, t: f64) -> f64 {
+    const ORDER: usize = 17;
+    let e = (t / h).powi(2);
+    let r = (h + t) * (h - t);
+    let q = (h / r).powi(2);
+    let mut coefficients = [0.0; ORDER + 1];
+    let mut double_factorial = 1.0;
+    for (k, coefficient) in coefficients.iter_mut().enumerate() {
+        let n = 2 * k + 1;
+        if k > 0 {
+            double_factorial *= (2 * k - 1) as f64;
+        }
+        // Σ_m C(n, 2m + 1) e^m, evaluated by Horner from the top
+        let mut binomials = [0.0; ORDER + 1];
+        let mut binomial = 1.0;
+        for j in 1..=n {
+            binomial *= (n + 1 - j) as f64 / j as f64;
+            if j % 2 == 1 {
+                binomials[(j - 1) / 2] = binomial;
+            }
+        }
+        let polynomial = binomials[..=k]
+            .iter()
+            .rev()
+            .fold(0.0, |acc, &c| acc * e + c);
+        let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+        *coefficient = 2.0 * sign * double_factorial * polynomial;
+    }
+    let sum = coefficients.iter().rev().fold(0.0, |acc, &c| acc * q + c);
+    let b = FRAC_1_SQRT_2PI * (-0.5 * (h * h + t * t)).exp() * (t / r) * sum;
+    b.max(0.0).abs()
+}
+
+// Region II: t = s/2 small.
+// With Y(h) = Φ(h)/φ(h) we have Y' = 1 + h·Y and Y^(n+1) = h·Y^(n) + n·Y^(n-1),
+// and Y(h+t) - Y(h-t) = 2 Σ_{j odd} Y^(j)(h)·t^j/j!.
+fn small_t_expansion_of_normalised_black_call(h: f64, t: f64) -> f64 {
+    const ORDER: usize = 13;
+    let mut derivatives = [0.0; ORDER + 1];
+    derivatives[0] = SQRT_PI_OVER_TWO * erfcx(-FRAC_1_SQRT_2 * h);
+    derivatives[1] = 1.0 + h * derivatives[0];
+    for n in 1..ORDER {
+        derivatives[n + 1] = h * derivatives[n] + n as f64 * derivatives[n - 1];
+    }
+    let mut terms = [0.0; ORDER.div_ceil(2)];
+    let mut t_power_over_factorial = t;
+    for (i, term) in terms.iter_mut().enumerate() {
+        let j = 2 * i + 1;
+        if j > 1 {
+            t_power_over_factorial *= t * t / ((j - 1) * j) as f64;
+        }
+        *term = derivatives[j] * t_power_over_factorial;
+    }
+    let expansion = 2.0 * terms.iter().rev().sum::<f64>();
+    let b = FRAC_1_SQRT_2PI * (-0.5 * (h * h + t * t)).exp() * expansion;
+    b.max(0.0).abs()
+}
+
+// Region III: the first term of the Black formula dominates
+fn normalised_black_call_using_norm_cdf(x: f64, s: f64) -> f64 {
+    let h = x / s;
+    let t = 0.5 * s;
+    let b_max = (0.5 * x).exp();
+    let b = cum_norm(h + t) * b_max - cum_norm(h - t) / b_max;
+    b.max(0.0).abs()
+}
+
+// Region IV
+fn normalised_black_call_using_erfcx(h: f64, t: f64) -> f64 {
+    let b = 0.5
+        * (-0.5 * (h * h + t * t)).exp()
+        * (erfcx(-FRAC_1_SQRT_2 * (h + t)) - erfcx(-FRAC_1_SQRT_2 * (h - t)));
+    b.max(0.0).abs()
+}
+
+pub(crate) fn normalised_black_call(x: f64, s: f64) -> f64 {
+    if x > 0.0 {
+        // in the money
+        return normalised_intrinsic(x, 1.0) + normalised_black_call(-x, s);
+    }
+    if s <= 0.0 {
+        return normalised_intrinsic(x, 1.0);
+    }
+    // h = x/s and t = s/2, with the region conditions evaluated without dividing by s
+    if x < s * ASYMPTOTIC_EXPANSION_ACCURACY_THRESHOLD
+        && 0.5 * s * s + x
+            < s * (SMALL_T_EXPANSION_OF_NORMALISED_BLACK_THRESHOLD
+                + ASYMPTOTIC_EXPANSION_ACCURACY_THRESHOLD)
+    {
+        return asymptotic_expansion_of_normalised_black_call(x / s, 0.5 * s);
+    }
+    if 0.5 * s < SMALL_T_EXPANSION_OF_NORMALISED_BLACK_THRESHOLD {
+        return small_t_expansion_of_normalised_black_call(x / s, 0.5 * s);
+    }
+    if x + 0.5 * s * s > s * 0.85 {
+        return normalised_black_call_using_norm_cdf(x, s);
+    }
+    normalised_black_call_using_erfcx(x / s, 0.5 * s)
+}
+
+fn normalised_vega(x: f64, s: f64) -> f64 {
+    let ax = x.abs();
+    if ax <= 0.0 {
+        FRAC_1_SQRT_2PI * (-0.125 * s * s).exp()
+    } else if s <= 0.0 || s <= ax * SQRT_DBL_MIN {
+        0.0
+    } else {
+        FRAC_1_SQRT_2PI * (-0.5 * ((x / s).powi(2) + (0.5 * s).powi(2))).exp()
+    }
+}
+
+// Rational cubic interpolation of Delbourgo and Gregory (1985)
+#[allow(clippy::too_many_arguments)]
+fn rational_cubic_interpolation(
+    x: f64,
+    x_l: f64,
+    x_r: f64,
+    y_l: f64,
+    y_r: f64,
+    d_l: f64,
+    d_r: f64,
+    r: f64,
+) -> f64 {
+    let h = x_r - x_l;
+    if h.abs() <= 0.0 {
+        return 0.5 * (y_l + y_r);
+    }
+    let t = (x - x_l) / h;
+    if r < MAXIMUM_RATIONAL_CUBIC_CONTROL_PARAMETER_VALUE {
+        let omt = 1.0 - t;
+        let t2 = t * t;
+        let omt2 = omt * omt;
+        (y_r * t2 * t
+            + (r * y_r - h * d_r) * t2 * omt
+            + (r * y_l + h * d_l) * t * omt2
+            + y_l * omt2 * omt)
+            / (1.0 + (r - 3.0) * t * omt)
+    } else {
+        // linear interpolation without over- or underflow
+        y_r * t + y_l * (1.0 - t)
+    }
+}
+
+fn rational_cubic_control_parameter_to_fit_second_derivative_at_left_side(
+    x_l: f64,
+    x_r: f64,
+    y_l: f64,
+    y_r: f64,
+    d_l: f64,
+    d_r: f64,
+    second_derivative_l: f64,
+) -> f64 {
+    let h = x_r - x_l;
+    let numerator = 0.5 * h * second_derivative_l + (d_r - d_l);
+    if is_below_horizon(numerator) {
+        return 0.0;
+    }
+    let denominator = (y_r - y_l) / h - d_l;
+    if is_below_horizon(denominator) {
+        return if numerator > 0.0 {
+            MAXIMUM_RATIONAL_CUBIC_CONTROL_PARAMETER_VALUE
+        } else {
+            MINIMUM_RATIONAL_CUBIC_CONTROL_PARAMETER_VALUE
+        };
+    }
+    numerator / denominator
+}
+
+fn rational_cubic_control_parameter_to_fit_second_derivative_at_right_side(
+    x_l: f64,
+    x_r: f64,
+    y_l: f64,
+    y_r: f64,
+    d_l: f64,
+    d_r: f64,
+    second_derivative_r: f64,
+) -> f64 {
+    let h = x_r - x_l;
+    let numerator = 0.5 * h * second_derivative_r + (d_r - d_l);
+    if is_below_horizon(numerator) {
+        return 0.0;
+    }
+    let denominator = d_r - (y_r - y_l) / h;
+    if is_below_horizon(denominator) {
+        return if numerator > 0.0 {
+            MAXIMUM_RATIONAL_CUBIC_CONTROL_PARAMETER_VALUE
+        } else {
+            MINIMUM_RATIONAL_CUBIC_CONTROL_PARAMETER_VALUE
+        };
+    }
+    numerator / denominator
+}
+
+fn minimum_rational_cubic_control_parameter(
+    d_l: f64,
+    d_r: f64,
+    s: f64,
+    prefer_shape_preservation_over_smoothness: bool,
+) -> f64 {
+    let monotonic = d_l * s >= 0.0 && d_r * s >= 0.0;
+    let convex = d_l <= s && s <= d_r;
+    let concave = d_l >= s && s >= d_r;
+    if !monotonic && !convex && !concave {
+        return MINIMUM_RATIONAL_CUBIC_CONTROL_PARAMETER_VALUE;
+    }
+    let d_r_m_d_l = d_r - d_l;
+    let d_r_m_s = d_r - s;
+    let s_m_d_l = s - d_l;
+    let mut r1 = f64::MIN;
+    let mut r2 = f64::MIN;
+    if monotonic {
+        if !is_below_horizon(s) {
+            r1 = (d_r + d_l) / s;
+        } else if prefer_shape_preservation_over_smoothness {
+            r1 = MAXIMUM_RATIONAL_CUBIC_CONTROL_PARAMETER_VALUE;
+        }
+    }
+    if convex || concave {
+        if !(is_below_horizon(s_m_d_l) || is_below_horizon(d_r_m_s)) {
+            r2 = (d_r_m_d_l / d_r_m_s).abs().max((d_r_m_d_l / s_m_d_l).abs());
+        } else if prefer_shape_preservation_over_smoothness {
+            r2 = MAXIMUM_RATIONAL_CUBIC_CONTROL_PARAMETER_VALUE;
+        }
+    } else if monotonic && prefer_shape_preservation_over_smoothness {
+        r2 = MAXIMUM_RATIONAL_CUBIC_CONTROL_PARAMETER_VALUE;
+    }
+    MINIMUM_RATIONAL_CUBIC_CONTROL_PARAMETER_VALUE.max(r1.max(r2))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn convex_rational_cubic_control_parameter_to_fit_second_derivative_at_left_side(
+    x_l: f64,
+    x_r: f64,
+    y_l: f64,
+    y_r: f64,
+    d_l: f64,
+    d_r: f64,
+    second_derivative_l: f64,
+    prefer_shape_preservation_over_smoothness: bool,
+) -> f64 {
+    let r = rational_cubic_control_parameter_to_fit_second_derivative_at_left_side(
+        x_l,
+        x_r,
+        y_l,
+        y_r,
+        d_l,
+        d_r,
+        second_derivative_l,
+    );
+    let r_min = minimum_rational_cubic_control_parameter(
+        d_l,
+        d_r,
+        (y_r - y_l) / (x_r - x_l),
+        prefer_shape_preservation_over_smoothness,
+    );
+    r.max(r_min)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn convex_rational_cubic_control_parameter_to_fit_second_derivative_at_right_side(
+    x_l: f64,
+    x_r: f64,
+    y_l: f64,
+    y_r: f64,
+    d_l: f64,
+    d_r: f64,
+    second_derivative_r: f64,
+    prefer_shape_preservation_over_smoothness: bool,
+) -> f64 {
+    let r = rational_cubic_control_parameter_to_fit_second_derivative_at_right_side(
+        x_l,
+        x_r,
+        y_l,
+        y_r,
+        d_l,
+        d_r,
+        second_derivative_r,
+    );
+    let r_min = minimum_rational_cubic_control_parameter(
+        d_l,
+        d_r,
+        (y_r - y_l) / (x_r - x_l),
+        prefer_shape_preservation_over_smoothness,
+    );
+    r.max(r_min)
+}
+
+// Formula (4.2): returns f, f' and f'' of the lower map at s
+fn compute_f_lower_map_and_first_two_derivatives(x: f64, s: f64) -> (f64, f64, f64) {
+    let ax = x.abs();
+    let z = SQRT_ONE_OVER_THREE * ax / s;
+    let y = z * z;
+    let s2 = s * s;
+    let phi_cdf = cum_norm(-z);
+    let phi_pdf = norm_pdf(z);
+    let fpp = FRAC_PI_6 * y / (s2 * s)
+        * phi_cdf
+        * (8.0 * SQRT_THREE * s * ax + (3.0 * s2 * (s2 - 8.0) - 8.0 * x * x) * phi_cdf / phi_pdf)
+        * (2.0 * y + 0.25 * s2).exp();
+    if is_below_horizon(s) {
+        (0.0, 1.0, fpp)
+    } else {
+        let phi_cdf_2 = phi_cdf * phi_cdf;
+        let fp = 2.0 * PI * y * phi_cdf_2 * (y + 0.125 * s2).exp();
+        let f = if is_below_horizon(x) {
+            0.0
+        } else {
+            TWO_PI_OVER_SQRT_TWENTY_SEVEN * ax * (phi_cdf_2 * phi_cdf)
+        };
+        (f, fp, fpp)
+    }
+}
+
+// Formula (4.4)
+fn inverse_f_lower_map(x: f64, f: f64) -> f64 {
+    if is_below_horizon(f) {
+        0.0
+    } else {
+        (x / (SQRT_THREE
+            * inverse_norm_cdf((f / (TWO_PI_OVER_SQRT_TWENTY_SEVEN * x.abs())).cbrt())))
+        .abs()
+    }
+}
+
+// Formula (4.5): returns f, f' and f'' of the upper map at s
+fn compute_f_upper_map_and_first_two_derivatives(x: f64, s: f64) -> (f64, f64, f64) {
+    let f = cum_norm(-0.5 * s);
+    if is_below_horizon(x) {
+        (f, -0.5, 0.0)
+    } else {
+        let w = (x / s).powi(2);
+        let fp = -0.5 * (0.5 * w).exp();
+        let fpp = SQRT_PI_OVER_TWO * (w + 0.125 * s * s).exp() * w / s;
+        (f, fp, fpp)
+    }
+}
+
+fn inverse_f_upper_map(f: f64) -> f64 {
+    -2.0 * inverse_norm_cdf(f)
+}
+
+// Bracketing state shared by the Householder iterations of each branch
+struct Iteration {
+    s: f64,
+    ds: f64,
+    ds_previous: f64,
+    s_left: f64,
+    s_right: f64,
+    direction_reversal_count: usize,
+}
+
+impl Iteration {
+    fn new(s: f64, s_left: f64, s_right: f64) -> Self {
+        Iteration {
+            s,
+            ds: f64::MIN,
+            ds_previous: 0.0,
+            s_left,
+            s_right,
+            direction_reversal_count: 0,
+        }
+    }
+
+    // Runs the Householder(3) iteration where `step` maps (s, b, b') to the step ds,
+    // or `None` on numerical underflow.  Falls back to bisection if the iterate
+    // leaves the bracket or oscillates.
+    fn run(mut self, x: f64, beta: f64, step: impl Fn(f64, f64, f64) -> Option<f64>) -> f64 {
+        let mut iterations = 0;
+        while iterations < MAXIMUM_ITERATIONS && self.ds.abs() > f64::EPSILON * self.s {
+            if self.ds * self.ds_previous < 0.0 {
+                self.direction_reversal_count += 1;
+            }
+            if iterations > 0
+                && (self.direction_reversal_count == 3
+                    || !(self.s > self.s_left && self.s < self.s_right))
+            {
+                self.s = 0.5 * (self.s_left + self.s_right);
+                if self.s_right - self.s_left <= f64::EPSILON * self.s {
+                    break;
+                }
+                self.direction_reversal_count = 0;
+                self.ds = 0.0;
+            }
+            self.ds_previous = self.ds;
+            let b = normalised_black_call(x, self.s);
+            let bp = normalised_vega(x, self.s);
+            if b > beta && self.s < self.s_right {
+                self.s_right = self.s;
+            } else if b < beta && self.s > self.s_left {
+                self.s_left = self.s;
+            }
+            self.ds = step(self.s, b, bp)
+                .unwrap_or(0.5 * (self.s_left + self.s_right) - self.s)
+                .max(-0.5 * self.s);
+            self.s += self.ds;
+            iterations += 1;
+        }
+        self.s
+    }
+}
+
+// Returns s = σ√T such that the normalised Black price equals beta,
+// or `None` if beta is at or above the maximum normalised price
+fn normalised_implied_volatility(beta: f64, x: f64, theta: f64) -> Option<f64> {
+    let (mut beta, mut x, mut theta) = (beta, x, theta);
+    // subtract intrinsic
+    if theta * x > 0.0 {
+        beta = (beta - normalised_intrinsic(x, theta)).max(0.0).abs();
+        theta = -theta;
+    }
+    // map puts to calls
+    if theta < 0.0 {
+        x = -x;
+    }
+    if beta <= 0.0 {
+        return Some(0.0);
+    }
+    let b_max = (0.5 * x).exp();
+    if beta >= b_max {
+        return None;
+    }
+    let s_c = (2.0 * x).abs().sqrt();
+    let b_c = normalised_black_call(x, s_c);
+    let v_c = normalised_vega(x, s_c);
+
+    // objective function g(s) = b(x, s) - beta on the two middle segments
+    let middle_step = |s: f64, b: f64, bp: f64| {
+        let newton = (beta - b) / bp;
+        let halley = (x / s).powi(2) / s - s / 4.0;
+        let hh3 = halley * halley - 3.0 * (x / (s * s)).powi(2) - 0.25;
+        Some(newton * householder_factor(newton, halley, hh3))
+    };
+
+    let iteration = if beta < b_c {
+        let s_l = s_c - b_c / v_c;
+        let b_l = normalised_black_call(x, s_l);
+        if beta < b_l {
+            let (f_lower_map_l, d_f_lower_map_l_d_beta, d2_f_lower_map_l_d_beta2) =
+                compute_f_lower_map_and_first_two_derivatives(x, s_l);
+            let r_ll =
+                convex_rational_cubic_control_parameter_to_fit_second_derivative_at_right_side(
+                    0.0,
+                    b_l,
+                    0.0,
+                    f_lower_map_l,
+                    1.0,
+                    d_f_lower_map_l_d_beta,
+                    d2_f_lower_map_l_d_beta2,
+                    true,
+                );
+            let mut f = rational_cubic_interpolation(
+                beta,
+                0.0,
+                b_l,
+                0.0,
+                f_lower_map_l,
+                1.0,
+                d_f_lower_map_l_d_beta,
+                r_ll,
+            );
+            if f <= 0.0 || f.is_nan() {
+                // quadratic interpolation using f(0) = 0, f(b_l) and f'(0) = 1
+                let t = beta / b_l;
+                f = (f_lower_map_l * t + b_l * (1.0 - t)) * t;
+            }
+            let s = inverse_f_lower_map(x, f);
+            // objective function g(s) = 1/ln(b(x, s)) - 1/ln(beta) on the lowest segment
+            let ln_beta = beta.ln();
+            let lower_step = |s: f64, b: f64, bp: f64| {
+                if b <= 0.0 || bp <= 0.0 {
+                    return None;
+                }
+                let ln_b = b.ln();
+                let bpob = bp / b;
+                let h = x / s;
+                let b_halley = h * h / s - s / 4.0;
+                let newton = (ln_beta - ln_b) * ln_b / ln_beta / bpob;
+                let halley = b_halley - bpob * (1.0 + 2.0 / ln_b);
+                let b_hh3 = b_halley * b_halley - 3.0 * (h / s).powi(2) - 0.25;
+                let hh3 = b_hh3 + 2.0 * bpob.powi(2) * (1.0 + 3.0 / ln_b * (1.0 + 1.0 / ln_b))
+                    - 3.0 * b_halley * bpob * (1.0 + 2.0 / ln_b);
+                Some(newton * householder_factor(newton, halley, hh3))
+            };
+            return Some(Iteration::new(s, f64::MIN_POSITIVE, s_l).run(x, beta, lower_step));
+        }
+        let v_l = normalised_vega(x, s_l);
+        let r_lm = convex_rational_cubic_control_parameter_to_fit_second_derivative_at_right_side(
+            b_l,
+            b_c,
+            s_l,
+            s_c,
+            1.0 / v_l,
+            1.0 / v_c,
+            0.0,
+            false,
+        );
+        let s = rational_cubic_interpolation(beta, b_l, b_c, s_l, s_c, 1.0 / v_l, 1.0 / v_c, r_lm);
+        Iteration::new(s, s_l, s_c)
+    } else {
+        let s_h = if v_c > f64::MIN_POSITIVE {
+            s_c + (b_max - b_c) / v_c
+        } else {
+            s_c
+        };
+        let b_h = normalised_black_call(x, s_h);
+        if beta <= b_h {
+            let v_h = normalised_vega(x, s_h);
+            let r_hm =
+                convex_rational_cubic_control_parameter_to_fit_second_derivative_at_left_side(
+                    b_c,
+                    b_h,
+                    s_c,
+                    s_h,
+                    1.0 / v_c,
+                    1.0 / v_h,
+                    0.0,
+                    false,
+                );
+            let s =
+                rational_cubic_interpolation(beta, b_c, b_h, s_c, s_h, 1.0 / v_c, 1.0 / v_h, r_hm);
+            Iteration::new(s, s_c, s_h)
+        } else {
+            let (f_upper_map_h, d_f_upper_map_h_d_beta, d2_f_upper_map_h_d_beta2) =
+                compute_f_upper_map_and_first_two_derivatives(x, s_h);
+            let mut f = f64::MIN;
+            if d2_f_upper_map_h_d_beta2 > -SQRT_DBL_MAX && d2_f_upper_map_h_d_beta2 < SQRT_DBL_MAX {
+                let r_hh =
+                    convex_rational_cubic_control_parameter_to_fit_second_derivative_at_left_side(
+                        b_h,
+                        b_max,
+                        f_upper_map_h,
+                        0.0,
+                        d_f_upper_map_h_d_beta,
+                        -0.5,
+                        d2_f_upper_map_h_d_beta2,
+                        true,
+                    );
+                f = rational_cubic_interpolation(
+                    beta,
+                    b_h,
+                    b_max,
+                    f_upper_map_h,
+                    0.0,
+                    d_f_upper_map_h_d_beta,
+                    -0.5,
+                    r_hh,
+                );
+            }
+            if f <= 0.0 || f.is_nan() {
+                // quadratic interpolation using f(b_h), f(b_max) = 0 and f'(b_max) = -1/2
+                let h = b_max - b_h;
+                let t = (beta - b_h) / h;
+                f = (f_upper_map_h * (1.0 - t) + 0.5 * h * t) * (1.0 - t);
+            }
+            let s = inverse_f_upper_map(f);
+            if beta > 0.5 * b_max {
+                // objective function g(s) = ln(b_max - beta) - ln(b_max - b(x, s)) on the upper segment
+                let upper_step = |s: f64, b: f64, bp: f64| {
+                    if b >= b_max || bp <= f64::MIN_POSITIVE {
+                        return None;
+                    }
+                    let b_max_minus_b = b_max - b;
+                    let g = ((b_max - beta) / b_max_minus_b).ln();
+                    let gp = bp / b_max_minus_b;
+                    let b_halley = (x / s).powi(2) / s - s / 4.0;
+                    let b_hh3 = b_halley * b_halley - 3.0 * (x / (s * s)).powi(2) - 0.25;
+                    let newton = -g / gp;
+                    let halley = b_halley + gp;
+                    let hh3 = b_hh3 + gp * (2.0 * gp + 3.0 * b_halley);
+                    Some(newton * householder_factor(newton, halley, hh3))
+                };
+                return Some(Iteration::new(s, s_h, f64::MAX).run(x, beta, upper_step));
+            }
+            Iteration::new(s, s_h, f64::MAX)
+        }
+    };
+    Some(iteration.run(x, beta, middle_step))
+}
+
+/// Returns the Black implied volatility of an undiscounted option price on a forward.
+pub(crate) fn implied_black_volatility(
+    price: f64,
+    forward: f64,
+    strike: f64,
+    maturity: f64,
+    is_call: bool,
+) -> Result<f64, IvError> {
+    let theta = if is_call { 1.0 } else { -1.0 };
+    let intrinsic = (theta * (forward - strike)).max(0.0);
+    if price < intrinsic {
+        return Err(IvError::PriceBelowIntrinsic { price, intrinsic });
+    }
+    let upper_bound = if is_call { forward } else { strike };
+    let above_upper_bound = IvError::PriceAboveUpperBound { price, upper_bound };
+    if price >= upper_bound {
+        return Err(above_upper_bound);
+    }
+    let x = (forward / strike).ln();
+    // map in-the-money to out-of-the-money
+    let (price, theta) = if theta * x > 0.0 {
+        ((price - intrinsic).max(0.0), -theta)
+    } else {
+        (price, theta)
+    };
+    normalised_implied_volatility(price / (forward.sqrt() * strike.sqrt()), x, theta)
+        .map(|s| s / maturity.sqrt())
+        .ok_or(above_upper_bound)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::*;
+
+    // Reference normalised Black price by direct evaluation
+    fn normalised_black_call_direct(x: f64, s: f64) -> f64 {
+        let h = x / s;
+        let t = 0.5 * s;
+        cum_norm(h + t) * (0.5 * x).exp() - cum_norm(h - t) * (-0.5 * x).exp()
+    }
+
+    #[test]
+    fn inverse_norm_cdf_round_trips() {
+        for &u in &[
+            1e-300, 1e-20, 1e-8, 0.001, 0.02425, 0.3, 0.5, 0.7, 0.975, 0.999999,
+        ] {
+            let z = inverse_norm_cdf(u);
+            assert_relative_eq!(cum_norm(z), u, max_relative = 1e-14);
+        }
+    }
+    #[test]
+    fn erfcx_is_continuous_at_asymptotic_switch() {
+        assert_relative_eq!(erfcx(26.0 - 1e-12), erfcx(26.0), max_relative = 1e-13);
+    }
+    #[test]
+    fn normalised_black_call_matches_direct_evaluation_in_each_region() {
+        // region I, II, III and IV respectively
+        for &(x, s) in &[(-12.0, 0.5), (-0.5, 0.1), (0.5, 2.0), (-1.0, 1.0)] {
+            assert_relative_eq!(
+                normalised_black_call(x, s),
+                normalised_black_call_direct(x, s),
+                max_relative = 1e-11
+            );
+        }
+    }
+    #[test]
+    fn normalised_implied_volatility_round_trips_on_all_branches() {
+        for &x in &[-5.0, -0.5, -0.01, 0.0, 0.3, 2.0] {
+            for &s in &[0.5, 1.0, 3.0, 8.0] {
+                let beta = normalised_black_call(x, s);
+                let implied = normalised_implied_volatility(beta, x, 1.0).unwrap();
+                assert_relative_eq!(implied, s, max_relative = 1e-11);
+            }
+        }
+    }
+}
@@ -1,18 +1,34 @@
 //! # black_scholes
 //! A Black Scholes option pricing library.
 use serde::Serialize;
-use special::Error;
-use std::f64::consts::{FRAC_1_PI, FRAC_1_SQRT_2, FRAC_2_SQRT_PI, SQRT_2};
+use stats::cum_norm;
+use std::f64::consts::{FRAC_1_PI, FRAC_2_SQRT_PI, SQRT_2};
+use std::fmt;
+
+pub mod american;
+pub mod asian;
+pub mod bachelier;
+pub mod barrier;
+pub mod digital;
+pub mod double_barrier;
+pub mod garman_kohlhagen;
+pub mod lattice;
+mod lets_be_rational;
+pub mod longstaff_schwartz;
+pub mod lookback;
+pub mod monte_carlo;
+pub mod pde;
+pub mod rainbow;
+pub mod shifted_lognormal;
+mod solver;
+pub mod spread;
+pub mod stats;
+pub mod vanna_volga;
 
 /// 1/sqrt(2π)
 #[allow(clippy::excessive_precision)]
 const FRAC_1_SQRT_2PI: f64 = 0.3989422804014326779399460599343818684758586311649346576659258296;
 
-// CDF of standard normal distribution
-fn cum_norm(x: f64) -> f64 {
-    (x * FRAC_1_SQRT_2).error() * 0.5 + 0.5
-}
-
 // PDF of standard normal distribution
 fn inc_norm(x: f64) -> f64 {
     (-x.powi(2) * 0.5).exp() * FRAC_1_SQRT_2PI
@@ -348,7 +364,7 @@ pub fn put_rho(s: f64, k: f64, rate: f64, sigma: f64, maturity: f64) -> f64 {
         let discount = (-rate * maturity).exp();
         let d1 = d1(s, k, discount, sqrt_maturity_sigma);
 
-        -1.0 * k * discount * maturity * cum_norm(-d1 + sqrt_maturity_sigma)
+        -k * discount * maturity * cum_norm(-d1 + sqrt_maturity_sigma)
     } else {
         0.0
     }
@@ -487,20 +503,479 @@ pub fn put_charm(s: f64, k: f64, rate: f64, sigma: f64, maturity: f64) -> f64 {
     call_charm(s, k, rate, sigma, maturity)
 }
 
+/// Delta quoting conventions for FX options.  Spot is the price of one unit
+/// of foreign currency in domestic currency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum FxDeltaConvention {
+    /// `∂V/∂S` with the premium paid in domestic currency: `ω·e^{-r_f T}·N(ω·d1)`.
+    Spot,
+    /// Spot delta without the foreign discount factor: `ω·N(ω·d1)`.
+    Forward,
+    /// Spot delta less the premium in foreign currency, `Δ - V/S`:
+    /// `ω·e^{-r_f T}·(K/F)·N(ω·d2)`.
+    PremiumAdjustedSpot,
+    /// Premium-adjusted delta without the foreign discount factor:
+    /// `ω·(K/F)·N(ω·d2)`.
+    PremiumAdjustedForward,
+}
+
+impl FxDeltaConvention {
+    fn is_premium_adjusted(self) -> bool {
+        matches!(
+            self,
+            FxDeltaConvention::PremiumAdjustedSpot | FxDeltaConvention::PremiumAdjustedForward
+        )
+    }
+    fn discount(self, foreign_rate: f64, maturity: f64) -> f64 {
+        match self {
+            FxDeltaConvention::Spot | FxDeltaConvention::PremiumAdjustedSpot => {
+                (-foreign_rate * maturity).exp()
+            }
+            FxDeltaConvention::Forward | FxDeltaConvention::PremiumAdjustedForward => 1.0,
+        }
+    }
+}
+
+// omega is 1 for calls and -1 for puts
+#[allow(clippy::too_many_arguments)]
+fn fx_delta(
+    omega: f64,
+    spot: f64,
+    strike: f64,
+    sigma: f64,
+    domestic_rate: f64,
+    foreign_rate: f64,
+    maturity: f64,
+    convention: FxDeltaConvention,
+) -> f64 {
+    let forward = spot * ((domestic_rate - foreign_rate) * maturity).exp();
+    let discount = convention.discount(foreign_rate, maturity);
+    let premium_adjustment = if convention.is_premium_adjusted() {
+        strike / forward
+    } else {
+        1.0
+    };
+    let sqrt_maturity_sigma = maturity.sqrt() * sigma;
+    if sqrt_maturity_sigma > 0.0 {
+        let d1 = (forward / strike).ln() / sqrt_maturity_sigma + 0.5 * sqrt_maturity_sigma;
+        let d = if convention.is_premium_adjusted() {
+            d1 - sqrt_maturity_sigma
+        } else {
+            d1
+        };
+        omega * discount * premium_adjustment * cum_norm(omega * d)
+    } else if omega * (forward - strike) > 0.0 {
+        omega * discount * premium_adjustment
+    } else {
+        0.0
+    }
+}
+
+const STRIKE_PRECISION: f64 = 1.0e-15;
+const STRIKE_ITERATIONS: usize = 100;
+
+// Strike K = F·exp(-d2·σ√T - σ²T/2) for which (K/F)·N(ω·d2) equals the
+// undiscounted premium-adjusted delta.  For calls the premium-adjusted delta
+// rises from zero, peaks and falls back to zero as the strike increases, so
+// the strike above the peak is returned, as is market convention.
+fn premium_adjusted_d2(omega: f64, normalised_delta: f64, sqrt_maturity_sigma: f64) -> Option<f64> {
+    let adjusted_delta = |d2: f64| {
+        (-d2 * sqrt_maturity_sigma - 0.5 * sqrt_maturity_sigma * sqrt_maturity_sigma).exp()
+            * cum_norm(omega * d2)
+    };
+    let (lower, upper) = if omega > 0.0 {
+        // the peak is where σ√T·N(d2) = φ(d2), which has a single root above -σ√T
+        let peak = solver::brent(
+            |d2| sqrt_maturity_sigma * cum_norm(d2) - inc_norm(d2),
+            -sqrt_maturity_sigma,
+            40.0,
+            STRIKE_PRECISION,
+            STRIKE_ITERATIONS,
+        )
+        .ok()?;
+        if normalised_delta > adjusted_delta(peak) || normalised_delta >= 1.0 {
+            return None;
+        }
+        // the unadjusted delta is larger, so its strike bounds the solution from above
+        let unadjusted = lets_be_rational::inverse_norm_cdf(normalised_delta) - sqrt_maturity_sigma;
+        (unadjusted.min(peak), peak)
+    } else {
+        // the put premium-adjusted delta is decreasing in d2 and unbounded below
+        let (mut lower, mut upper) = (-1.0, 1.0);
+        for _ in 0..64 {
+            if adjusted_delta(lower) >= normalised_delta {
+                break;
+            }
+            lower *= 2.0;
+        }
+        for _ in 0..64 {
+            if adjusted_delta(upper) <= normalised_delta {
+                break;
+            }
+            upper *= 2.0;
+        }
+        (lower, upper)
+    };
+    solver::brent(
+        |d2| adjusted_delta(d2) - normalised_delta,
+        lower,
+        upper,
+        STRIKE_PRECISION,
+        STRIKE_ITERATIONS,
+    )
+    .ok()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn fx_strike_from_delta(
+    omega: f64,
+    delta: f64,
+    spot: f64,
+    sigma: f64,
+    domestic_rate: f64,
+    foreign_rate: f64,
+    maturity: f64,
+    convention: FxDeltaConvention,
+) -> Option<f64> {
+    let forward = spot * ((domestic_rate - foreign_rate) * maturity).exp();
+    let sqrt_maturity_sigma = maturity.sqrt() * sigma;
+    let normalised_delta = omega * delta / convention.discount(foreign_rate, maturity);
+    if !(sqrt_maturity_sigma > 0.0 && normalised_delta > 0.0 && normalised_delta.is_finite())
+        || forward.is_nan()
+    {
+        return None;
+    }
+    let log_moneyness = if convention.is_premium_adjusted() {
+        let d2 = premium_adjusted_d2(omega, normalised_delta, sqrt_maturity_sigma)?;
+        d2 * sqrt_maturity_sigma + 0.5 * sqrt_maturity_sigma * sqrt_maturity_sigma
+    } else {
+        if normalised_delta >= 1.0 {
+            return None;
+        }
+        let d1 = omega * lets_be_rational::inverse_norm_cdf(normalised_delta);
+        d1 * sqrt_maturity_sigma - 0.5 * sqrt_maturity_sigma * sqrt_maturity_sigma
+    };
+    Some(forward * (-log_moneyness).exp())
+}
+
+/// Returns the delta of an FX call option under the given quoting convention
+///
+/// # Examples
+///
+/// ```
+/// let spot = 1.0850;
+/// let strike = 1.10;
+/// let sigma = 0.08;
+/// let domestic_rate = 0.05;
+/// let foreign_rate = 0.03;
+/// let maturity = 0.5;
+/// let delta = black_scholes::fx_call_delta(
+///     spot, strike, sigma, domestic_rate, foreign_rate, maturity,
+///     black_scholes::FxDeltaConvention::PremiumAdjustedSpot
+/// );
+/// ```
+pub fn fx_call_delta(
+    spot: f64,
+    strike: f64,
+    sigma: f64,
+    domestic_rate: f64,
+    foreign_rate: f64,
+    maturity: f64,
+    convention: FxDeltaConvention,
+) -> f64 {
+    fx_delta(
+        1.0,
+        spot,
+        strike,
+        sigma,
+        domestic_rate,
+        foreign_rate,
+        maturity,
+        convention,
+    )
+}
+
+/// Returns the delta of an FX put option under the given quoting convention
+///
+/// # Examples
+///
+/// ```
+/// let spot = 1.0850;
+/// let strike = 1.05;
+/// let sigma = 0.08;
+/// let domestic_rate = 0.05;
+/// let foreign_rate = 0.03;
+/// let maturity = 0.5;
+/// let delta = black_scholes::fx_put_delta(
+///     spot, strike, sigma, domestic_rate, foreign_rate, maturity,
+///     black_scholes::FxDeltaConvention::Forward
+/// );
+/// ```
+pub fn fx_put_delta(
+    spot: f64,
+    strike: f64,
+    sigma: f64,
+    domestic_rate: f64,
+    foreign_rate: f64,
+    maturity: f64,
+    convention: FxDeltaConvention,
+) -> f64 {
+    fx_delta(
+        -1.0,
+        spot,
+        strike,
+        sigma,
+        domestic_rate,
+        foreign_rate,
+        maturity,
+        convention,
+    )
+}
+
+/// Returns the strike of an FX call option with the given delta (e.g. 0.25)
+/// under the given quoting convention.  Returns `None` if no strike has
+/// that delta.  A premium-adjusted call delta is not monotone in the
+/// strike, in which case the larger of the two strikes is returned.
+///
+/// # Examples
+///
+/// ```
+/// let spot = 1.0850;
+/// let sigma = 0.08;
+/// let domestic_rate = 0.05;
+/// let foreign_rate = 0.03;
+/// let maturity = 0.5;
+/// let strike = black_scholes::fx_call_strike_from_delta(
+///     0.25, spot, sigma, domestic_rate, foreign_rate, maturity,
+///     black_scholes::FxDeltaConvention::PremiumAdjustedSpot
+/// ).unwrap();
+/// ```
+pub fn fx_call_strike_from_delta(
+    delta: f64,
+    spot: f64,
+    sigma: f64,
+    domestic_rate: f64,
+    foreign_rate: f64,
+    maturity: f64,
+    convention: FxDeltaConvention,
+) -> Option<f64> {
+    fx_strike_from_delta(
+        1.0,
+        delta,
+        spot,
+        sigma,
+        domestic_rate,
+        foreign_rate,
+        maturity,
+        convention,
+    )
+}
+
+/// Returns the strike of an FX put option with the given (negative) delta,
+/// e.g. -0.25, under the given quoting convention.  Returns `None` if no
+/// strike has that delta.
+///
+/// # Examples
+///
+/// ```
+/// let spot = 1.0850;
+/// let sigma = 0.08;
+/// let domestic_rate = 0.05;
+/// let foreign_rate = 0.03;
+/// let maturity = 0.5;
+/// let strike = black_scholes::fx_put_strike_from_delta(
+///     -0.25, spot, sigma, domestic_rate, foreign_rate, maturity,
+///     black_scholes::FxDeltaConvention::Spot
+/// ).unwrap();
+/// ```
+pub fn fx_put_strike_from_delta(
+    delta: f64,
+    spot: f64,
+    sigma: f64,
+    domestic_rate: f64,
+    foreign_rate: f64,
+    maturity: f64,
+    convention: FxDeltaConvention,
+) -> Option<f64> {
+    fx_strike_from_delta(
+        -1.0,
+        delta,
+        spot,
+        sigma,
+        domestic_rate,
+        foreign_rate,
+        maturity,
+        convention,
+    )
+}
+
+/// Returns the at-the-money delta-neutral-straddle strike, for which call
+/// and put deltas sum to zero under the given convention: `F·e^{σ²T/2}`,
+/// or `F·e^{-σ²T/2}` if premium-adjusted.
+///
+/// # Examples
+///
+/// ```
+/// let spot = 1.0850;
+/// let sigma = 0.08;
+/// let domestic_rate = 0.05;
+/// let foreign_rate = 0.03;
+/// let maturity = 0.5;
+/// let strike = black_scholes::fx_atm_dns_strike(
+///     spot, sigma, domestic_rate, foreign_rate, maturity,
+///     black_scholes::FxDeltaConvention::Spot
+/// );
+/// ```
+pub fn fx_atm_dns_strike(
+    spot: f64,
+    sigma: f64,
+    domestic_rate: f64,
+    foreign_rate: f64,
+    maturity: f64,
+    convention: FxDeltaConvention,
+) -> f64 {
+    let forward = spot * ((domestic_rate - foreign_rate) * maturity).exp();
+    let half_variance = 0.5 * sigma * sigma * maturity;
+    if convention.is_premium_adjusted() {
+        forward * (-half_variance).exp()
+    } else {
+        forward * half_variance.exp()
+    }
+}
+
+/// Reasons an implied volatility could not be found.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum IvError {
+    /// The option price is below its intrinsic value.
+    PriceBelowIntrinsic { price: f64, intrinsic: f64 },
+    /// The option price is at or above its upper bound
+    /// (`S` for calls, `K·e^{-rT}` for puts).
+    PriceAboveUpperBound { price: f64, upper_bound: f64 },
+    /// The maturity is zero or negative.
+    NonPositiveMaturity(f64),
+    /// An input is NaN or negative.
+    InvalidInput,
+    /// The root finder did not converge.
+    NoConvergence { iterations: usize, residual: f64 },
+}
+
+impl fmt::Display for IvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IvError::PriceBelowIntrinsic { price, intrinsic } => {
+                write!(f, "price {price} is below intrinsic value {intrinsic}")
+            }
+            IvError::PriceAboveUpperBound { price, upper_bound } => {
+                write!(f, "price {price} is at or above upper bound {upper_bound}")
+            }
+            IvError::NonPositiveMaturity(maturity) => {
+                write!(f, "maturity {maturity} is not positive")
+            }
+            IvError::InvalidInput => write!(f, "inputs must be positive and not NaN"),
+            IvError::NoConvergence {
+                iterations,
+                residual,
+            } => write!(
+                f,
+                "no convergence after {iterations} iterations, residual {residual}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for IvError {}
+
+// Checks inputs and no-arbitrage bounds before solving for implied volatility
+fn check_iv_inputs(
+    price: f64,
+    s: f64,
+    k: f64,
+    rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+    is_call: bool,
+) -> Result<(), IvError> {
+    if [price, s, k, rate, dividend_yield, maturity]
+        .iter()
+        .any(|v| v.is_nan())
+        || price < 0.0
+        || s <= 0.0
+        || k <= 0.0
+    {
+        return Err(IvError::InvalidInput);
+    }
+    if maturity <= 0.0 {
+        return Err(IvError::NonPositiveMaturity(maturity));
+    }
+    let s_dividend = s * (-dividend_yield * maturity).exp();
+    let k_discount = k * (-rate * maturity).exp();
+    let (intrinsic, upper_bound) = if is_call {
+        (max_or_zero(s_dividend - k_discount), s_dividend)
+    } else {
+        (max_or_zero(k_discount - s_dividend), k_discount)
+    };
+    if price < intrinsic {
+        Err(IvError::PriceBelowIntrinsic { price, intrinsic })
+    } else if price >= upper_bound {
+        Err(IvError::PriceAboveUpperBound { price, upper_bound })
+    } else {
+        Ok(())
+    }
+}
+
+// Restates bounds reported on forward prices as the discounted prices the
+// caller supplied
+fn discounted_iv_error(error: IvError, discount: f64) -> IvError {
+    match error {
+        IvError::PriceBelowIntrinsic { price, intrinsic } => IvError::PriceBelowIntrinsic {
+            price: price * discount,
+            intrinsic: intrinsic * discount,
+        },
+        IvError::PriceAboveUpperBound { price, upper_bound } => IvError::PriceAboveUpperBound {
+            price: price * discount,
+            upper_bound: upper_bound * discount,
+        },
+        error => error,
+    }
+}
+
+const IV_PRECISION: f64 = 0.000001;
+const IV_ITERATIONS: i32 = 10000;
+
+// Newton-Raphson on the price error, reporting the residual on failure
+fn newton_iv(
+    obj_fn: &dyn Fn(f64) -> f64,
+    dfn: &dyn Fn(f64) -> f64,
+    initial_guess: f64,
+) -> Result<f64, IvError> {
+    if initial_guess.is_nan() || initial_guess <= 0.0 {
+        return Err(IvError::InvalidInput);
+    }
+    nrfind::find_root(obj_fn, dfn, initial_guess, IV_PRECISION, IV_ITERATIONS).map_err(|sigma| {
+        IvError::NoConvergence {
+            iterations: IV_ITERATIONS as usize,
+            residual: obj_fn(sigma),
+        }
+    })
+}
+
 const SQRT_TWO_PI: f64 = 2.0 * SQRT_2 / FRAC_2_SQRT_PI;
-//Corrado and Miller (1996)
-fn approximate_vol(price: f64, s: f64, k: f64, rate: f64, maturity: f64) -> f64 {
-    let discount = (-rate * maturity).exp();
-    let x = k * discount;
-    let coef = SQRT_TWO_PI / (s + x);
-    let helper_1 = s - x;
-    let c1 = price - helper_1 * 0.5;
+//Corrado and Miller (1996), on the undiscounted price of an option on a forward
+fn approximate_forward_vol(undiscounted_price: f64, forward: f64, k: f64, maturity: f64) -> f64 {
+    let coef = SQRT_TWO_PI / (forward + k);
+    let helper_1 = forward - k;
+    let c1 = undiscounted_price - helper_1 * 0.5;
     let c2 = c1.powi(2);
     let c3 = helper_1.powi(2) * FRAC_1_PI;
     let bridge_1 = c2 - c3;
     let bridge_m = bridge_1.max(0.0).sqrt();
     coef * (c1 + bridge_m) / maturity.sqrt()
 }
+//Corrado and Miller (1996)
+fn approximate_vol(price: f64, s: f64, k: f64, rate: f64, maturity: f64) -> f64 {
+    let discount = (-rate * maturity).exp();
+    approximate_forward_vol(price / discount, s / discount, k, maturity)
+}
 /// Returns implied volatility from a call option with initial guess
 ///
 /// # Examples
@@ -524,12 +999,8 @@ pub fn call_iv_guess(
     rate: f64,
     maturity: f64,
     initial_guess: f64,
-) -> Result<f64, f64> {
-    let obj_fn = |sigma| call(s, k, rate, sigma, maturity) - price;
-    let dfn = |sigma| call_vega(s, k, rate, sigma, maturity);
-    let precision = 0.000001;
-    let iterations = 10000;
-    nrfind::find_root(&obj_fn, &dfn, initial_guess, precision, iterations)
+) -> Result<f64, IvError> {
+    bsm_call_iv_guess(price, s, k, rate, 0.0, maturity, initial_guess)
 }
 /// Returns implied volatility from a call option
 ///
@@ -546,9 +1017,8 @@ pub fn call_iv_guess(
 ///     maturity
 /// ).unwrap();
 /// ```
-pub fn call_iv(price: f64, s: f64, k: f64, rate: f64, maturity: f64) -> Result<f64, f64> {
-    let initial_guess = approximate_vol(price, s, k, rate, maturity);
-    call_iv_guess(price, s, k, rate, maturity, initial_guess)
+pub fn call_iv(price: f64, s: f64, k: f64, rate: f64, maturity: f64) -> Result<f64, IvError> {
+    bsm_call_iv(price, s, k, rate, 0.0, maturity)
 }
 
 /// Returns implied volatility from a put option with initial guess
@@ -574,12 +1044,8 @@ pub fn put_iv_guess(
     rate: f64,
     maturity: f64,
     initial_guess: f64,
-) -> Result<f64, f64> {
-    let obj_fn = |sigma| put(s, k, rate, sigma, maturity) - price;
-    let dfn = |sigma| put_vega(s, k, rate, sigma, maturity);
-    let precision = 0.000001;
-    let iterations = 10000;
-    nrfind::find_root(&obj_fn, &dfn, initial_guess, precision, iterations)
+) -> Result<f64, IvError> {
+    bsm_put_iv_guess(price, s, k, rate, 0.0, maturity, initial_guess)
 }
 /// Returns implied volatility from a put option
 ///
@@ -597,10 +1063,259 @@ pub fn put_iv_guess(
 ///     maturity
 /// ).unwrap();
 /// ```
-pub fn put_iv(price: f64, s: f64, k: f64, rate: f64, maturity: f64) -> Result<f64, f64> {
-    let c_price = price + s - k * (-rate * maturity).exp();
-    let initial_guess = approximate_vol(c_price, s, k, rate, maturity);
-    put_iv_guess(price, s, k, rate, maturity, initial_guess)
+pub fn put_iv(price: f64, s: f64, k: f64, rate: f64, maturity: f64) -> Result<f64, IvError> {
+    bsm_put_iv(price, s, k, rate, 0.0, maturity)
+}
+
+/// Returns implied volatility from a call option with initial guess
+/// using the Black-Scholes-Merton formula.
+///
+/// - `price`: option price ($$$ per share)
+/// - `stock` (aka `S`): stock price ($$$ per share)
+/// - `strike` (aka `K`): strike price ($$$ per share)
+/// - `risk_free_rate` (aka `r`): annualised continuously compounded risk-free interest rate (% p.a.)
+/// - `dividend_yield` (aka `q`): annualised continuously compounded dividend yield (% p.a.)
+/// - `maturity` (aka `T`): time to maturity (% of years)
+/// - `initial_guess`: starting volatility for Newton-Raphson
+///
+/// # Examples
+///
+/// ```
+/// let price = 1.0;
+/// let stock = 5.0;
+/// let strike = 4.5;
+/// let rate = 0.05;
+/// let dividend_yield = 0.02;
+/// let maturity = 1.0;
+/// let initial_guess = 0.3;
+/// let iv = black_scholes::bsm_call_iv_guess(
+///     price, stock, strike, rate,
+///     dividend_yield, maturity, initial_guess
+/// ).unwrap();
+/// ```
+pub fn bsm_call_iv_guess(
+    price: f64,
+    stock: f64,
+    strike: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+    initial_guess: f64,
+) -> Result<f64, IvError> {
+    check_iv_inputs(
+        price,
+        stock,
+        strike,
+        risk_free_rate,
+        dividend_yield,
+        maturity,
+        true,
+    )?;
+    // Merton's price is Black-Scholes on the dividend-adjusted stock price
+    let s_dividend = stock * (-dividend_yield * maturity).exp();
+    let obj_fn = |sigma| call(s_dividend, strike, risk_free_rate, sigma, maturity) - price;
+    let dfn = |sigma| call_vega(s_dividend, strike, risk_free_rate, sigma, maturity);
+    newton_iv(&obj_fn, &dfn, initial_guess)
+}
+
+/// Returns implied volatility from a call option using the
+/// Black-Scholes-Merton formula.  The initial guess is taken
+/// from the dividend-adjusted forward.
+///
+/// # Examples
+///
+/// ```
+/// let price = 1.0;
+/// let stock = 5.0;
+/// let strike = 4.5;
+/// let rate = 0.05;
+/// let dividend_yield = 0.02;
+/// let maturity = 1.0;
+/// let iv = black_scholes::bsm_call_iv(
+///     price, stock, strike, rate,
+///     dividend_yield, maturity
+/// ).unwrap();
+/// ```
+pub fn bsm_call_iv(
+    price: f64,
+    stock: f64,
+    strike: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> Result<f64, IvError> {
+    check_iv_inputs(
+        price,
+        stock,
+        strike,
+        risk_free_rate,
+        dividend_yield,
+        maturity,
+        true,
+    )?;
+    let s_dividend = stock * (-dividend_yield * maturity).exp();
+    let initial_guess = approximate_vol(price, s_dividend, strike, risk_free_rate, maturity);
+    bsm_call_iv_guess(
+        price,
+        stock,
+        strike,
+        risk_free_rate,
+        dividend_yield,
+        maturity,
+        initial_guess,
+    )
+}
+
+/// Returns implied volatility from a put option with initial guess
+/// using the Black-Scholes-Merton formula.
+///
+/// # Examples
+///
+/// ```
+/// let price = 0.3;
+/// let stock = 5.0;
+/// let strike = 4.5;
+/// let rate = 0.05;
+/// let dividend_yield = 0.02;
+/// let maturity = 1.0;
+/// let initial_guess = 0.3;
+/// let iv = black_scholes::bsm_put_iv_guess(
+///     price, stock, strike, rate,
+///     dividend_yield, maturity, initial_guess
+/// ).unwrap();
+/// ```
+pub fn bsm_put_iv_guess(
+    price: f64,
+    stock: f64,
+    strike: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+    initial_guess: f64,
+) -> Result<f64, IvError> {
+    check_iv_inputs(
+        price,
+        stock,
+        strike,
+        risk_free_rate,
+        dividend_yield,
+        maturity,
+        false,
+    )?;
+    let s_dividend = stock * (-dividend_yield * maturity).exp();
+    let obj_fn = |sigma| put(s_dividend, strike, risk_free_rate, sigma, maturity) - price;
+    let dfn = |sigma| put_vega(s_dividend, strike, risk_free_rate, sigma, maturity);
+    newton_iv(&obj_fn, &dfn, initial_guess)
+}
+
+/// Returns implied volatility from a put option using the
+/// Black-Scholes-Merton formula.  The initial guess is taken
+/// from the dividend-adjusted forward.
+///
+/// # Examples
+///
+/// ```
+/// let price = 0.3;
+/// let stock = 5.0;
+/// let strike = 4.5;
+/// let rate = 0.05;
+/// let dividend_yield = 0.02;
+/// let maturity = 1.0;
+/// let iv = black_scholes::bsm_put_iv(
+///     price, stock, strike, rate,
+///     dividend_yield, maturity
+/// ).unwrap();
+/// ```
+pub fn bsm_put_iv(
+    price: f64,
+    stock: f64,
+    strike: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> Result<f64, IvError> {
+    check_iv_inputs(
+        price,
+        stock,
+        strike,
+        risk_free_rate,
+        dividend_yield,
+        maturity,
+        false,
+    )?;
+    let s_dividend = stock * (-dividend_yield * maturity).exp();
+    // convert to the equivalent call price via put-call parity
+    let c_price = price + s_dividend - strike * (-risk_free_rate * maturity).exp();
+    let initial_guess = approximate_vol(c_price, s_dividend, strike, risk_free_rate, maturity);
+    bsm_put_iv_guess(
+        price,
+        stock,
+        strike,
+        risk_free_rate,
+        dividend_yield,
+        maturity,
+        initial_guess,
+    )
+}
+
+/// Returns implied volatility from a call option using Jäckel's
+/// "Let's Be Rational" algorithm.  Converges to machine precision
+/// in two iterations for any price within the no-arbitrage bounds.
+///
+/// # Examples
+///
+/// ```
+/// let price = 1.0;
+/// let stock = 5.0;
+/// let strike = 4.5;
+/// let rate = 0.05;
+/// let maturity = 1.0;
+/// let iv = black_scholes::call_iv_rational(
+///     price, stock, strike, rate,
+///     maturity
+/// ).unwrap();
+/// ```
+pub fn call_iv_rational(
+    price: f64,
+    s: f64,
+    k: f64,
+    rate: f64,
+    maturity: f64,
+) -> Result<f64, IvError> {
+    check_iv_inputs(price, s, k, rate, 0.0, maturity, true)?;
+    let discount = (-rate * maturity).exp();
+    lets_be_rational::implied_black_volatility(price / discount, s / discount, k, maturity, true)
+        .map_err(|error| discounted_iv_error(error, discount))
+}
+
+/// Returns implied volatility from a put option using Jäckel's
+/// "Let's Be Rational" algorithm.  Converges to machine precision
+/// in two iterations for any price within the no-arbitrage bounds.
+///
+/// # Examples
+///
+/// ```
+/// let price = 0.3;
+/// let stock = 5.0;
+/// let strike = 4.5;
+/// let rate = 0.05;
+/// let maturity = 1.0;
+/// let iv = black_scholes::put_iv_rational(
+///     price, stock, strike, rate,
+///     maturity
+/// ).unwrap();
+/// ```
+pub fn put_iv_rational(
+    price: f64,
+    s: f64,
+    k: f64,
+    rate: f64,
+    maturity: f64,
+) -> Result<f64, IvError> {
+    check_iv_inputs(price, s, k, rate, 0.0, maturity, false)?;
+    let discount = (-rate * maturity).exp();
+    lets_be_rational::implied_black_volatility(price / discount, s / discount, k, maturity, false)
+        .map_err(|error| discounted_iv_error(error, discount))
 }
 
 #[derive(Debug, Serialize)]
@@ -624,6 +1339,32 @@ pub struct PricesAndGreeks {
     pub put_vomma: f64,
     pub put_charm: f64,
 }
+
+/// Prices and greeks of call and put options on two underlyings.  Gammas
+/// and vegas are per underlying, `cross_gamma` is the mixed second
+/// derivative and `correlation_sensitivity` the derivative with respect to
+/// the correlation between the two.
+#[derive(Debug, Serialize)]
+pub struct TwoAssetPricesAndGreeks {
+    pub call_price: f64,
+    pub call_delta_1: f64,
+    pub call_delta_2: f64,
+    pub call_gamma_1: f64,
+    pub call_gamma_2: f64,
+    pub call_cross_gamma: f64,
+    pub call_vega_1: f64,
+    pub call_vega_2: f64,
+    pub call_correlation_sensitivity: f64,
+    pub put_price: f64,
+    pub put_delta_1: f64,
+    pub put_delta_2: f64,
+    pub put_gamma_1: f64,
+    pub put_gamma_2: f64,
+    pub put_cross_gamma: f64,
+    pub put_vega_1: f64,
+    pub put_vega_2: f64,
+    pub put_correlation_sensitivity: f64,
+}
 /// Returns call and put prices and greeks.
 /// Due to caching the complex computations
 /// (such as N(d1)), this implementation is
@@ -684,7 +1425,7 @@ pub fn compute_all(
         let put_theta =
             -stock * pdf_d1 * sigma / (2.0 * sqrt_maturity) + rate * k_discount * (1.0 - cdf_d2);
         let put_vega = call_vega;
-        let put_rho = -1.0 * k_discount * maturity * (1.0 - cdf_d2);
+        let put_rho = -k_discount * maturity * (1.0 - cdf_d2);
         let put_vanna = call_vanna;
         let put_vomma = call_vomma;
         let put_charm = call_charm;
@@ -939,6 +1680,131 @@ pub fn black76(
     }
 }
 
+/// Returns implied volatility from a call option on a forward
+/// (Black-76 model) with initial guess
+///
+/// # Examples
+///
+/// ```
+/// let price = 6.2;
+/// let forward = 55.0;
+/// let strike = 50.0;
+/// let rate = 0.0025;
+/// let maturity = 1.0;
+/// let initial_guess = 0.3;
+/// let iv = black_scholes::black76_call_iv_guess(
+///     price, forward, strike, rate,
+///     maturity, initial_guess
+/// ).unwrap();
+/// ```
+pub fn black76_call_iv_guess(
+    price: f64,
+    forward_price: f64,
+    strike: f64,
+    rate: f64,
+    maturity: f64,
+    initial_guess: f64,
+) -> Result<f64, IvError> {
+    // discounting the forward at `rate` gives bounds D·max(F - K, 0) and D·F
+    check_iv_inputs(price, forward_price, strike, rate, rate, maturity, true)?;
+    let f_discount = forward_price * (-rate * maturity).exp();
+    let obj_fn = |sigma| call(f_discount, strike, rate, sigma, maturity) - price;
+    let dfn = |sigma| call_vega(f_discount, strike, rate, sigma, maturity);
+    newton_iv(&obj_fn, &dfn, initial_guess)
+}
+
+/// Returns implied volatility from a call option on a forward
+/// (Black-76 model).  The initial guess is computed from the forward.
+///
+/// # Examples
+///
+/// ```
+/// let price = 6.2;
+/// let forward = 55.0;
+/// let strike = 50.0;
+/// let rate = 0.0025;
+/// let maturity = 1.0;
+/// let iv = black_scholes::black76_call_iv(
+///     price, forward, strike, rate,
+///     maturity
+/// ).unwrap();
+/// ```
+pub fn black76_call_iv(
+    price: f64,
+    forward_price: f64,
+    strike: f64,
+    rate: f64,
+    maturity: f64,
+) -> Result<f64, IvError> {
+    check_iv_inputs(price, forward_price, strike, rate, rate, maturity, true)?;
+    let discount = (-rate * maturity).exp();
+    let initial_guess = approximate_forward_vol(price / discount, forward_price, strike, maturity);
+    black76_call_iv_guess(price, forward_price, strike, rate, maturity, initial_guess)
+}
+
+/// Returns implied volatility from a put option on a forward
+/// (Black-76 model) with initial guess
+///
+/// # Examples
+///
+/// ```
+/// let price = 1.2;
+/// let forward = 55.0;
+/// let strike = 50.0;
+/// let rate = 0.0025;
+/// let maturity = 1.0;
+/// let initial_guess = 0.3;
+/// let iv = black_scholes::black76_put_iv_guess(
+///     price, forward, strike, rate,
+///     maturity, initial_guess
+/// ).unwrap();
+/// ```
+pub fn black76_put_iv_guess(
+    price: f64,
+    forward_price: f64,
+    strike: f64,
+    rate: f64,
+    maturity: f64,
+    initial_guess: f64,
+) -> Result<f64, IvError> {
+    check_iv_inputs(price, forward_price, strike, rate, rate, maturity, false)?;
+    let f_discount = forward_price * (-rate * maturity).exp();
+    let obj_fn = |sigma| put(f_discount, strike, rate, sigma, maturity) - price;
+    let dfn = |sigma| put_vega(f_discount, strike, rate, sigma, maturity);
+    newton_iv(&obj_fn, &dfn, initial_guess)
+}
+
+/// Returns implied volatility from a put option on a forward
+/// (Black-76 model).  The initial guess is computed from the forward.
+///
+/// # Examples
+///
+/// ```
+/// let price = 1.2;
+/// let forward = 55.0;
+/// let strike = 50.0;
+/// let rate = 0.0025;
+/// let maturity = 1.0;
+/// let iv = black_scholes::black76_put_iv(
+///     price, forward, strike, rate,
+///     maturity
+/// ).unwrap();
+/// ```
+pub fn black76_put_iv(
+    price: f64,
+    forward_price: f64,
+    strike: f64,
+    rate: f64,
+    maturity: f64,
+) -> Result<f64, IvError> {
+    check_iv_inputs(price, forward_price, strike, rate, rate, maturity, false)?;
+    let discount = (-rate * maturity).exp();
+    // convert to the equivalent undiscounted call price via put-call parity
+    let c_price = price / discount + forward_price - strike;
+    let initial_guess = approximate_forward_vol(c_price, forward_price, strike, maturity);
+    black76_put_iv_guess(price, forward_price, strike, rate, maturity, initial_guess)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1099,6 +1965,193 @@ mod tests {
         );
     }
     #[test]
+    fn call_iv_rational_works() {
+        let sigma = 0.2;
+        let s = 5.0;
+        let k = 4.5;
+        let rate = 0.05;
+        let maturity = 1.0;
+        let price = call(s, k, rate, sigma, maturity);
+        assert_abs_diff_eq!(
+            call_iv_rational(price, s, k, rate, maturity).unwrap(),
+            sigma,
+            epsilon = 0.00000000001
+        );
+    }
+    #[test]
+    fn iv_rational_errors_are_in_discounted_prices() {
+        let (s, k, rate, maturity): (f64, f64, f64, f64) = (100.0, 100.0, 0.03, 10.0);
+        let discount = (-rate * maturity).exp();
+        let intrinsic = s - k * discount;
+        match call_iv_rational(intrinsic, s, k, rate, maturity) {
+            Err(IvError::PriceBelowIntrinsic {
+                price,
+                intrinsic: reported,
+            }) => {
+                assert_relative_eq!(price, intrinsic, max_relative = 1e-15);
+                assert_relative_eq!(reported, intrinsic, max_relative = 1e-15);
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+        match put_iv_rational(k * discount, s, k, rate, maturity) {
+            Err(IvError::PriceAboveUpperBound { price, upper_bound }) => {
+                assert_relative_eq!(price, k * discount, max_relative = 1e-15);
+                assert_relative_eq!(upper_bound, k * discount, max_relative = 1e-15);
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+    #[test]
+    fn put_iv_rational_works() {
+        let sigma = 0.2;
+        let s = 5.0;
+        let k = 4.5;
+        let rate = 0.05;
+        let maturity = 1.0;
+        let price = put(s, k, rate, sigma, maturity);
+        assert_abs_diff_eq!(
+            put_iv_rational(price, s, k, rate, maturity).unwrap(),
+            sigma,
+            epsilon = 0.00000000001
+        );
+    }
+    #[test]
+    fn call_iv_rational_works_deep_otm_and_short_dated() {
+        let s = 100.0f64;
+        let rate = 0.03f64;
+        for &(k, sigma, maturity) in &[
+            (300.0f64, 0.25f64, 1.0f64),
+            (105.0, 0.2, 1.0 / 365.0),
+            (130.0, 0.6, 2.0 / 365.0),
+            (250.0, 0.15, 5.0),
+        ] {
+            // `call` loses precision this far out of the money, so price from the normalised formula
+            let discount = (-rate * maturity).exp();
+            let forward = s / discount;
+            let price = discount
+                * (forward * k).sqrt()
+                * lets_be_rational::normalised_black_call(
+                    (forward / k).ln(),
+                    sigma * maturity.sqrt(),
+                );
+            assert_relative_eq!(
+                call_iv_rational(price, s, k, rate, maturity).unwrap(),
+                sigma,
+                max_relative = 1e-12
+            );
+        }
+    }
+    #[test]
+    fn call_iv_rational_works_with_broad_set_of_numbers() {
+        let seed: [u8; 32] = [2; 32];
+        let mut rng_seed = get_rng_seed(seed);
+        let uniform = Uniform::new(0.0f64, 1.0).unwrap();
+        let num_total: usize = 10000;
+
+        (0..num_total).for_each(|_| {
+            let s = 1.0;
+            let k = get_over_region(0.3, 3.0, uniform.sample(&mut rng_seed));
+            let sigma = get_over_region(0.1, 2.0, uniform.sample(&mut rng_seed));
+            let rate = 0.0247;
+            let maturity = 0.7599;
+            let price = call(s, k, rate, sigma, maturity);
+            let discount = (-rate * maturity).exp();
+            let cutoff = 0.000001;
+            if price - max_or_zero(s - k * discount) > cutoff && s - price > cutoff {
+                let iv = call_iv_rational(price, s, k, rate, maturity).unwrap();
+                assert_abs_diff_eq!(iv, sigma, epsilon = 0.0000001);
+            }
+        })
+    }
+    #[test]
+    fn call_iv_rational_returns_err_outside_bounds() {
+        let s = 274.525;
+        let k = 225.000;
+        let rate = 0.0244;
+        let maturity = 0.156;
+        assert!(matches!(
+            call_iv_rational(50.275, s, k, rate, maturity),
+            Err(IvError::PriceBelowIntrinsic { .. })
+        ));
+        assert!(matches!(
+            call_iv_rational(s, s, k, rate, maturity),
+            Err(IvError::PriceAboveUpperBound { .. })
+        ));
+    }
+    #[test]
+    fn bsm_call_iv_works() {
+        let sigma = 0.37;
+        let s = 550.88;
+        let k = 510.0;
+        let rate = 0.05;
+        let q = 0.03;
+        let maturity = 0.09;
+        let price = bsm_compute_all(s, k, sigma, rate, q, maturity).call_price;
+        assert_abs_diff_eq!(
+            bsm_call_iv(price, s, k, rate, q, maturity).unwrap(),
+            sigma,
+            epsilon = 0.00000001
+        );
+        assert_abs_diff_eq!(
+            bsm_call_iv_guess(price, s, k, rate, q, maturity, 0.5).unwrap(),
+            sigma,
+            epsilon = 0.00000001
+        );
+    }
+    #[test]
+    fn bsm_put_iv_works() {
+        let sigma = 0.37;
+        let s = 550.88;
+        let k = 510.0;
+        let rate = 0.05;
+        let q = 0.03;
+        let maturity = 0.09;
+        let price = bsm_compute_all(s, k, sigma, rate, q, maturity).put_price;
+        assert_abs_diff_eq!(
+            bsm_put_iv(price, s, k, rate, q, maturity).unwrap(),
+            sigma,
+            epsilon = 0.00000001
+        );
+        assert_abs_diff_eq!(
+            bsm_put_iv_guess(price, s, k, rate, q, maturity, 0.5).unwrap(),
+            sigma,
+            epsilon = 0.00000001
+        );
+    }
+    #[test]
+    fn bsm_iv_differs_from_call_iv_with_dividends() {
+        let sigma = 0.25;
+        let s = 100.0;
+        let k = 100.0;
+        let rate = 0.04;
+        let q = 0.06;
+        let maturity = 2.0;
+        let price = bsm_compute_all(s, k, sigma, rate, q, maturity).call_price;
+        let biased = call_iv(price, s, k, rate, maturity).unwrap();
+        assert!(biased < sigma - 0.01);
+        assert_abs_diff_eq!(
+            bsm_call_iv(price, s, k, rate, q, maturity).unwrap(),
+            sigma,
+            epsilon = 0.00000001
+        );
+    }
+    #[test]
+    fn bsm_iv_upper_bound_uses_dividend_adjusted_stock() {
+        let s = 100.0;
+        let k = 100.0;
+        let rate = 0.04;
+        let q: f64 = 0.06;
+        let maturity = 2.0;
+        let upper_bound = s * (-q * maturity).exp();
+        assert_eq!(
+            bsm_call_iv(upper_bound, s, k, rate, q, maturity),
+            Err(IvError::PriceAboveUpperBound {
+                price: upper_bound,
+                upper_bound
+            })
+        );
+    }
+    #[test]
     fn call_iv_returns_err_if_no_possible_solution() {
         let price = 50.275;
         let s = 274.525;
@@ -1108,6 +2161,83 @@ mod tests {
         assert!(call_iv(price, s, k, rate, maturity).is_err());
     }
 
+    #[test]
+    fn call_iv_returns_price_below_intrinsic() {
+        let price = 50.275;
+        let s = 274.525;
+        let k = 225.000;
+        let rate = 0.0244;
+        let maturity = 0.156;
+        match call_iv(price, s, k, rate, maturity) {
+            Err(IvError::PriceBelowIntrinsic {
+                price: p,
+                intrinsic,
+            }) => {
+                assert_eq!(p, price);
+                assert_approx_eq!(intrinsic, s - k * (-rate * maturity).exp());
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+    #[test]
+    fn iv_returns_price_above_upper_bound() {
+        let s = 5.0;
+        let k = 4.5;
+        let rate = 0.05;
+        let maturity = 1.0;
+        assert_eq!(
+            call_iv(5.0, s, k, rate, maturity),
+            Err(IvError::PriceAboveUpperBound {
+                price: 5.0,
+                upper_bound: s
+            })
+        );
+        assert!(matches!(
+            put_iv_guess(4.4, s, k, rate, maturity, 0.3),
+            Err(IvError::PriceAboveUpperBound { .. })
+        ));
+    }
+    #[test]
+    fn iv_returns_non_positive_maturity() {
+        assert_eq!(
+            call_iv(1.0, 5.0, 4.5, 0.05, 0.0),
+            Err(IvError::NonPositiveMaturity(0.0))
+        );
+        assert_eq!(
+            put_iv(0.3, 5.0, 4.5, 0.05, -1.0),
+            Err(IvError::NonPositiveMaturity(-1.0))
+        );
+    }
+    #[test]
+    fn iv_returns_invalid_input() {
+        assert_eq!(
+            call_iv(f64::NAN, 5.0, 4.5, 0.05, 1.0),
+            Err(IvError::InvalidInput)
+        );
+        assert_eq!(
+            put_iv(0.3, -5.0, 4.5, 0.05, 1.0),
+            Err(IvError::InvalidInput)
+        );
+        assert_eq!(
+            call_iv_guess(1.0, 5.0, 4.5, 0.05, 1.0, -0.3),
+            Err(IvError::InvalidInput)
+        );
+    }
+    #[test]
+    fn iv_returns_no_convergence() {
+        // a guess this far out gives zero vega, so Newton never moves
+        match call_iv_guess(1.0, 5.0, 4.5, 0.05, 1.0, 1.0e10) {
+            Err(IvError::NoConvergence {
+                iterations,
+                residual,
+            }) => {
+                assert_eq!(iterations, IV_ITERATIONS as usize);
+                assert!(residual.is_nan() || residual.abs() > 0.0);
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
     #[test]
     fn put_greeks_work() {
         let s = 550.88;
@@ -1443,6 +2573,89 @@ mod tests {
         assert_approx_eq!(put_charm, -0.086042); // value not verified externally :(
     }
 
+    #[test]
+    fn black76_iv_works() {
+        let f = 55.;
+        let k = 50.0;
+        let maturity = 1.0;
+        let sigma = 0.15;
+        let rate = 0.0025;
+        let PricesAndGreeks {
+            call_price,
+            put_price,
+            ..
+        } = black76(f, k, rate, sigma, maturity);
+        assert_abs_diff_eq!(
+            black76_call_iv(call_price, f, k, rate, maturity).unwrap(),
+            sigma,
+            epsilon = 0.00000001
+        );
+        assert_abs_diff_eq!(
+            black76_put_iv(put_price, f, k, rate, maturity).unwrap(),
+            sigma,
+            epsilon = 0.00000001
+        );
+        assert_abs_diff_eq!(
+            black76_call_iv_guess(call_price, f, k, rate, maturity, 0.5).unwrap(),
+            sigma,
+            epsilon = 0.00000001
+        );
+        assert_abs_diff_eq!(
+            black76_put_iv_guess(put_price, f, k, rate, maturity, 0.5).unwrap(),
+            sigma,
+            epsilon = 0.00000001
+        );
+    }
+    #[test]
+    fn black76_iv_approx() {
+        let f = 55.;
+        let k = 50.0;
+        let maturity = 1.0;
+        let sigma = 0.15;
+        let rate: f64 = 0.05;
+        let discount = (-rate * maturity).exp();
+        let price = black76(f, k, rate, sigma, maturity).call_price;
+        let approx_vol = approximate_forward_vol(price / discount, f, k, maturity);
+        assert_abs_diff_eq!(sigma, approx_vol, epsilon = 0.01);
+    }
+    #[test]
+    fn black76_iv_works_with_broad_set_of_numbers() {
+        let seed: [u8; 32] = [2; 32];
+        let mut rng_seed = get_rng_seed(seed);
+        let uniform = Uniform::new(0.0f64, 1.0).unwrap();
+        let num_total: usize = 10000;
+
+        (0..num_total).for_each(|_| {
+            let f = 1.0;
+            let k = get_over_region(0.5, 2.0, uniform.sample(&mut rng_seed));
+            let sigma = get_over_region(0.1, 1.0, uniform.sample(&mut rng_seed));
+            let rate = 0.0247;
+            let maturity = 0.7599;
+            let price = black76(f, k, rate, sigma, maturity).put_price;
+            let discount = (-rate * maturity).exp();
+            let cutoff = 0.000001;
+            if price - discount * max_or_zero(k - f) > cutoff {
+                let iv = black76_put_iv(price, f, k, rate, maturity).unwrap();
+                assert_abs_diff_eq!(iv, sigma, epsilon = 0.000001);
+            }
+        })
+    }
+    #[test]
+    fn black76_iv_returns_price_above_discounted_forward() {
+        let f = 55.;
+        let k = 50.0;
+        let maturity = 1.0;
+        let rate: f64 = 0.05;
+        let upper_bound = f * (-rate * maturity).exp();
+        assert_eq!(
+            black76_call_iv(upper_bound, f, k, rate, maturity),
+            Err(IvError::PriceAboveUpperBound {
+                price: upper_bound,
+                upper_bound
+            })
+        );
+    }
+
     #[test]
     fn call_delta_with_negative_maturity_works() {
         let s = 550.88;
@@ -1547,4 +2760,141 @@ mod tests {
         assert_approx_eq!(call_charm(s, k, rate, sigma, maturity), 0.0); // TODO: check that this is true....
         assert_approx_eq!(put_charm(s, k, rate, sigma, maturity), 0.0);
     }
+
+    const FX_CONVENTIONS: [FxDeltaConvention; 4] = [
+        FxDeltaConvention::Spot,
+        FxDeltaConvention::Forward,
+        FxDeltaConvention::PremiumAdjustedSpot,
+        FxDeltaConvention::PremiumAdjustedForward,
+    ];
+
+    #[test]
+    fn fx_delta_matches_garman_kohlhagen() {
+        let (spot, strike, sigma, rd, rf, maturity) = (1.085, 1.1, 0.08, 0.05, 0.03, 0.5);
+        let domestic = garman_kohlhagen::compute_all(spot, strike, sigma, rd, rf, maturity);
+        let foreign =
+            garman_kohlhagen::compute_all_foreign_premium(spot, strike, sigma, rd, rf, maturity);
+        let spot_delta =
+            |convention| fx_call_delta(spot, strike, sigma, rd, rf, maturity, convention);
+        assert_abs_diff_eq!(
+            spot_delta(FxDeltaConvention::Spot),
+            domestic.call_delta,
+            epsilon = 1e-14
+        );
+        assert_abs_diff_eq!(
+            spot_delta(FxDeltaConvention::PremiumAdjustedSpot),
+            foreign.call_delta,
+            epsilon = 1e-14
+        );
+        assert_abs_diff_eq!(
+            fx_put_delta(
+                spot,
+                strike,
+                sigma,
+                rd,
+                rf,
+                maturity,
+                FxDeltaConvention::PremiumAdjustedSpot
+            ),
+            foreign.put_delta,
+            epsilon = 1e-14
+        );
+        let rf_discount: f64 = (-rf * maturity).exp();
+        assert_abs_diff_eq!(
+            fx_put_delta(
+                spot,
+                strike,
+                sigma,
+                rd,
+                rf,
+                maturity,
+                FxDeltaConvention::Forward
+            ),
+            domestic.put_delta / rf_discount,
+            epsilon = 1e-14
+        );
+    }
+    #[test]
+    fn fx_strike_from_delta_round_trips() {
+        let (spot, sigma, rd, rf) = (1.3465, 0.15, 0.0294, 0.0346);
+        for &maturity in &[0.02, 1.0, 5.0] {
+            for convention in FX_CONVENTIONS {
+                for &delta in &[0.1, 0.25, 0.4] {
+                    let strike =
+                        fx_call_strike_from_delta(delta, spot, sigma, rd, rf, maturity, convention)
+                            .unwrap();
+                    assert_abs_diff_eq!(
+                        fx_call_delta(spot, strike, sigma, rd, rf, maturity, convention),
+                        delta,
+                        epsilon = 1e-12
+                    );
+                    let strike =
+                        fx_put_strike_from_delta(-delta, spot, sigma, rd, rf, maturity, convention)
+                            .unwrap();
+                    assert_abs_diff_eq!(
+                        fx_put_delta(spot, strike, sigma, rd, rf, maturity, convention),
+                        -delta,
+                        epsilon = 1e-12
+                    );
+                }
+            }
+        }
+    }
+    #[test]
+    fn premium_adjusted_call_strike_is_above_the_peak() {
+        // with high volatility a 10 delta premium-adjusted call has two strikes
+        let (spot, sigma, rd, rf, maturity) = (100.0, 1.0, 0.0, 0.0, 2.0);
+        let convention = FxDeltaConvention::PremiumAdjustedForward;
+        let strike =
+            fx_call_strike_from_delta(0.1, spot, sigma, rd, rf, maturity, convention).unwrap();
+        let delta = |strike| fx_call_delta(spot, strike, sigma, rd, rf, maturity, convention);
+        assert!(delta(strike * 1.001) < 0.1);
+        assert!(delta(strike * 0.999) > 0.1);
+        assert!(delta(spot * 0.05) < 0.1);
+    }
+    #[test]
+    fn fx_strike_from_delta_returns_none_if_unattainable() {
+        let (spot, sigma, rd, rf, maturity) = (100.0, 1.0, 0.0, 0.0, 2.0);
+        assert_eq!(
+            fx_call_strike_from_delta(
+                0.45,
+                spot,
+                sigma,
+                rd,
+                rf,
+                maturity,
+                FxDeltaConvention::PremiumAdjustedForward
+            ),
+            None
+        );
+        assert_eq!(
+            fx_call_strike_from_delta(
+                1.2,
+                spot,
+                sigma,
+                rd,
+                rf,
+                maturity,
+                FxDeltaConvention::Forward
+            ),
+            None
+        );
+        assert_eq!(
+            fx_put_strike_from_delta(0.25, spot, sigma, rd, rf, maturity, FxDeltaConvention::Spot),
+            None
+        );
+    }
+    #[test]
+    fn fx_atm_dns_strike_is_delta_neutral() {
+        let (spot, sigma, rd, rf, maturity) = (1.3465, 0.15, 0.0294, 0.0346, 2.0);
+        for convention in FX_CONVENTIONS {
+            let strike = fx_atm_dns_strike(spot, sigma, rd, rf, maturity, convention);
+            assert_abs_diff_eq!(
+                fx_call_delta(spot, strike, sigma, rd, rf, maturity, convention)
+                    + fx_put_delta(spot, strike, sigma, rd, rf, maturity, convention),
+                0.0,
+                epsilon = 1e-14
+            );
+        }
+    }
 }
@@ -0,0 +1,339 @@
+//! Longstaff-Schwartz least-squares Monte Carlo for options that can be
+//! exercised on a schedule of dates.
+//!
+//! Geometric Brownian motion paths are simulated at the exercise dates
+//! only, so the dates need not be equally spaced.  Working back from
+//! maturity, the discounted future cash flows of the in-the-money paths are
+//! regressed on basis functions of the stock price to estimate the
+//! continuation value, and each path is exercised where the exercise value
+//! exceeds it.
+use crate::monte_carlo::{MonteCarlo, MonteCarloEstimate, RandomNumbers, adjusted_estimate};
+use serde::Serialize;
+
+/// Basis functions of `x = S/S₀` for the continuation value regression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Basis {
+    /// `1, e^{-x/2}L₀(x), …, e^{-x/2}L_{n-1}(x)` for degree `n`
+    Laguerre,
+    /// `1, x, …, xⁿ` for degree `n`
+    Monomial,
+}
+
+impl Basis {
+    fn evaluate(self, x: f64, degree: usize, values: &mut Vec<f64>) {
+        values.clear();
+        values.push(1.0);
+        match self {
+            Basis::Monomial => {
+                for k in 1..=degree {
+                    values.push(x.powi(k as i32));
+                }
+            }
+            Basis::Laguerre => {
+                let weight = (-0.5 * x).exp();
+                let (mut previous, mut current) = (0.0, 1.0);
+                for k in 0..degree {
+                    values.push(weight * current);
+                    let next = ((2 * k + 1) as f64 - x) * current - k as f64 * previous;
+                    previous = current;
+                    current = next / (k + 1) as f64;
+                }
+            }
+        }
+    }
+}
+
+// Solves the normal equations for the least-squares coefficients by
+// Gaussian elimination with partial pivoting
+fn least_squares(rows: &[Vec<f64>], targets: &[f64]) -> Vec<f64> {
+    let n = rows[0].len();
+    let mut matrix = vec![vec![0.0; n + 1]; n];
+    for (row, target) in rows.iter().zip(targets) {
+        for i in 0..n {
+            for j in 0..n {
+                matrix[i][j] += row[i] * row[j];
+            }
+            matrix[i][n] += row[i] * target;
+        }
+    }
+    for column in 0..n {
+        let pivot = (column..n)
+            .max_by(|&a, &b| matrix[a][column].abs().total_cmp(&matrix[b][column].abs()))
+            .unwrap();
+        matrix.swap(column, pivot);
+        if matrix[column][column] == 0.0 {
+            continue;
+        }
+        let (pivot_rows, rows_below) = matrix.split_at_mut(column + 1);
+        let pivot_row = &pivot_rows[column];
+        for row in rows_below {
+            let factor = row[column] / pivot_row[column];
+            for (value, pivot) in row.iter_mut().zip(pivot_row).skip(column) {
+                *value -= factor * pivot;
+            }
+        }
+    }
+    let mut coefficients = vec![0.0; n];
+    for i in (0..n).rev() {
+        if matrix[i][i] == 0.0 {
+            continue;
+        }
+        let sum: f64 = (i + 1..n).map(|j| matrix[i][j] * coefficients[j]).sum();
+        coefficients[i] = (matrix[i][n] - sum) / matrix[i][i];
+    }
+    coefficients
+}
+
+/// Least-squares Monte Carlo pricer.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::longstaff_schwartz::{Basis, LongstaffSchwartz};
+/// let strike = 40.0;
+/// let exercise_dates: Vec<f64> = (1..=50).map(|i| i as f64 / 50.0).collect();
+/// let pricer = LongstaffSchwartz::new(10_000, 42, exercise_dates, Basis::Laguerre, 3)
+///     .unwrap()
+///     .with_antithetic_variates();
+/// let estimate = pricer.price(|s| (strike - s).max(0.0), 36.0, 0.2, 0.06, 0.0);
+/// assert!(estimate.price > black_scholes::put(36.0, strike, 0.06, 0.2, 1.0));
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LongstaffSchwartz {
+    engine: MonteCarlo,
+    exercise_dates: Vec<f64>,
+    basis: Basis,
+    degree: usize,
+}
+
+impl LongstaffSchwartz {
+    /// Returns a pricer drawing `paths` sets of normal variates.  The last
+    /// of the `exercise_dates` (in years) is maturity.
+    ///
+    /// Returns `None` unless `exercise_dates` are non-empty, positive and
+    /// strictly increasing.
+    pub fn new(
+        paths: usize,
+        seed: u64,
+        exercise_dates: Vec<f64>,
+        basis: Basis,
+        degree: usize,
+    ) -> Option<Self> {
+        let increasing = exercise_dates.windows(2).all(|pair| pair[0] < pair[1]);
+        if !increasing || !exercise_dates.first().is_some_and(|&first| first > 0.0) {
+            return None;
+        }
+        Some(LongstaffSchwartz {
+            engine: MonteCarlo::new(paths, exercise_dates.len(), seed),
+            exercise_dates,
+            basis,
+            degree,
+        })
+    }
+    pub fn with_antithetic_variates(self) -> Self {
+        LongstaffSchwartz {
+            engine: self.engine.with_antithetic_variates(),
+            ..self
+        }
+    }
+    pub fn with_random_numbers(self, random_numbers: RandomNumbers) -> Self {
+        LongstaffSchwartz {
+            engine: self.engine.with_random_numbers(random_numbers),
+            ..self
+        }
+    }
+
+    /// Returns the value of receiving `exercise_value(S)` on the optimal
+    /// exercise date.  The standard error is that of the in-sample paths
+    /// and does not include the regression bias.
+    pub fn price(
+        &self,
+        exercise_value: impl Fn(f64) -> f64,
+        stock: f64,
+        sigma: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+    ) -> MonteCarloEstimate {
+        let dates = &self.exercise_dates;
+        let drift = risk_free_rate - dividend_yield - 0.5 * sigma * sigma;
+        let steps: Vec<(f64, f64)> = dates
+            .iter()
+            .scan(0.0, |previous, &date| {
+                let dt = date - *previous;
+                *previous = date;
+                Some((drift * dt, sigma * dt.sqrt()))
+            })
+            .collect();
+        let simulate = |normals: &[f64], sign: f64| -> Vec<f64> {
+            steps
+                .iter()
+                .zip(normals)
+                .scan(stock, |s, (&(mean, volatility), normal)| {
+                    *s *= (mean + sign * volatility * normal).exp();
+                    Some(*s)
+                })
+                .collect()
+        };
+        let mut paths: Vec<Vec<f64>> = Vec::new();
+        let mut batches: Vec<usize> = Vec::new();
+        let antithetic = self.engine.is_antithetic();
+        self.engine.for_each_draw(|batch, normals| {
+            paths.push(simulate(normals, 1.0));
+            if antithetic {
+                paths.push(simulate(normals, -1.0));
+            }
+            batches.push(batch);
+        });
+
+        // cash flow of each path and the index of the date it is received
+        let last = dates.len() - 1;
+        let mut cash_flows: Vec<(f64, usize)> = paths
+            .iter()
+            .map(|path| (exercise_value(path[last]), last))
+            .collect();
+        let mut basis_values = Vec::new();
+        for date in (0..last).rev() {
+            let mut rows = Vec::new();
+            let mut targets = Vec::new();
+            let mut in_the_money = Vec::new();
+            for (index, path) in paths.iter().enumerate() {
+                let value = exercise_value(path[date]);
+                if value > 0.0 {
+                    let (cash_flow, paid) = cash_flows[index];
+                    targets.push(cash_flow * (-risk_free_rate * (dates[paid] - dates[date])).exp());
+                    self.basis
+                        .evaluate(path[date] / stock, self.degree, &mut basis_values);
+                    rows.push(basis_values.clone());
+                    in_the_money.push((index, value));
+                }
+            }
+            if rows.len() <= self.degree + 1 {
+                continue;
+            }
+            let coefficients = least_squares(&rows, &targets);
+            for (row, (index, value)) in rows.iter().zip(in_the_money) {
+                let continuation: f64 = row.iter().zip(&coefficients).map(|(b, c)| b * c).sum();
+                if value > continuation {
+                    cash_flows[index] = (value, date);
+                }
+            }
+        }
+
+        let present_value =
+            |(cash_flow, paid): (f64, usize)| cash_flow * (-risk_free_rate * dates[paid]).exp();
+        let per_draw = if antithetic { 2 } else { 1 };
+        let samples: Vec<(usize, f64, f64)> = cash_flows
+            .chunks(per_draw)
+            .zip(batches)
+            .map(|(flows, batch)| {
+                let value =
+                    flows.iter().map(|&flow| present_value(flow)).sum::<f64>() / per_draw as f64;
+                (batch, value, 0.0)
+            })
+            .collect();
+        let batch_count = samples.iter().map(|s| s.0).max().unwrap_or(0) + 1;
+        adjusted_estimate(&samples, 0.0, batch_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lattice::{Exercise, Tree, TreeKind};
+    use crate::put;
+    use approx::*;
+
+    #[test]
+    fn least_squares_recovers_polynomial() {
+        let rows: Vec<Vec<f64>> = (0..10)
+            .map(|i| {
+                let x = i as f64 / 10.0;
+                vec![1.0, x, x * x]
+            })
+            .collect();
+        let targets: Vec<f64> = rows.iter().map(|r| 2.0 - 3.0 * r[1] + 0.5 * r[2]).collect();
+        let coefficients = least_squares(&rows, &targets);
+        assert_abs_diff_eq!(coefficients[0], 2.0, epsilon = 1e-10);
+        assert_abs_diff_eq!(coefficients[1], -3.0, epsilon = 1e-10);
+        assert_abs_diff_eq!(coefficients[2], 0.5, epsilon = 1e-10);
+    }
+    #[test]
+    fn laguerre_polynomials() {
+        let mut values = Vec::new();
+        let x: f64 = 0.7;
+        Basis::Laguerre.evaluate(x, 3, &mut values);
+        let weight = (-0.5 * x).exp();
+        assert_abs_diff_eq!(values[0], 1.0);
+        assert_abs_diff_eq!(values[1], weight, epsilon = 1e-15);
+        assert_abs_diff_eq!(values[2], weight * (1.0 - x), epsilon = 1e-15);
+        assert_abs_diff_eq!(
+            values[3],
+            weight * (1.0 - 2.0 * x + 0.5 * x * x),
+            epsilon = 1e-15
+        );
+    }
+    #[test]
+    fn bermudan_put_matches_tree() {
+        let (s, k, sigma, rate, maturity) = (100.0, 110.0, 0.3, 0.06, 1.0);
+        let dates = vec![0.25, 0.5, 0.75, maturity];
+        let tree = Tree::new(TreeKind::CoxRossRubinstein, 2000)
+            .with_exercise(Exercise::Bermudan(dates.clone()))
+            .put(s, k, sigma, rate, 0.0, maturity)
+            .price;
+        for basis in [Basis::Laguerre, Basis::Monomial] {
+            let estimate = LongstaffSchwartz::new(50_000, 1, dates.clone(), basis, 3)
+                .unwrap()
+                .with_antithetic_variates()
+                .price(|x| (k - x).max(0.0), s, sigma, rate, 0.0);
+            assert!(
+                (estimate.price - tree).abs() < 4.0 * estimate.standard_error + 0.02,
+                "{:?} {} {}",
+                basis,
+                estimate.price,
+                tree
+            );
+        }
+    }
+    #[test]
+    fn american_put_matches_tree() {
+        // Longstaff and Schwartz (2001) table 1 example, with 50 exercise
+        // dates a year
+        let (s, k, sigma, rate, maturity) = (36.0, 40.0, 0.2, 0.06, 1.0);
+        let dates: Vec<f64> = (1..=50).map(|i| i as f64 / 50.0).collect();
+        let tree = Tree::new(TreeKind::LeisenReimer, 1001)
+            .with_exercise(Exercise::American)
+            .put(s, k, sigma, rate, 0.0, maturity)
+            .price;
+        let estimate = LongstaffSchwartz::new(20_000, 2, dates, Basis::Laguerre, 3)
+            .unwrap()
+            .with_random_numbers(RandomNumbers::Sobol)
+            .price(|x| (k - x).max(0.0), s, sigma, rate, 0.0);
+        assert!(estimate.price > put(s, k, rate, sigma, maturity));
+        // exercising on 50 dates rather than continuously and the sub-optimal
+        // regression rule both bias the estimate down slightly
+        assert!((estimate.price - tree).abs() < 4.0 * estimate.standard_error + 0.03);
+    }
+    #[test]
+    fn single_date_is_european() {
+        let (s, k, sigma, rate, maturity) = (100.0, 100.0, 0.2, 0.05, 0.5);
+        let estimate = LongstaffSchwartz::new(20_000, 3, vec![maturity], Basis::Monomial, 2)
+            .unwrap()
+            .price(|x| (k - x).max(0.0), s, sigma, rate, 0.0);
+        assert!(
+            (estimate.price - put(s, k, rate, sigma, maturity)).abs()
+                < 4.0 * estimate.standard_error
+        );
+    }
+    #[test]
+    fn rejects_invalid_schedules() {
+        for dates in [
+            vec![],
+            vec![0.0, 1.0],
+            vec![0.5, 0.5],
+            vec![1.0, 0.5],
+            vec![f64::NAN],
+        ] {
+            assert!(LongstaffSchwartz::new(100, 1, dates, Basis::Laguerre, 2).is_none());
+        }
+    }
+}
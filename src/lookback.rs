@@ -0,0 +1,421 @@
+//! Lookback options on the extremes of the stock price: floating strike
+//! (Goldman, Sosin and Gatto, 1979) and fixed strike (Conze and
+//! Viswanathan, 1991).
+//!
+//! Each function takes the running minimum or maximum observed so far; for
+//! a new option this is today's stock price.  Discretely monitored
+//! extremes are priced with the Broadie, Glasserman and Kou (1999) shift,
+//! which treats the discrete maximum as the continuous maximum scaled by
+//! `e^{-0.5826σ√Δt}` (and the minimum by `e^{0.5826σ√Δt}`).
+use crate::barrier::BGK_BETA;
+use crate::stats::log_cum_norm;
+use crate::{cum_norm, inc_norm};
+use serde::Serialize;
+
+// Below this cost of carry the b → 0 limit of the formulas is used
+const ZERO_CARRY: f64 = 1e-8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum Monitoring {
+    Continuous,
+    /// Monitoring every given number of years.
+    Discrete(f64),
+}
+
+impl Monitoring {
+    fn shift(self, sigma: f64) -> f64 {
+        match self {
+            Monitoring::Continuous => 0.0,
+            Monitoring::Discrete(interval) => BGK_BETA * sigma * interval.sqrt(),
+        }
+    }
+}
+
+// e^{log_scale}·Φ(x), for scales that overflow at low volatility
+fn scaled_cum_norm(log_scale: f64, x: f64) -> f64 {
+    (log_scale + log_cum_norm(x)).exp()
+}
+
+// Discounted expected excess of the continuous maximum over `level`,
+// e^{-rT} E[max(M_T, level) - level], for level >= stock
+fn maximum_excess(
+    stock: f64,
+    level: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    let carry = risk_free_rate - dividend_yield;
+    let sqrt_maturity_sigma = sigma * maturity.sqrt();
+    let discount = (-risk_free_rate * maturity).exp();
+    if sqrt_maturity_sigma <= 0.0 {
+        // the stock follows its forward, so the maximum is the larger of
+        // today's price and the forward
+        return discount * (stock * (carry * maturity).exp() - level).max(0.0);
+    }
+    let log_moneyness = (stock / level).ln();
+    let d1 = (log_moneyness + (carry + 0.5 * sigma * sigma) * maturity) / sqrt_maturity_sigma;
+    let d2 = d1 - sqrt_maturity_sigma;
+    let reflection = if carry.abs() < ZERO_CARRY {
+        cum_norm(d1) * (log_moneyness + 0.5 * sigma * sigma * maturity)
+            + inc_norm(d1) * sqrt_maturity_sigma
+    } else {
+        let exponent = 2.0 * carry / (sigma * sigma);
+        sigma * sigma / (2.0 * carry)
+            * ((carry * maturity).exp() * cum_norm(d1)
+                - scaled_cum_norm(
+                    -exponent * log_moneyness,
+                    d1 - exponent * sqrt_maturity_sigma,
+                ))
+    };
+    stock * (-dividend_yield * maturity).exp() * cum_norm(d1) - level * discount * cum_norm(d2)
+        + stock * discount * reflection
+}
+
+// Discounted expected shortfall of the continuous minimum below `level`,
+// e^{-rT} E[level - min(m_T, level)], for level <= stock
+fn minimum_shortfall(
+    stock: f64,
+    level: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    let carry = risk_free_rate - dividend_yield;
+    let sqrt_maturity_sigma = sigma * maturity.sqrt();
+    let discount = (-risk_free_rate * maturity).exp();
+    if sqrt_maturity_sigma <= 0.0 {
+        // the stock follows its forward, so the minimum is the smaller of
+        // today's price and the forward
+        return discount * (level - stock * (carry * maturity).exp()).max(0.0);
+    }
+    let log_moneyness = (stock / level).ln();
+    let d1 = (log_moneyness + (carry + 0.5 * sigma * sigma) * maturity) / sqrt_maturity_sigma;
+    let d2 = d1 - sqrt_maturity_sigma;
+    let reflection = if carry.abs() < ZERO_CARRY {
+        inc_norm(d1) * sqrt_maturity_sigma
+            - cum_norm(-d1) * (log_moneyness + 0.5 * sigma * sigma * maturity)
+    } else {
+        let exponent = 2.0 * carry / (sigma * sigma);
+        sigma * sigma / (2.0 * carry)
+            * (scaled_cum_norm(
+                -exponent * log_moneyness,
+                exponent * sqrt_maturity_sigma - d1,
+            ) - (carry * maturity).exp() * cum_norm(-d1))
+    };
+    level * discount * cum_norm(-d2) - stock * (-dividend_yield * maturity).exp() * cum_norm(-d1)
+        + stock * discount * reflection
+}
+
+/// Returns the price of a floating strike lookback call, paying the stock
+/// price at maturity less its minimum.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::lookback::{floating_strike_call, Monitoring};
+/// let stock = 120.0;
+/// let running_minimum = 100.0;
+/// let sigma = 0.3;
+/// let risk_free_rate = 0.1;
+/// let dividend_yield = 0.04;
+/// let maturity = 0.5;
+/// let continuous = floating_strike_call(
+///     Monitoring::Continuous, stock, running_minimum,
+///     sigma, risk_free_rate, dividend_yield, maturity
+/// );
+/// let daily = floating_strike_call(
+///     Monitoring::Discrete(1.0 / 252.0), stock, running_minimum,
+///     sigma, risk_free_rate, dividend_yield, maturity
+/// );
+/// assert!(daily < continuous);
+/// ```
+pub fn floating_strike_call(
+    monitoring: Monitoring,
+    stock: f64,
+    running_minimum: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    let shift = monitoring.shift(sigma).exp();
+    let forward_value = stock * (-dividend_yield * maturity).exp();
+    let minimum = running_minimum / shift;
+    let continuous = forward_value - minimum * (-risk_free_rate * maturity).exp()
+        + minimum_shortfall(
+            stock,
+            minimum,
+            sigma,
+            risk_free_rate,
+            dividend_yield,
+            maturity,
+        );
+    shift * continuous - (shift - 1.0) * forward_value
+}
+
+/// Returns the price of a floating strike lookback put, paying the maximum
+/// stock price less the stock price at maturity.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::lookback::{floating_strike_put, Monitoring};
+/// let price = floating_strike_put(Monitoring::Continuous, 100.0, 110.0, 0.3, 0.1, 0.04, 0.5);
+/// assert!(price > 10.0);
+/// ```
+pub fn floating_strike_put(
+    monitoring: Monitoring,
+    stock: f64,
+    running_maximum: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    let shift = monitoring.shift(sigma).exp();
+    let forward_value = stock * (-dividend_yield * maturity).exp();
+    let maximum = running_maximum * shift;
+    let continuous = maximum * (-risk_free_rate * maturity).exp() - forward_value
+        + maximum_excess(
+            stock,
+            maximum,
+            sigma,
+            risk_free_rate,
+            dividend_yield,
+            maturity,
+        );
+    continuous / shift - (1.0 - 1.0 / shift) * forward_value
+}
+
+/// Returns the price of a fixed strike lookback call, paying the maximum
+/// stock price less the strike if positive.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::lookback::{fixed_strike_call, Monitoring};
+/// let price = fixed_strike_call(Monitoring::Continuous, 100.0, 105.0, 100.0, 0.3, 0.1, 0.0, 0.5);
+/// assert!(price > black_scholes::call(100.0, 105.0, 0.1, 0.3, 0.5));
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn fixed_strike_call(
+    monitoring: Monitoring,
+    stock: f64,
+    strike: f64,
+    running_maximum: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    let shift = monitoring.shift(sigma).exp();
+    let maximum = running_maximum * shift;
+    let level = maximum.max(strike * shift);
+    let continuous = (level - strike * shift) * (-risk_free_rate * maturity).exp()
+        + maximum_excess(
+            stock,
+            level,
+            sigma,
+            risk_free_rate,
+            dividend_yield,
+            maturity,
+        );
+    continuous / shift
+}
+
+/// Returns the price of a fixed strike lookback put, paying the strike less
+/// the minimum stock price if positive.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::lookback::{fixed_strike_put, Monitoring};
+/// let price = fixed_strike_put(Monitoring::Continuous, 100.0, 95.0, 100.0, 0.3, 0.1, 0.0, 0.5);
+/// assert!(price > black_scholes::put(100.0, 95.0, 0.1, 0.3, 0.5));
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn fixed_strike_put(
+    monitoring: Monitoring,
+    stock: f64,
+    strike: f64,
+    running_minimum: f64,
+    sigma: f64,
+    risk_free_rate: f64,
+    dividend_yield: f64,
+    maturity: f64,
+) -> f64 {
+    let shift = monitoring.shift(sigma).exp();
+    let minimum = running_minimum / shift;
+    let level = minimum.min(strike / shift);
+    let continuous = (strike / shift - level) * (-risk_free_rate * maturity).exp()
+        + minimum_shortfall(
+            stock,
+            level,
+            sigma,
+            risk_free_rate,
+            dividend_yield,
+            maturity,
+        );
+    continuous * shift
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monte_carlo::MonteCarlo;
+    use approx::*;
+
+    #[test]
+    fn floating_strike_matches_reference() {
+        // Haug, The Complete Guide to Option Pricing Formulas, section 4.15.1
+        let call = floating_strike_call(Monitoring::Continuous, 120.0, 100.0, 0.3, 0.1, 0.06, 0.5);
+        assert_abs_diff_eq!(call, 25.3533, epsilon = 1e-4);
+    }
+    #[test]
+    fn discrete_monitoring_matches_simulation() {
+        let (s, sigma, rate, q, maturity) = (100.0, 0.3, 0.05, 0.02, 1.0);
+        let steps = 52;
+        let monitoring = Monitoring::Discrete(maturity / steps as f64);
+        let (running_minimum, running_maximum, strike) = (95.0, 104.0, 100.0);
+        let simulation = MonteCarlo::new(40_000, steps, 3).with_antithetic_variates();
+        let simulate = |payoff: &dyn Fn(f64, f64, f64) -> f64| {
+            simulation.price(
+                |path| {
+                    let minimum = path.iter().fold(running_minimum, |m: f64, &x| m.min(x));
+                    let maximum = path.iter().fold(running_maximum, |m: f64, &x| m.max(x));
+                    payoff(path[steps - 1], minimum, maximum)
+                },
+                s,
+                sigma,
+                rate,
+                q,
+                maturity,
+            )
+        };
+        let cases = [
+            (
+                floating_strike_call(monitoring, s, running_minimum, sigma, rate, q, maturity),
+                simulate(&|last, minimum, _| last - minimum),
+            ),
+            (
+                floating_strike_put(monitoring, s, running_maximum, sigma, rate, q, maturity),
+                simulate(&|last, _, maximum| maximum - last),
+            ),
+            (
+                fixed_strike_call(
+                    monitoring,
+                    s,
+                    strike,
+                    running_maximum,
+                    sigma,
+                    rate,
+                    q,
+                    maturity,
+                ),
+                simulate(&|_, _, maximum| (maximum - strike).max(0.0)),
+            ),
+            (
+                fixed_strike_put(
+                    monitoring,
+                    s,
+                    strike,
+                    running_minimum,
+                    sigma,
+                    rate,
+                    q,
+                    maturity,
+                ),
+                simulate(&|_, minimum, _| (strike - minimum).max(0.0)),
+            ),
+        ];
+        for (price, simulated) in cases {
+            assert!((price - simulated.price).abs() < 4.0 * simulated.standard_error + 0.02);
+        }
+    }
+    #[test]
+    fn fixed_strike_in_the_money_is_linear_in_strike() {
+        let (s, sigma, rate, q, maturity): (f64, f64, f64, f64, f64) =
+            (100.0, 0.25, 0.05, 0.01, 0.75);
+        let discount = (-rate * maturity).exp();
+        let call = |strike| {
+            fixed_strike_call(
+                Monitoring::Continuous,
+                s,
+                strike,
+                110.0,
+                sigma,
+                rate,
+                q,
+                maturity,
+            )
+        };
+        let put = |strike| {
+            fixed_strike_put(
+                Monitoring::Continuous,
+                s,
+                strike,
+                90.0,
+                sigma,
+                rate,
+                q,
+                maturity,
+            )
+        };
+        assert_abs_diff_eq!(call(100.0) - call(105.0), 5.0 * discount, epsilon = 1e-10);
+        assert_abs_diff_eq!(put(100.0) - put(95.0), 5.0 * discount, epsilon = 1e-10);
+    }
+    #[test]
+    fn zero_carry_is_continuous() {
+        let (s, sigma, rate, maturity) = (100.0, 0.2, 0.03, 1.0);
+        let floating =
+            |q| floating_strike_call(Monitoring::Continuous, s, 95.0, sigma, rate, q, maturity);
+        let fixed = |q| {
+            fixed_strike_put(
+                Monitoring::Continuous,
+                s,
+                100.0,
+                100.0,
+                sigma,
+                rate,
+                q,
+                maturity,
+            )
+        };
+        // the carry terms are linear near zero, so the neighbours average out
+        assert_abs_diff_eq!(
+            0.5 * (floating(rate - 1e-5) + floating(rate + 1e-5)),
+            floating(rate),
+            epsilon = 1e-7
+        );
+        assert_abs_diff_eq!(
+            0.5 * (fixed(rate - 1e-5) + fixed(rate + 1e-5)),
+            fixed(rate),
+            epsilon = 1e-7
+        );
+    }
+    #[test]
+    fn degenerate_cases_follow_the_forward() {
+        let (s, rate, q): (f64, f64, f64) = (100.0, 0.05, 0.0);
+        // the forward moves 5% away from today's price in either case
+        let forward_gain = s * (1.0 - (-rate).exp());
+        for sigma in [0.0, 1e-6] {
+            let call = floating_strike_call(Monitoring::Continuous, s, s, sigma, rate, q, 1.0);
+            let put = floating_strike_put(Monitoring::Continuous, s, s, sigma, q, rate, 1.0);
+            assert_abs_diff_eq!(call, forward_gain, epsilon = 1e-5);
+            assert_abs_diff_eq!(put, forward_gain, epsilon = 1e-5);
+            let fixed = fixed_strike_call(Monitoring::Continuous, s, 95.0, s, sigma, rate, q, 1.0);
+            assert_abs_diff_eq!(fixed, forward_gain + 5.0 * (-rate).exp(), epsilon = 1e-5);
+        }
+        // at expiry only the running extremes matter
+        let at_expiry = [
+            floating_strike_call(Monitoring::Continuous, s, 90.0, 0.2, rate, q, 0.0),
+            floating_strike_put(Monitoring::Continuous, s, s, 0.2, rate, q, 0.0),
+            fixed_strike_call(Monitoring::Continuous, s, 95.0, 110.0, 0.2, rate, q, 0.0),
+            fixed_strike_put(Monitoring::Continuous, s, 95.0, s, 0.2, rate, q, 0.0),
+        ];
+        assert_eq!(at_expiry, [10.0, 0.0, 15.0, 0.0]);
+    }
+}
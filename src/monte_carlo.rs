@@ -0,0 +1,820 @@
+//! Monte Carlo pricing of payoffs on geometric Brownian motion paths.
+//!
+//! Paths are simulated exactly at equally spaced monitoring times from the
+//! same inputs as `bsm_compute_all`.  Antithetic variates, a European call
+//! control variate and randomised Sobol numbers reduce the variance, and
+//! every estimate comes with its standard error.
+use crate::{call_discount, lets_be_rational::inverse_norm_cdf, max_or_zero};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use serde::Serialize;
+
+/// Maximum number of Sobol dimensions; see [`Sobol`].
+pub const SOBOL_MAX_DIMENSIONS: usize = 32;
+
+// Joe and Kuo (2008) primitive polynomial degree s, coefficients a and
+// initial direction numbers m for dimensions 2 to 32
+const JOE_KUO: [(u32, u32, &[u32]); SOBOL_MAX_DIMENSIONS - 1] = [
+    (1, 0, &[1]),
+    (2, 1, &[1, 3]),
+    (3, 1, &[1, 3, 1]),
+    (3, 2, &[1, 1, 1]),
+    (4, 1, &[1, 1, 3, 3]),
+    (4, 4, &[1, 3, 5, 13]),
+    (5, 2, &[1, 1, 5, 5, 17]),
+    (5, 4, &[1, 1, 5, 5, 5]),
+    (5, 7, &[1, 1, 7, 11, 19]),
+    (5, 11, &[1, 1, 5, 1, 1]),
+    (5, 13, &[1, 1, 1, 3, 11]),
+    (5, 14, &[1, 3, 5, 5, 31]),
+    (6, 1, &[1, 3, 3, 9, 7, 49]),
+    (6, 13, &[1, 1, 1, 15, 21, 21]),
+    (6, 16, &[1, 3, 1, 13, 27, 49]),
+    (6, 19, &[1, 1, 1, 15, 7, 5]),
+    (6, 22, &[1, 3, 1, 15, 13, 25]),
+    (6, 25, &[1, 1, 5, 5, 19, 61]),
+    (7, 1, &[1, 3, 7, 11, 23, 15, 103]),
+    (7, 4, &[1, 3, 7, 13, 13, 15, 69]),
+    (7, 7, &[1, 1, 3, 13, 7, 35, 63]),
+    (7, 8, &[1, 3, 5, 9, 1, 25, 53]),
+    (7, 14, &[1, 3, 1, 13, 9, 35, 107]),
+    (7, 19, &[1, 3, 1, 5, 27, 61, 31]),
+    (7, 21, &[1, 1, 5, 11, 19, 41, 61]),
+    (7, 28, &[1, 3, 5, 3, 3, 13, 69]),
+    (7, 31, &[1, 1, 7, 13, 1, 19, 1]),
+    (7, 32, &[1, 3, 7, 5, 13, 19, 59]),
+    (7, 37, &[1, 1, 3, 9, 25, 29, 41]),
+    (7, 41, &[1, 3, 5, 13, 23, 1, 55]),
+    (7, 42, &[1, 3, 7, 3, 13, 59, 17]),
+];
+
+const SOBOL_BITS: usize = 32;
+
+// maps 32 random bits to the middle of their interval in (0, 1)
+fn to_unit_interval(bits: u32) -> f64 {
+    (bits as f64 + 0.5) / 4_294_967_296.0
+}
+
+/// Sobol low discrepancy sequence with Joe-Kuo direction numbers, generated
+/// in Gray code order starting from the origin.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::monte_carlo::Sobol;
+/// let mut sobol = Sobol::new(2).unwrap();
+/// assert_eq!(sobol.next(), Some(vec![0.0, 0.0]));
+/// assert_eq!(sobol.next(), Some(vec![0.5, 0.5]));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Sobol {
+    directions: Vec<[u32; SOBOL_BITS]>,
+    integers: Vec<u32>,
+    index: u32,
+}
+
+impl Sobol {
+    /// Returns `None` for zero or more than [`SOBOL_MAX_DIMENSIONS`]
+    /// dimensions.
+    pub fn new(dimensions: usize) -> Option<Self> {
+        if dimensions == 0 || dimensions > SOBOL_MAX_DIMENSIONS {
+            return None;
+        }
+        let mut directions = vec![std::array::from_fn(|k| 1 << (SOBOL_BITS - 1 - k))];
+        for &(degree, coefficients, initial) in JOE_KUO.iter().take(dimensions - 1) {
+            let s = degree as usize;
+            let mut v = [0u32; SOBOL_BITS];
+            for k in 0..SOBOL_BITS {
+                v[k] = if k < s {
+                    initial[k] << (SOBOL_BITS - 1 - k)
+                } else {
+                    let mut value = v[k - s] ^ (v[k - s] >> s);
+                    for j in 1..s {
+                        if (coefficients >> (s - 1 - j)) & 1 == 1 {
+                            value ^= v[k - j];
+                        }
+                    }
+                    value
+                };
+            }
+            directions.push(v);
+        }
+        Some(Sobol {
+            directions,
+            integers: vec![0; dimensions],
+            index: 0,
+        })
+    }
+
+    // the next point as 32 bit integers
+    fn next_integers(&mut self) -> Option<&[u32]> {
+        if self.index > 0 {
+            // the bit to flip is the lowest zero bit of the previous index
+            let bit = (self.index - 1).trailing_ones() as usize;
+            if bit >= SOBOL_BITS {
+                return None;
+            }
+            for (integer, direction) in self.integers.iter_mut().zip(&self.directions) {
+                *integer ^= direction[bit];
+            }
+        }
+        self.index = self.index.checked_add(1)?;
+        Some(&self.integers)
+    }
+}
+
+impl Iterator for Sobol {
+    type Item = Vec<f64>;
+    fn next(&mut self) -> Option<Vec<f64>> {
+        self.next_integers().map(|integers| {
+            integers
+                .iter()
+                .map(|&integer| integer as f64 / 4_294_967_296.0)
+                .collect()
+        })
+    }
+}
+
+/// Monte Carlo price and its standard error.
+#[derive(Debug, Serialize)]
+pub struct MonteCarloEstimate {
+    pub price: f64,
+    pub standard_error: f64,
+}
+
+/// Monte Carlo price, delta, gamma and vega with their standard errors.
+#[derive(Debug, Serialize)]
+pub struct MonteCarloGreeks {
+    pub price: f64,
+    pub delta: f64,
+    pub gamma: f64,
+    pub vega: f64,
+    pub price_standard_error: f64,
+    pub delta_standard_error: f64,
+    pub gamma_standard_error: f64,
+    pub vega_standard_error: f64,
+}
+
+/// Source of the normal variates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum RandomNumbers {
+    Pseudo,
+    /// Sobol points in independent random digital shifts, so that the
+    /// standard error can be estimated from the spread between them.  The
+    /// paths are shared out as evenly as possible between up to 16 shifts.
+    /// Time steps beyond [`SOBOL_MAX_DIMENSIONS`] use pseudo-random numbers.
+    Sobol,
+}
+
+// Number of independently shifted Sobol batches
+const SOBOL_BATCHES: usize = 16;
+
+/// A seeded Monte Carlo engine.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::monte_carlo::MonteCarlo;
+/// let strike = 100.0;
+/// let engine = MonteCarlo::new(10_000, 1, 42).with_antithetic_variates();
+/// let estimate = engine.price(
+///     |path| (path[path.len() - 1] - strike).max(0.0),
+///     100.0,
+///     0.2,
+///     0.05,
+///     0.0,
+///     1.0,
+/// );
+/// let exact = black_scholes::call(100.0, strike, 0.05, 0.2, 1.0);
+/// assert!((estimate.price - exact).abs() < 4.0 * estimate.standard_error);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MonteCarlo {
+    paths: usize,
+    time_steps: usize,
+    seed: u64,
+    antithetic: bool,
+    control_strike: Option<f64>,
+    random_numbers: RandomNumbers,
+}
+
+impl MonteCarlo {
+    /// Returns an engine drawing `paths` sets of normal variates, each
+    /// monitored at `time_steps` equally spaced times up to maturity.
+    pub fn new(paths: usize, time_steps: usize, seed: u64) -> Self {
+        MonteCarlo {
+            paths: paths.max(2),
+            time_steps: time_steps.max(1),
+            seed,
+            antithetic: false,
+            control_strike: None,
+            random_numbers: RandomNumbers::Pseudo,
+        }
+    }
+    /// Also simulates the mirror image of each path.
+    pub fn with_antithetic_variates(self) -> Self {
+        MonteCarlo {
+            antithetic: true,
+            ..self
+        }
+    }
+    /// Uses a European call struck at `strike` on the final stock price as
+    /// a control variate, with the optimal coefficient estimated from the
+    /// same paths.
+    pub fn with_control_variate(self, strike: f64) -> Self {
+        MonteCarlo {
+            control_strike: Some(strike),
+            ..self
+        }
+    }
+    pub fn with_random_numbers(self, random_numbers: RandomNumbers) -> Self {
+        MonteCarlo {
+            random_numbers,
+            ..self
+        }
+    }
+
+    pub(crate) fn is_antithetic(&self) -> bool {
+        self.antithetic
+    }
+
+    // Draws the normal variates and calls `sample` with each batch index and
+    // set of variates
+    pub(crate) fn for_each_draw(&self, mut sample: impl FnMut(usize, &[f64])) {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut normals = vec![0.0; self.time_steps];
+        match self.random_numbers {
+            RandomNumbers::Pseudo => {
+                for _ in 0..self.paths {
+                    for normal in normals.iter_mut() {
+                        *normal = inverse_norm_cdf(to_unit_interval(rng.random()));
+                    }
+                    sample(0, &normals);
+                }
+            }
+            RandomNumbers::Sobol => {
+                let dimensions = self.time_steps.min(SOBOL_MAX_DIMENSIONS);
+                let batches = self.batches();
+                for batch in 0..batches {
+                    let shifts: Vec<u32> = (0..dimensions).map(|_| rng.random()).collect();
+                    let mut sobol = Sobol::new(dimensions).unwrap();
+                    // the first `paths % batches` batches take one path more
+                    let per_batch =
+                        self.paths / batches + usize::from(batch < self.paths % batches);
+                    for _ in 0..per_batch {
+                        let integers = sobol.next_integers().unwrap();
+                        for (normal, (integer, shift)) in
+                            normals.iter_mut().zip(integers.iter().zip(&shifts))
+                        {
+                            *normal = inverse_norm_cdf(to_unit_interval(integer ^ shift));
+                        }
+                        for normal in normals.iter_mut().skip(dimensions) {
+                            *normal = inverse_norm_cdf(to_unit_interval(rng.random()));
+                        }
+                        sample(batch, &normals);
+                    }
+                }
+            }
+        }
+    }
+
+    fn batches(&self) -> usize {
+        match self.random_numbers {
+            RandomNumbers::Pseudo => 1,
+            // every batch needs at least one path for its mean
+            RandomNumbers::Sobol => SOBOL_BATCHES.min(self.paths),
+        }
+    }
+
+    // Simulates the path for each draw, and its mirror image with
+    // antithetic variates, and returns the batch and the average over the
+    // paths of `evaluate`, which receives the variates and the path
+    fn sample_paths<const N: usize>(
+        &self,
+        stock: f64,
+        sigma: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        maturity: f64,
+        mut evaluate: impl FnMut(&[f64], &[f64]) -> [f64; N],
+    ) -> Vec<(usize, [f64; N])> {
+        let dt = maturity / self.time_steps as f64;
+        let drift = (risk_free_rate - dividend_yield - 0.5 * sigma * sigma) * dt;
+        let volatility = sigma * dt.sqrt();
+        let mut path = vec![0.0; self.time_steps];
+        let mut mirror = vec![0.0; self.time_steps];
+        let mut samples = Vec::with_capacity(self.paths);
+        self.for_each_draw(|batch, normals| {
+            let mut simulate = |normals: &[f64]| {
+                let mut s = stock;
+                for (price, normal) in path.iter_mut().zip(normals) {
+                    s *= (drift + volatility * normal).exp();
+                    *price = s;
+                }
+                evaluate(normals, &path)
+            };
+            let mut values = simulate(normals);
+            if self.antithetic {
+                for (mirrored, normal) in mirror.iter_mut().zip(normals) {
+                    *mirrored = -normal;
+                }
+                let mirror_values = simulate(&mirror);
+                for (value, mirror_value) in values.iter_mut().zip(mirror_values) {
+                    *value = 0.5 * (*value + mirror_value);
+                }
+            }
+            samples.push((batch, values));
+        });
+        samples
+    }
+
+    /// Returns the discounted expectation of `payoff`, which receives the
+    /// stock prices at the monitoring times (the last being maturity).
+    pub fn price(
+        &self,
+        payoff: impl Fn(&[f64]) -> f64,
+        stock: f64,
+        sigma: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        maturity: f64,
+    ) -> MonteCarloEstimate {
+        let discount = (-risk_free_rate * maturity).exp();
+        // discounted payoff and control
+        let samples: Vec<(usize, f64, f64)> = self
+            .sample_paths(
+                stock,
+                sigma,
+                risk_free_rate,
+                dividend_yield,
+                maturity,
+                |_, path| {
+                    let control = self
+                        .control_strike
+                        .map_or(0.0, |strike| max_or_zero(path[path.len() - 1] - strike));
+                    [discount * payoff(path), discount * control]
+                },
+            )
+            .into_iter()
+            .map(|(batch, [value, control])| (batch, value, control))
+            .collect();
+        let control_mean = self.control_strike.map_or(0.0, |strike| {
+            call_discount(
+                stock * (-dividend_yield * maturity).exp(),
+                strike,
+                discount,
+                sigma * maturity.sqrt(),
+            )
+        });
+        adjusted_estimate(&samples, control_mean, self.batches())
+    }
+
+    // Estimates of the price and greeks from samples of each
+    fn greeks(&self, samples: &[(usize, [f64; 4])]) -> MonteCarloGreeks {
+        let [price, delta, gamma, vega] = [0, 1, 2, 3].map(|greek| {
+            let samples: Vec<(usize, f64, f64)> = samples
+                .iter()
+                .map(|(batch, values)| (*batch, values[greek], 0.0))
+                .collect();
+            adjusted_estimate(&samples, 0.0, self.batches())
+        });
+        MonteCarloGreeks {
+            price: price.price,
+            delta: delta.price,
+            gamma: gamma.price,
+            vega: vega.price,
+            price_standard_error: price.standard_error,
+            delta_standard_error: delta.standard_error,
+            gamma_standard_error: gamma.standard_error,
+            vega_standard_error: vega.standard_error,
+        }
+    }
+
+    /// Returns the price, delta, gamma and vega by differentiating along
+    /// each path, for payoffs that are Lipschitz in the path.
+    /// `payoff_gradient` fills in the derivative of the payoff with respect
+    /// to the stock price at each monitoring time.  The payoff has no second
+    /// derivative, so gamma differentiates the pathwise delta by the
+    /// likelihood ratio of the first step.  The control variate is not used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use black_scholes::monte_carlo::MonteCarlo;
+    /// let strike = 100.0;
+    /// let greeks = MonteCarlo::new(20_000, 1, 42).pathwise_greeks(
+    ///     |path| (path[0] - strike).max(0.0),
+    ///     |path, gradient| gradient[0] = if path[0] > strike { 1.0 } else { 0.0 },
+    ///     100.0,
+    ///     0.2,
+    ///     0.05,
+    ///     0.0,
+    ///     1.0,
+    /// );
+    /// let exact = black_scholes::call_delta(100.0, strike, 0.05, 0.2, 1.0);
+    /// assert!((greeks.delta - exact).abs() < 4.0 * greeks.delta_standard_error);
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub fn pathwise_greeks(
+        &self,
+        payoff: impl Fn(&[f64]) -> f64,
+        payoff_gradient: impl Fn(&[f64], &mut [f64]),
+        stock: f64,
+        sigma: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        maturity: f64,
+    ) -> MonteCarloGreeks {
+        let dt = maturity / self.time_steps as f64;
+        let sqrt_dt = dt.sqrt();
+        let discount = (-risk_free_rate * maturity).exp();
+        let mut gradient = vec![0.0; self.time_steps];
+        let samples = self.sample_paths(
+            stock,
+            sigma,
+            risk_free_rate,
+            dividend_yield,
+            maturity,
+            |normals, path| {
+                gradient.fill(0.0);
+                payoff_gradient(path, &mut gradient);
+                // dS_i/dS₀ = S_i/S₀ and dS_i/dσ = S_i(W_i - σt_i)
+                let mut delta = 0.0;
+                let mut vega = 0.0;
+                let mut brownian = 0.0;
+                for (i, ((derivative, price), normal)) in
+                    gradient.iter().zip(path).zip(normals).enumerate()
+                {
+                    brownian += sqrt_dt * normal;
+                    delta += derivative * price / stock;
+                    vega += derivative * price * (brownian - sigma * (i + 1) as f64 * dt);
+                }
+                let score = normals[0] / (sigma * sqrt_dt);
+                [
+                    discount * payoff(path),
+                    discount * delta,
+                    discount * delta * (score - 1.0) / stock,
+                    discount * vega,
+                ]
+            },
+        );
+        self.greeks(&samples)
+    }
+
+    /// Returns the price, delta, gamma and vega by weighting the payoff with
+    /// the derivatives of the log density of the path, which needs no
+    /// continuity of the payoff and so suits digitals.  The control variate
+    /// is not used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use black_scholes::monte_carlo::MonteCarlo;
+    /// let strike = 100.0;
+    /// let digital = MonteCarlo::new(20_000, 1, 42).likelihood_ratio_greeks(
+    ///     |path| if path[0] > strike { 1.0 } else { 0.0 },
+    ///     100.0,
+    ///     0.2,
+    ///     0.05,
+    ///     0.0,
+    ///     1.0,
+    /// );
+    /// assert!(digital.delta > 0.0);
+    /// ```
+    pub fn likelihood_ratio_greeks(
+        &self,
+        payoff: impl Fn(&[f64]) -> f64,
+        stock: f64,
+        sigma: f64,
+        risk_free_rate: f64,
+        dividend_yield: f64,
+        maturity: f64,
+    ) -> MonteCarloGreeks {
+        let dt = maturity / self.time_steps as f64;
+        let sqrt_dt = dt.sqrt();
+        let discount = (-risk_free_rate * maturity).exp();
+        let samples = self.sample_paths(
+            stock,
+            sigma,
+            risk_free_rate,
+            dividend_yield,
+            maturity,
+            |normals, path| {
+                let value = discount * payoff(path);
+                // only the first step depends on the initial stock price
+                let z = normals[0];
+                let delta_weight = z / (stock * sigma * sqrt_dt);
+                let gamma_weight = ((z * z - 1.0) / (sigma * sigma * dt) - z / (sigma * sqrt_dt))
+                    / (stock * stock);
+                let vega_weight: f64 = normals
+                    .iter()
+                    .map(|z| (z * z - 1.0) / sigma - z * sqrt_dt)
+                    .sum();
+                [
+                    value,
+                    value * delta_weight,
+                    value * gamma_weight,
+                    value * vega_weight,
+                ]
+            },
+        );
+        self.greeks(&samples)
+    }
+}
+
+fn mean_and_standard_error(values: &[f64]) -> (f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    (mean, (variance / n).sqrt())
+}
+
+// Applies the control variate with the regression coefficient and returns
+// the estimate with its standard error, from the batch means if there is
+// more than one batch
+pub(crate) fn adjusted_estimate(
+    samples: &[(usize, f64, f64)],
+    control_mean: f64,
+    batches: usize,
+) -> MonteCarloEstimate {
+    let n = samples.len() as f64;
+    let value_mean = samples.iter().map(|s| s.1).sum::<f64>() / n;
+    let control_sample_mean = samples.iter().map(|s| s.2).sum::<f64>() / n;
+    let covariance: f64 = samples
+        .iter()
+        .map(|s| (s.1 - value_mean) * (s.2 - control_sample_mean))
+        .sum();
+    let control_variance: f64 = samples
+        .iter()
+        .map(|s| (s.2 - control_sample_mean).powi(2))
+        .sum();
+    let beta = if control_variance > 0.0 {
+        covariance / control_variance
+    } else {
+        0.0
+    };
+    let adjusted =
+        |&(_, value, control): &(usize, f64, f64)| value - beta * (control - control_mean);
+    let (price, standard_error) = if batches > 1 {
+        let mut totals = vec![(0.0, 0.0); batches];
+        for sample in samples {
+            totals[sample.0].0 += adjusted(sample);
+            totals[sample.0].1 += 1.0;
+        }
+        let batch_means: Vec<f64> = totals.iter().map(|(sum, count)| sum / count).collect();
+        mean_and_standard_error(&batch_means)
+    } else {
+        let values: Vec<f64> = samples.iter().map(adjusted).collect();
+        mean_and_standard_error(&values)
+    };
+    MonteCarloEstimate {
+        price,
+        standard_error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bsm_compute_all, call, call_delta, call_gamma, call_vega, cum_norm};
+    use approx::*;
+    use std::f64::consts::PI;
+
+    fn european_call(strike: f64) -> impl Fn(&[f64]) -> f64 {
+        move |path| max_or_zero(path[path.len() - 1] - strike)
+    }
+
+    #[test]
+    fn sobol_matches_reference_points() {
+        let points: Vec<Vec<f64>> = Sobol::new(3).unwrap().take(8).collect();
+        let expected = [
+            [0.0, 0.0, 0.0],
+            [0.5, 0.5, 0.5],
+            [0.75, 0.25, 0.25],
+            [0.25, 0.75, 0.75],
+            [0.375, 0.375, 0.625],
+            [0.875, 0.875, 0.125],
+            [0.625, 0.125, 0.875],
+            [0.125, 0.625, 0.375],
+        ];
+        for (point, expected) in points.iter().zip(expected) {
+            assert_eq!(point.as_slice(), expected.as_slice());
+        }
+        assert!(Sobol::new(0).is_none());
+        assert!(Sobol::new(SOBOL_MAX_DIMENSIONS + 1).is_none());
+    }
+    #[test]
+    fn sobol_dimensions_are_stratified() {
+        // each of the first 2^k points falls in a different interval of
+        // width 2^-k in every dimension
+        let k = 10;
+        let points: Vec<Vec<f64>> = Sobol::new(SOBOL_MAX_DIMENSIONS)
+            .unwrap()
+            .take(1 << k)
+            .collect();
+        for dimension in 0..SOBOL_MAX_DIMENSIONS {
+            let mut cells: Vec<usize> = points
+                .iter()
+                .map(|point| (point[dimension] * (1 << k) as f64) as usize)
+                .collect();
+            cells.sort_unstable();
+            cells.dedup();
+            assert_eq!(cells.len(), 1 << k);
+        }
+    }
+    #[test]
+    fn european_call_within_standard_errors() {
+        let (s, k, sigma, rate, q, maturity) = (100.0, 110.0, 0.25, 0.05, 0.02, 1.0);
+        let exact = bsm_compute_all(s, k, sigma, rate, q, maturity).call_price;
+        let engine = MonteCarlo::new(100_000, 1, 1);
+        let estimate = engine.price(european_call(k), s, sigma, rate, q, maturity);
+        assert!((estimate.price - exact).abs() < 4.0 * estimate.standard_error);
+        assert!(estimate.standard_error < 0.1);
+        // multiple monitoring dates give the same terminal distribution
+        let estimate =
+            MonteCarlo::new(20_000, 12, 2).price(european_call(k), s, sigma, rate, q, maturity);
+        assert!((estimate.price - exact).abs() < 4.0 * estimate.standard_error);
+    }
+    #[test]
+    fn seed_gives_reproducible_results() {
+        let price = |seed| {
+            MonteCarlo::new(1000, 4, seed)
+                .price(european_call(100.0), 100.0, 0.2, 0.05, 0.0, 1.0)
+                .price
+        };
+        assert_eq!(price(7), price(7));
+        assert_ne!(price(7), price(8));
+    }
+    #[test]
+    fn variance_reduction_lowers_standard_error() {
+        let (s, k, sigma, rate, maturity) = (100.0, 105.0, 0.2, 0.05, 1.0);
+        let exact = call(s, k, rate, sigma, maturity);
+        let engine = MonteCarlo::new(20_000, 1, 3);
+        let plain = engine.price(european_call(k), s, sigma, rate, 0.0, maturity);
+        for (engine, reduction) in [
+            (engine.clone().with_antithetic_variates(), 0.75),
+            (engine.clone().with_control_variate(100.0), 0.5),
+            (
+                engine.clone().with_random_numbers(RandomNumbers::Sobol),
+                0.1,
+            ),
+        ] {
+            let estimate = engine.price(european_call(k), s, sigma, rate, 0.0, maturity);
+            assert!(
+                estimate.standard_error < reduction * plain.standard_error,
+                "{:?}",
+                engine
+            );
+            assert!((estimate.price - exact).abs() < 4.0 * estimate.standard_error);
+        }
+    }
+    #[test]
+    fn control_variate_on_itself_is_exact() {
+        let (s, k, sigma, rate, maturity) = (100.0, 100.0, 0.3, 0.03, 0.5);
+        let estimate = MonteCarlo::new(1000, 1, 4).with_control_variate(k).price(
+            european_call(k),
+            s,
+            sigma,
+            rate,
+            0.0,
+            maturity,
+        );
+        assert_abs_diff_eq!(
+            estimate.price,
+            call(s, k, rate, sigma, maturity),
+            epsilon = 1e-10
+        );
+        assert_abs_diff_eq!(estimate.standard_error, 0.0, epsilon = 1e-10);
+    }
+    fn call_gradient(strike: f64) -> impl Fn(&[f64], &mut [f64]) {
+        move |path, gradient| {
+            let last = path.len() - 1;
+            gradient[last] = if path[last] > strike { 1.0 } else { 0.0 };
+        }
+    }
+    fn within_standard_errors(estimate: f64, standard_error: f64, exact: f64) -> bool {
+        (estimate - exact).abs() < 4.0 * standard_error
+    }
+
+    #[test]
+    fn pathwise_greeks_match_call() {
+        let (s, k, sigma, rate, maturity) = (100.0, 95.0, 0.25, 0.04, 1.0);
+        for time_steps in [1, 12] {
+            let greeks = MonteCarlo::new(50_000, time_steps, 6)
+                .with_antithetic_variates()
+                .pathwise_greeks(
+                    european_call(k),
+                    call_gradient(k),
+                    s,
+                    sigma,
+                    rate,
+                    0.0,
+                    maturity,
+                );
+            assert!(within_standard_errors(
+                greeks.delta,
+                greeks.delta_standard_error,
+                call_delta(s, k, rate, sigma, maturity)
+            ));
+            assert!(within_standard_errors(
+                greeks.gamma,
+                greeks.gamma_standard_error,
+                call_gamma(s, k, rate, sigma, maturity)
+            ));
+            assert!(within_standard_errors(
+                greeks.vega,
+                greeks.vega_standard_error,
+                call_vega(s, k, rate, sigma, maturity)
+            ));
+            assert!(greeks.delta_standard_error < 2e-3);
+            assert!(greeks.vega_standard_error < 0.5);
+        }
+    }
+    #[test]
+    fn likelihood_ratio_greeks_match_call() {
+        let (s, k, sigma, rate, maturity) = (100.0, 105.0, 0.2, 0.05, 0.5);
+        let greeks = MonteCarlo::new(100_000, 1, 7).likelihood_ratio_greeks(
+            european_call(k),
+            s,
+            sigma,
+            rate,
+            0.0,
+            maturity,
+        );
+        assert!(within_standard_errors(
+            greeks.delta,
+            greeks.delta_standard_error,
+            call_delta(s, k, rate, sigma, maturity)
+        ));
+        assert!(within_standard_errors(
+            greeks.gamma,
+            greeks.gamma_standard_error,
+            call_gamma(s, k, rate, sigma, maturity)
+        ));
+        assert!(within_standard_errors(
+            greeks.vega,
+            greeks.vega_standard_error,
+            call_vega(s, k, rate, sigma, maturity)
+        ));
+    }
+    #[test]
+    fn likelihood_ratio_greeks_of_digital() {
+        let (s, k, sigma, rate, q, maturity): (f64, f64, f64, f64, f64, f64) =
+            (100.0, 100.0, 0.2, 0.05, 0.01, 1.0);
+        let sqrt_maturity_sigma = sigma * maturity.sqrt();
+        let d2 = ((s / k).ln() + (rate - q - 0.5 * sigma * sigma) * maturity) / sqrt_maturity_sigma;
+        let d1 = d2 + sqrt_maturity_sigma;
+        let discounted_density =
+            (-rate * maturity).exp() * (-0.5 * d2 * d2).exp() / (2.0 * PI).sqrt();
+        let digital = |path: &[f64]| if path[path.len() - 1] > k { 1.0 } else { 0.0 };
+        for time_steps in [1, 4] {
+            let greeks = MonteCarlo::new(100_000, time_steps, 8)
+                .with_random_numbers(RandomNumbers::Sobol)
+                .likelihood_ratio_greeks(digital, s, sigma, rate, q, maturity);
+            assert!(within_standard_errors(
+                greeks.price,
+                greeks.price_standard_error,
+                (-rate * maturity).exp() * cum_norm(d2)
+            ));
+            assert!(within_standard_errors(
+                greeks.delta,
+                greeks.delta_standard_error,
+                discounted_density / (s * sqrt_maturity_sigma)
+            ));
+            assert!(within_standard_errors(
+                greeks.gamma,
+                greeks.gamma_standard_error,
+                -discounted_density * d1 / (s * s * sqrt_maturity_sigma * sqrt_maturity_sigma)
+            ));
+            assert!(within_standard_errors(
+                greeks.vega,
+                greeks.vega_standard_error,
+                -discounted_density * d1 / sigma
+            ));
+        }
+    }
+    #[test]
+    fn sobol_with_more_steps_than_dimensions() {
+        let (s, k, sigma, rate, maturity) = (100.0, 100.0, 0.2, 0.05, 1.0);
+        let estimate = MonteCarlo::new(16_000, 50, 5)
+            .with_random_numbers(RandomNumbers::Sobol)
+            .price(european_call(k), s, sigma, rate, 0.0, maturity);
+        let exact = call(s, k, rate, sigma, maturity);
+        assert!((estimate.price - exact).abs() < 4.0 * estimate.standard_error);
+    }
+    #[test]
+    fn sobol_draws_every_path() {
+        for paths in [2, 5, 16, 100, 1000] {
+            let engine = MonteCarlo::new(paths, 3, 6).with_random_numbers(RandomNumbers::Sobol);
+            let mut counts = vec![0; SOBOL_BATCHES];
+            engine.for_each_draw(|batch, _| counts[batch] += 1);
+            assert_eq!(counts.iter().sum::<usize>(), paths);
+            let filled: Vec<usize> = counts.into_iter().filter(|&count| count > 0).collect();
+            assert_eq!(filled.len(), SOBOL_BATCHES.min(paths));
+            assert!(filled.iter().max().unwrap() - filled.iter().min().unwrap() <= 1);
+        }
+        let estimate = MonteCarlo::new(5, 1, 7)
+            .with_random_numbers(RandomNumbers::Sobol)
+            .price(european_call(100.0), 100.0, 0.2, 0.05, 0.0, 1.0);
+        assert!(estimate.price.is_finite() && estimate.standard_error.is_finite());
+    }
+}
@@ -0,0 +1,569 @@
+//! Crank-Nicolson finite-difference solver for the Black-Scholes PDE
+//!
+//! `V_t + ½σ(t)²S²V_SS + (r(t) - q)S V_S - r(t)V = 0`
+//!
+//! on a non-uniform spot grid.  The first time steps are replaced by fully
+//! implicit half steps (Rannacher start-up) to damp the oscillations caused
+//! by non-smooth payoffs, American exercise is handled by projected SOR and
+//! knock-out barriers are Dirichlet boundaries.
+use crate::lattice::Exercise;
+use serde::Serialize;
+
+/// A knock-out barrier with a rebate paid when it is hit.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Barrier {
+    pub level: f64,
+    pub rebate: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PdePriceAndGreeks {
+    pub price: f64,
+    pub delta: f64,
+    pub gamma: f64,
+    pub theta: f64,
+}
+
+// the price is interpolated from three nodes around the stock
+const MINIMUM_GRID_POINTS: usize = 3;
+
+// Returns the grid if the solver can use it: at least three nodes, all
+// finite and strictly increasing
+fn valid_grid(grid: Vec<f64>) -> Option<Vec<f64>> {
+    let usable = grid.len() >= MINIMUM_GRID_POINTS
+        && grid.iter().all(|node| node.is_finite())
+        && grid.windows(2).all(|pair| pair[0] < pair[1]);
+    usable.then_some(grid)
+}
+
+/// Returns `points` equally spaced nodes from `lower` to `upper`, or `None`
+/// for fewer than three points or `lower >= upper`.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::pde::uniform_grid;
+/// let grid = uniform_grid(0.0, 200.0, 201).unwrap();
+/// assert_eq!(grid[100], 100.0);
+/// assert!(uniform_grid(0.0, 200.0, 2).is_none());
+/// ```
+pub fn uniform_grid(lower: f64, upper: f64, points: usize) -> Option<Vec<f64>> {
+    let step = (upper - lower) / points.saturating_sub(1) as f64;
+    valid_grid((0..points).map(|i| lower + step * i as f64).collect())
+}
+
+/// Returns `points` nodes from `lower` to `upper` concentrated around
+/// `centre` (Tavella and Randall).  Smaller `concentration` clusters the
+/// nodes more tightly; large values give an almost uniform grid.  Returns
+/// `None` unless the nodes are at least three and strictly increasing.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::pde::sinh_grid;
+/// let grid = sinh_grid(0.0, 400.0, 201, 100.0, 0.1).unwrap();
+/// assert_eq!(grid[0], 0.0);
+/// assert!((grid[200] - 400.0).abs() < 1e-10);
+/// ```
+pub fn sinh_grid(
+    lower: f64,
+    upper: f64,
+    points: usize,
+    centre: f64,
+    concentration: f64,
+) -> Option<Vec<f64>> {
+    let alpha = concentration * (upper - lower);
+    let c_lower = ((lower - centre) / alpha).asinh();
+    let c_upper = ((upper - centre) / alpha).asinh();
+    valid_grid(
+        (0..points)
+            .map(|i| {
+                let u = i as f64 / points.saturating_sub(1) as f64;
+                centre + alpha * (c_lower + u * (c_upper - c_lower)).sinh()
+            })
+            .collect(),
+    )
+}
+
+const PSOR_RELAXATION: f64 = 1.2;
+const PSOR_PRECISION: f64 = 1e-24;
+const PSOR_ITERATIONS: usize = 10_000;
+
+// Solves the tridiagonal system with the Thomas algorithm
+fn solve_tridiagonal(lower: &[f64], diagonal: &[f64], upper: &[f64], rhs: &[f64]) -> Vec<f64> {
+    let n = rhs.len();
+    let mut c = vec![0.0; n];
+    let mut x = vec![0.0; n];
+    c[0] = upper[0] / diagonal[0];
+    x[0] = rhs[0] / diagonal[0];
+    for i in 1..n {
+        let m = diagonal[i] - lower[i] * c[i - 1];
+        c[i] = upper[i] / m;
+        x[i] = (rhs[i] - lower[i] * x[i - 1]) / m;
+    }
+    for i in (0..n - 1).rev() {
+        x[i] -= c[i] * x[i + 1];
+    }
+    x
+}
+
+// Solves the tridiagonal linear complementarity problem x ≥ floor by
+// projected successive over-relaxation, starting from `x`
+fn psor(
+    lower: &[f64],
+    diagonal: &[f64],
+    upper: &[f64],
+    rhs: &[f64],
+    floor: &[f64],
+    mut x: Vec<f64>,
+) -> Vec<f64> {
+    let n = rhs.len();
+    for (value, &floor) in x.iter_mut().zip(floor) {
+        *value = value.max(floor);
+    }
+    for _ in 0..PSOR_ITERATIONS {
+        let mut change = 0.0;
+        for i in 0..n {
+            let mut residual = rhs[i] - diagonal[i] * x[i];
+            if i > 0 {
+                residual -= lower[i] * x[i - 1];
+            }
+            if i + 1 < n {
+                residual -= upper[i] * x[i + 1];
+            }
+            let value = (x[i] + PSOR_RELAXATION * residual / diagonal[i]).max(floor[i]);
+            change += (value - x[i]) * (value - x[i]);
+            x[i] = value;
+        }
+        if change < PSOR_PRECISION {
+            break;
+        }
+    }
+    x
+}
+
+/// A finite-difference solver on a fixed spot grid with uniform time steps.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::lattice::Exercise;
+/// use black_scholes::pde::{sinh_grid, PdeSolver};
+/// let grid = sinh_grid(0.0, 400.0, 301, 100.0, 0.1).unwrap();
+/// let solver = PdeSolver::new(grid, 200)
+///     .unwrap()
+///     .with_exercise(Exercise::American);
+/// let strike = 100.0;
+/// let put = solver.price(
+///     |s| (strike - s).max(0.0),
+///     100.0,
+///     |_| 0.05,
+///     0.0,
+///     |_| 0.2,
+///     1.0,
+/// );
+/// assert!(put.price > black_scholes::put(100.0, strike, 0.05, 0.2, 1.0));
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PdeSolver {
+    grid: Vec<f64>,
+    time_steps: usize,
+    rannacher_steps: usize,
+    exercise: Exercise,
+    lower_barrier: Option<Barrier>,
+    upper_barrier: Option<Barrier>,
+}
+
+impl PdeSolver {
+    /// Returns a European solver on the spot `grid` with `time_steps` steps
+    /// and two Rannacher steps, or `None` unless the grid has at least three
+    /// finite, strictly increasing nodes.
+    pub fn new(grid: Vec<f64>, time_steps: usize) -> Option<Self> {
+        Some(PdeSolver {
+            grid: valid_grid(grid)?,
+            time_steps: time_steps.max(1),
+            rannacher_steps: 2,
+            exercise: Exercise::European,
+            lower_barrier: None,
+            upper_barrier: None,
+        })
+    }
+    pub fn with_exercise(self, exercise: Exercise) -> Self {
+        PdeSolver { exercise, ..self }
+    }
+    /// Sets the number of Crank-Nicolson steps, starting at maturity, that
+    /// are each replaced by two fully implicit half steps.
+    pub fn with_rannacher_steps(self, rannacher_steps: usize) -> Self {
+        PdeSolver {
+            rannacher_steps,
+            ..self
+        }
+    }
+    /// Truncates the grid at a down-and-out barrier.
+    pub fn with_lower_barrier(self, barrier: Barrier) -> Self {
+        PdeSolver {
+            lower_barrier: Some(barrier),
+            ..self
+        }
+    }
+    /// Truncates the grid at an up-and-out barrier.
+    pub fn with_upper_barrier(self, barrier: Barrier) -> Self {
+        PdeSolver {
+            upper_barrier: Some(barrier),
+            ..self
+        }
+    }
+
+    // the grid truncated at the barriers, which become its end nodes
+    fn spot_grid(&self) -> Vec<f64> {
+        let mut grid: Vec<f64> = self
+            .grid
+            .iter()
+            .copied()
+            .filter(|&s| {
+                self.lower_barrier.is_none_or(|barrier| s > barrier.level)
+                    && self.upper_barrier.is_none_or(|barrier| s < barrier.level)
+            })
+            .collect();
+        if let Some(barrier) = self.lower_barrier {
+            grid.insert(0, barrier.level);
+        }
+        if let Some(barrier) = self.upper_barrier {
+            grid.push(barrier.level);
+        }
+        grid
+    }
+
+    /// Returns the value today of the option paying `payoff(S)` at
+    /// `maturity`, with delta and gamma from the grid around `stock` and
+    /// theta from the last time step.  Rates and volatilities are functions
+    /// of the time from today, evaluated at the middle of each step.
+    /// Returns NaN if `stock` is outside the grid.
+    pub fn price(
+        &self,
+        payoff: impl Fn(f64) -> f64,
+        stock: f64,
+        risk_free_rate: impl Fn(f64) -> f64,
+        dividend_yield: f64,
+        sigma: impl Fn(f64) -> f64,
+        maturity: f64,
+    ) -> PdePriceAndGreeks {
+        let grid = self.spot_grid();
+        let n = grid.len();
+        if n < 3 || stock < grid[0] || stock > grid[n - 1] {
+            return PdePriceAndGreeks {
+                price: f64::NAN,
+                delta: f64::NAN,
+                gamma: f64::NAN,
+                theta: f64::NAN,
+            };
+        }
+        let intrinsic: Vec<f64> = grid.iter().map(|&s| payoff(s)).collect();
+        let mut values = intrinsic.clone();
+        let lower_rebate = self.lower_barrier.map(|barrier| barrier.rebate);
+        let upper_rebate = self.upper_barrier.map(|barrier| barrier.rebate);
+        let mut floor = intrinsic.clone();
+        if let Some(rebate) = lower_rebate {
+            values[0] = rebate;
+            floor[0] = rebate;
+        }
+        if let Some(rebate) = upper_rebate {
+            values[n - 1] = rebate;
+            floor[n - 1] = rebate;
+        }
+
+        // one step back in time from `later` to `earlier`, weighting the
+        // implicit part by `implicitness`
+        let step = |values: &[f64], later: f64, earlier: f64, implicitness: f64| -> Vec<f64> {
+            let dt = later - earlier;
+            let middle = 0.5 * (later + earlier);
+            let rate = risk_free_rate(middle);
+            let variance = sigma(middle).powi(2);
+            let drift = rate - dividend_yield;
+            // the spatial operator L as a tridiagonal matrix
+            let mut l_lower = vec![0.0; n];
+            let mut l_diagonal = vec![0.0; n];
+            let mut l_upper = vec![0.0; n];
+            for i in 1..n - 1 {
+                let h_minus = grid[i] - grid[i - 1];
+                let h_plus = grid[i + 1] - grid[i];
+                let h_sum = h_minus + h_plus;
+                let diffusion = 0.5 * variance * grid[i] * grid[i];
+                let convection = drift * grid[i];
+                l_lower[i] = (2.0 * diffusion - convection * h_plus) / (h_minus * h_sum);
+                l_diagonal[i] = (-2.0 * diffusion + convection * (h_plus - h_minus))
+                    / (h_minus * h_plus)
+                    - rate;
+                l_upper[i] = (2.0 * diffusion + convection * h_minus) / (h_plus * h_sum);
+            }
+            // linear in S at the free ends: no diffusion and one-sided first
+            // derivatives
+            let h_first = grid[1] - grid[0];
+            l_diagonal[0] = -drift * grid[0] / h_first - rate;
+            l_upper[0] = drift * grid[0] / h_first;
+            let h_last = grid[n - 1] - grid[n - 2];
+            l_lower[n - 1] = -drift * grid[n - 1] / h_last;
+            l_diagonal[n - 1] = drift * grid[n - 1] / h_last - rate;
+
+            let explicitness = 1.0 - implicitness;
+            let mut rhs: Vec<f64> = (0..n)
+                .map(|i| {
+                    let mut lv = l_diagonal[i] * values[i];
+                    if i > 0 {
+                        lv += l_lower[i] * values[i - 1];
+                    }
+                    if i + 1 < n {
+                        lv += l_upper[i] * values[i + 1];
+                    }
+                    values[i] + explicitness * dt * lv
+                })
+                .collect();
+            let mut lower: Vec<f64> = l_lower.iter().map(|l| -implicitness * dt * l).collect();
+            let mut diagonal: Vec<f64> = l_diagonal
+                .iter()
+                .map(|l| 1.0 - implicitness * dt * l)
+                .collect();
+            let mut upper: Vec<f64> = l_upper.iter().map(|l| -implicitness * dt * l).collect();
+            if let Some(rebate) = lower_rebate {
+                diagonal[0] = 1.0;
+                upper[0] = 0.0;
+                rhs[0] = rebate;
+            }
+            if let Some(rebate) = upper_rebate {
+                diagonal[n - 1] = 1.0;
+                lower[n - 1] = 0.0;
+                rhs[n - 1] = rebate;
+            }
+            if self.exercise == Exercise::American {
+                psor(&lower, &diagonal, &upper, &rhs, &floor, values.to_vec())
+            } else {
+                solve_tridiagonal(&lower, &diagonal, &upper, &rhs)
+            }
+        };
+
+        let dt = maturity / self.time_steps as f64;
+        let mut previous = values.clone();
+        for i in (0..self.time_steps).rev() {
+            let later = (i + 1) as f64 * dt;
+            let earlier = i as f64 * dt;
+            previous = values;
+            values = if self.time_steps - i <= self.rannacher_steps {
+                let middle = 0.5 * (later + earlier);
+                let half = step(&previous, later, middle, 1.0);
+                step(&half, middle, earlier, 1.0)
+            } else {
+                step(&previous, later, earlier, 0.5)
+            };
+            let exercisable = matches!(&self.exercise, Exercise::Bermudan(times)
+                if times.iter().any(|time| (time / dt).round() as usize == i));
+            if exercisable {
+                for (value, &floor) in values.iter_mut().zip(&floor) {
+                    *value = value.max(floor);
+                }
+            }
+        }
+
+        // quadratic through the three nodes centred nearest to the stock
+        let centre = grid.partition_point(|&s| s < stock).clamp(1, n - 2);
+        let centre = if centre > 1 && stock - grid[centre - 1] < grid[centre] - stock {
+            centre - 1
+        } else {
+            centre
+        };
+        let (x0, x1, x2) = (grid[centre - 1], grid[centre], grid[centre + 1]);
+        let interpolate = |v: &[f64]| -> (f64, f64, f64) {
+            let (v0, v1, v2) = (v[centre - 1], v[centre], v[centre + 1]);
+            let w0 = v0 / ((x0 - x1) * (x0 - x2));
+            let w1 = v1 / ((x1 - x0) * (x1 - x2));
+            let w2 = v2 / ((x2 - x0) * (x2 - x1));
+            let value = w0 * (stock - x1) * (stock - x2)
+                + w1 * (stock - x0) * (stock - x2)
+                + w2 * (stock - x0) * (stock - x1);
+            let first = w0 * (2.0 * stock - x1 - x2)
+                + w1 * (2.0 * stock - x0 - x2)
+                + w2 * (2.0 * stock - x0 - x1);
+            (value, first, 2.0 * (w0 + w1 + w2))
+        };
+        let (price, delta, gamma) = interpolate(&values);
+        let (later_price, _, _) = interpolate(&previous);
+        PdePriceAndGreeks {
+            price,
+            delta,
+            gamma,
+            theta: (later_price - price) / dt,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lattice::{Tree, TreeKind};
+    use crate::{bsm_compute_all, call, compute_all, cum_norm};
+    use approx::*;
+
+    #[test]
+    fn rejects_unusable_grids() {
+        assert!(PdeSolver::new(vec![90.0, 110.0], 10).is_none());
+        assert!(PdeSolver::new(vec![90.0, 110.0, 100.0], 10).is_none());
+        assert!(PdeSolver::new(vec![90.0, 100.0, 100.0], 10).is_none());
+        assert!(PdeSolver::new(vec![90.0, 100.0, f64::NAN], 10).is_none());
+        assert!(PdeSolver::new(vec![90.0, 100.0, 110.0], 10).is_some());
+        assert!(uniform_grid(0.0, 100.0, 2).is_none());
+        assert!(uniform_grid(100.0, 0.0, 11).is_none());
+        assert!(sinh_grid(0.0, 100.0, 1, 50.0, 0.1).is_none());
+        assert!(sinh_grid(0.0, 100.0, 11, 50.0, 0.0).is_none());
+    }
+    #[test]
+    fn european_matches_compute_all() {
+        let (s, k, rate, sigma, maturity) = (100.0, 105.0, 0.05, 0.25, 1.0);
+        let solver = PdeSolver::new(sinh_grid(0.0, 500.0, 401, k, 0.1).unwrap(), 400).unwrap();
+        let exact = compute_all(s, k, rate, sigma, maturity);
+        let call = solver.price(|x| (x - k).max(0.0), s, |_| rate, 0.0, |_| sigma, maturity);
+        let put = solver.price(|x| (k - x).max(0.0), s, |_| rate, 0.0, |_| sigma, maturity);
+        assert_abs_diff_eq!(call.price, exact.call_price, epsilon = 1e-3);
+        assert_abs_diff_eq!(call.delta, exact.call_delta, epsilon = 1e-4);
+        assert_abs_diff_eq!(call.gamma, exact.call_gamma, epsilon = 1e-4);
+        assert_abs_diff_eq!(call.theta, exact.call_theta, epsilon = 1e-2);
+        assert_abs_diff_eq!(put.price, exact.put_price, epsilon = 1e-3);
+        assert_abs_diff_eq!(put.delta, exact.put_delta, epsilon = 1e-4);
+        assert_abs_diff_eq!(put.theta, exact.put_theta, epsilon = 1e-2);
+    }
+    #[test]
+    fn european_with_dividend_yield() {
+        let (s, k, sigma, rate, q, maturity) = (90.0, 100.0, 0.3, 0.03, 0.06, 0.5);
+        let solver = PdeSolver::new(uniform_grid(0.0, 400.0, 801).unwrap(), 200).unwrap();
+        let exact = bsm_compute_all(s, k, sigma, rate, q, maturity);
+        let call = solver.price(|x| (x - k).max(0.0), s, |_| rate, q, |_| sigma, maturity);
+        assert_abs_diff_eq!(call.price, exact.call_price, epsilon = 1e-3);
+        assert_abs_diff_eq!(call.delta, exact.call_delta, epsilon = 1e-4);
+        assert_abs_diff_eq!(call.gamma, exact.call_gamma, epsilon = 1e-5);
+    }
+    #[test]
+    fn time_dependent_rate_and_volatility() {
+        // a European option depends only on the average rate and variance
+        let (s, k, maturity) = (100.0, 100.0, 1.0);
+        let rate = |t: f64| 0.03 + 0.02 * t;
+        let sigma = |t: f64| 0.2 + 0.1 * t;
+        let average_rate = 0.04;
+        let average_sigma = (0.04f64 + 0.02 + 0.01 / 3.0).sqrt();
+        let solver = PdeSolver::new(sinh_grid(0.0, 500.0, 401, k, 0.1).unwrap(), 400).unwrap();
+        let price = solver
+            .price(|x| (x - k).max(0.0), s, rate, 0.0, sigma, maturity)
+            .price;
+        assert_abs_diff_eq!(
+            price,
+            call(s, k, average_rate, average_sigma, maturity),
+            epsilon = 1e-3
+        );
+    }
+    #[test]
+    fn american_put_matches_tree() {
+        let (s, k, sigma, rate, maturity) = (100.0, 100.0, 0.2, 0.08, 0.25);
+        let solver = PdeSolver::new(sinh_grid(0.0, 400.0, 401, k, 0.1).unwrap(), 400)
+            .unwrap()
+            .with_exercise(Exercise::American);
+        let put = solver.price(|x| (k - x).max(0.0), s, |_| rate, 0.0, |_| sigma, maturity);
+        // 2000 step CRR tree
+        assert_abs_diff_eq!(put.price, 3.2247, epsilon = 2e-3);
+        let tree = Tree::new(TreeKind::LeisenReimer, 1001)
+            .with_exercise(Exercise::American)
+            .put(s, k, sigma, rate, 0.0, maturity);
+        assert_abs_diff_eq!(put.delta, tree.delta, epsilon = 1e-3);
+        assert_abs_diff_eq!(put.gamma, tree.gamma, epsilon = 1e-3);
+        // deep in the money it is exercised
+        let deep = solver.price(
+            |x| (k - x).max(0.0),
+            70.0,
+            |_| rate,
+            0.0,
+            |_| sigma,
+            maturity,
+        );
+        assert_abs_diff_eq!(deep.price, 30.0, epsilon = 1e-10);
+    }
+    #[test]
+    fn bermudan_matches_tree() {
+        let (s, k, sigma, rate, maturity) = (100.0, 110.0, 0.3, 0.06, 1.0);
+        let exercise = Exercise::Bermudan(vec![0.25, 0.5, 0.75]);
+        let solver = PdeSolver::new(sinh_grid(0.0, 500.0, 401, k, 0.1).unwrap(), 400)
+            .unwrap()
+            .with_exercise(exercise.clone());
+        let price = solver
+            .price(|x| (k - x).max(0.0), s, |_| rate, 0.0, |_| sigma, maturity)
+            .price;
+        let tree = Tree::new(TreeKind::CoxRossRubinstein, 2000)
+            .with_exercise(exercise)
+            .put(s, k, sigma, rate, 0.0, maturity)
+            .price;
+        assert_abs_diff_eq!(price, tree, epsilon = 5e-3);
+    }
+    #[test]
+    fn down_and_out_call_matches_closed_form() {
+        let (s, k, h, rate, q, sigma, maturity) = (100.0, 100.0, 90.0, 0.05, 0.02, 0.25, 1.0);
+        let vanilla = |x: f64| bsm_compute_all(x, k, sigma, rate, q, maturity).call_price;
+        let exponent = 2.0 * (rate - q) / (sigma * sigma) - 1.0;
+        let exact = vanilla(s) - (h / s).powf(exponent) * vanilla(h * h / s);
+        let solver = PdeSolver::new(sinh_grid(0.0, 500.0, 401, k, 0.1).unwrap(), 400)
+            .unwrap()
+            .with_lower_barrier(Barrier {
+                level: h,
+                rebate: 0.0,
+            });
+        let price = solver
+            .price(|x| (x - k).max(0.0), s, |_| rate, q, |_| sigma, maturity)
+            .price;
+        assert_abs_diff_eq!(price, exact, epsilon = 2e-3);
+    }
+    #[test]
+    fn rebate_is_paid_at_the_barrier() {
+        // a zero payoff with a rebate on hitting either barrier is worth the
+        // rebate at the barrier and increases towards it
+        let barrier = Barrier {
+            level: 120.0,
+            rebate: 1.0,
+        };
+        let solver = PdeSolver::new(uniform_grid(0.0, 200.0, 401).unwrap(), 100)
+            .unwrap()
+            .with_lower_barrier(Barrier {
+                level: 80.0,
+                rebate: 1.0,
+            })
+            .with_upper_barrier(barrier);
+        let price = |s: f64| solver.price(|_| 0.0, s, |_| 0.05, 0.0, |_| 0.2, 1.0).price;
+        assert_abs_diff_eq!(price(120.0), 1.0, epsilon = 1e-12);
+        assert!(price(100.0) > 0.0 && price(100.0) < price(115.0) && price(115.0) < 1.0);
+        assert!(price(79.0).is_nan());
+    }
+    #[test]
+    fn rannacher_start_up_smooths_a_digital() {
+        let (s, k, rate, sigma, maturity): (f64, f64, f64, f64, f64) =
+            (100.0, 100.0, 0.05, 0.2, 0.5);
+        let sqrt_maturity_sigma = sigma * f64::sqrt(maturity);
+        let d2 = ((s / k).ln() + (rate - 0.5 * sigma * sigma) * maturity) / sqrt_maturity_sigma;
+        let exact = (-rate * maturity).exp() * cum_norm(d2);
+        let digital = |x: f64| if x > k { 1.0 } else { 0.0 };
+        // strike half way between nodes, and long time steps relative to
+        // the grid spacing, where Crank-Nicolson alone oscillates
+        let grid = uniform_grid(0.125, 300.125, 1201).unwrap();
+        let smoothed = PdeSolver::new(grid.clone(), 25).unwrap().price(
+            digital,
+            s,
+            |_| rate,
+            0.0,
+            |_| sigma,
+            maturity,
+        );
+        let crank_nicolson = PdeSolver::new(grid, 25)
+            .unwrap()
+            .with_rannacher_steps(0)
+            .price(digital, s, |_| rate, 0.0, |_| sigma, maturity);
+        assert_abs_diff_eq!(smoothed.price, exact, epsilon = 1e-3);
+        let exact_gamma = -(-rate * maturity).exp() * (-0.5 * d2 * d2).exp()
+            / (2.0 * std::f64::consts::PI).sqrt()
+            * (d2 + sqrt_maturity_sigma)
+            / (s * s * sqrt_maturity_sigma * sqrt_maturity_sigma);
+        assert_abs_diff_eq!(smoothed.gamma, exact_gamma, epsilon = 5e-5);
+        assert!((crank_nicolson.gamma - exact_gamma).abs() > 1.0);
+    }
+}
@@ -0,0 +1,455 @@
+//! Options on the better or worse performer of two assets (Stulz, 1982),
+//! struck at a common strike.
+//!
+//! Best-of calls pay `max(S₁, S₂) - K` and worst-of calls `min(S₁, S₂) - K`
+//! if positive, and the puts pay the reverse.  Greeks are central finite
+//! differences.
+use crate::spread::finite_difference_two_asset_greeks;
+use crate::stats::bivariate_cum_norm;
+use crate::{TwoAssetPricesAndGreeks, cum_norm};
+
+// Black-Scholes d1 of an asset with the given discounted forward against
+// the discounted strike, taking its limit when the asset is deterministic
+fn d1(forward_value: f64, strike_value: f64, sqrt_maturity_sigma: f64) -> f64 {
+    let log_moneyness = (forward_value / strike_value).ln();
+    if sqrt_maturity_sigma > 0.0 {
+        log_moneyness / sqrt_maturity_sigma + 0.5 * sqrt_maturity_sigma
+    } else if log_moneyness == 0.0 {
+        0.0
+    } else {
+        log_moneyness.signum() * f64::INFINITY
+    }
+}
+
+// Prices (call, put) on the minimum (omega = -1) or maximum (omega = 1) of
+// the two assets
+#[allow(clippy::too_many_arguments)]
+fn prices(
+    omega: f64,
+    stock_1: f64,
+    stock_2: f64,
+    strike: f64,
+    sigma_1: f64,
+    sigma_2: f64,
+    correlation: f64,
+    risk_free_rate: f64,
+    dividend_yield_1: f64,
+    dividend_yield_2: f64,
+    maturity: f64,
+) -> (f64, f64) {
+    let sqrt_maturity = maturity.sqrt();
+    let forward_value_1 = stock_1 * (-dividend_yield_1 * maturity).exp();
+    let forward_value_2 = stock_2 * (-dividend_yield_2 * maturity).exp();
+    let strike_value = strike * (-risk_free_rate * maturity).exp();
+    let sigma = (sigma_1 * sigma_1 + sigma_2 * sigma_2 - 2.0 * correlation * sigma_1 * sigma_2)
+        .max(0.0)
+        .sqrt();
+    let sqrt_maturity_sigma = sigma * sqrt_maturity;
+    // Margrabe's d for receiving asset 1 against asset 2, and the value
+    // today of receiving the better or worse asset at maturity
+    let exchange_d1 = if sqrt_maturity_sigma > 0.0 {
+        (forward_value_1 / forward_value_2).ln() / sqrt_maturity_sigma + 0.5 * sqrt_maturity_sigma
+    } else {
+        0.0
+    };
+    let exchange_d2 = sqrt_maturity_sigma - exchange_d1;
+    let exchange = if sqrt_maturity_sigma > 0.0 {
+        forward_value_1 * cum_norm(exchange_d1) - forward_value_2 * cum_norm(-exchange_d2)
+    } else {
+        (forward_value_1 - forward_value_2).max(0.0)
+    };
+    let extreme_value = if omega > 0.0 {
+        forward_value_2 + exchange
+    } else {
+        forward_value_1 - exchange
+    };
+    if strike_value <= 0.0 {
+        // both assets finish above the strike, so the call is always exercised
+        return (extreme_value - strike_value, 0.0);
+    }
+    let (sqrt_maturity_sigma_1, sqrt_maturity_sigma_2) =
+        (sigma_1 * sqrt_maturity, sigma_2 * sqrt_maturity);
+    let d_1 = d1(forward_value_1, strike_value, sqrt_maturity_sigma_1);
+    let d_2 = d1(forward_value_2, strike_value, sqrt_maturity_sigma_2);
+    let call = if sqrt_maturity_sigma > 0.0 {
+        // correlations of each asset with the ratio S₁/S₂
+        let rho_1 = ((sigma_1 - correlation * sigma_2) / sigma).clamp(-1.0, 1.0);
+        let rho_2 = ((sigma_2 - correlation * sigma_1) / sigma).clamp(-1.0, 1.0);
+        forward_value_1 * bivariate_cum_norm(d_1, omega * exchange_d1, omega * rho_1)
+            + forward_value_2 * bivariate_cum_norm(d_2, omega * exchange_d2, omega * rho_2)
+            - strike_value
+                * if omega > 0.0 {
+                    1.0 - bivariate_cum_norm(
+                        sqrt_maturity_sigma_1 - d_1,
+                        sqrt_maturity_sigma_2 - d_2,
+                        correlation,
+                    )
+                } else {
+                    bivariate_cum_norm(
+                        d_1 - sqrt_maturity_sigma_1,
+                        d_2 - sqrt_maturity_sigma_2,
+                        correlation,
+                    )
+                }
+    } else {
+        // the ratio of the assets is known, and so is which one is the
+        // better at maturity
+        let (forward_value, d, sqrt_maturity_sigma) =
+            if (forward_value_1 > forward_value_2) == (omega > 0.0) {
+                (forward_value_1, d_1, sqrt_maturity_sigma_1)
+            } else {
+                (forward_value_2, d_2, sqrt_maturity_sigma_2)
+            };
+        forward_value * cum_norm(d) - strike_value * cum_norm(d - sqrt_maturity_sigma)
+    };
+    (call, call - extreme_value + strike_value)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn compute_all(
+    omega: f64,
+    stock_1: f64,
+    stock_2: f64,
+    strike: f64,
+    sigma_1: f64,
+    sigma_2: f64,
+    correlation: f64,
+    risk_free_rate: f64,
+    dividend_yield_1: f64,
+    dividend_yield_2: f64,
+    maturity: f64,
+) -> TwoAssetPricesAndGreeks {
+    finite_difference_two_asset_greeks(
+        |stock_1, stock_2, sigma_1, sigma_2, correlation| {
+            prices(
+                omega,
+                stock_1,
+                stock_2,
+                strike,
+                sigma_1,
+                sigma_2,
+                correlation,
+                risk_free_rate,
+                dividend_yield_1,
+                dividend_yield_2,
+                maturity,
+            )
+        },
+        stock_1,
+        stock_2,
+        sigma_1,
+        sigma_2,
+        correlation,
+    )
+}
+
+/// Returns the prices and greeks of calls and puts on the better performer
+/// of two assets.
+///
+/// # Examples
+///
+/// ```
+/// let stock_1 = 100.0;
+/// let stock_2 = 105.0;
+/// let strike = 98.0;
+/// let sigma_1 = 0.11;
+/// let sigma_2 = 0.16;
+/// let correlation = 0.63;
+/// let risk_free_rate = 0.05;
+/// let dividend_yield_1 = 0.06;
+/// let dividend_yield_2 = 0.09;
+/// let maturity = 0.5;
+/// let best_of = black_scholes::rainbow::best_of(
+///     stock_1, stock_2, strike, sigma_1, sigma_2, correlation,
+///     risk_free_rate, dividend_yield_1, dividend_yield_2, maturity
+/// );
+/// assert!(best_of.call_delta_1 > 0.0 && best_of.call_delta_2 > 0.0);
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn best_of(
+    stock_1: f64,
+    stock_2: f64,
+    strike: f64,
+    sigma_1: f64,
+    sigma_2: f64,
+    correlation: f64,
+    risk_free_rate: f64,
+    dividend_yield_1: f64,
+    dividend_yield_2: f64,
+    maturity: f64,
+) -> TwoAssetPricesAndGreeks {
+    compute_all(
+        1.0,
+        stock_1,
+        stock_2,
+        strike,
+        sigma_1,
+        sigma_2,
+        correlation,
+        risk_free_rate,
+        dividend_yield_1,
+        dividend_yield_2,
+        maturity,
+    )
+}
+
+/// Returns the prices and greeks of calls and puts on the worse performer
+/// of two assets.
+///
+/// # Examples
+///
+/// ```
+/// let worst_of = black_scholes::rainbow::worst_of(
+///     100.0, 105.0, 98.0, 0.11, 0.16, 0.63, 0.05, 0.06, 0.09, 0.5
+/// );
+/// // a worst-of put gains from lower correlation
+/// assert!(worst_of.put_correlation_sensitivity < 0.0);
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn worst_of(
+    stock_1: f64,
+    stock_2: f64,
+    strike: f64,
+    sigma_1: f64,
+    sigma_2: f64,
+    correlation: f64,
+    risk_free_rate: f64,
+    dividend_yield_1: f64,
+    dividend_yield_2: f64,
+    maturity: f64,
+) -> TwoAssetPricesAndGreeks {
+    compute_all(
+        -1.0,
+        stock_1,
+        stock_2,
+        strike,
+        sigma_1,
+        sigma_2,
+        correlation,
+        risk_free_rate,
+        dividend_yield_1,
+        dividend_yield_2,
+        maturity,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bsm_compute_all;
+    use crate::monte_carlo::MonteCarlo;
+    use crate::spread::margrabe;
+    use approx::*;
+
+    #[test]
+    fn best_and_worst_of_add_to_vanillas() {
+        let (s_1, s_2, strike, sigma_1, sigma_2, correlation, rate, q_1, q_2, maturity) =
+            (95.0, 110.0, 100.0, 0.3, 0.2, -0.4, 0.04, 0.01, 0.03, 1.5);
+        let best = best_of(
+            s_1,
+            s_2,
+            strike,
+            sigma_1,
+            sigma_2,
+            correlation,
+            rate,
+            q_1,
+            q_2,
+            maturity,
+        );
+        let worst = worst_of(
+            s_1,
+            s_2,
+            strike,
+            sigma_1,
+            sigma_2,
+            correlation,
+            rate,
+            q_1,
+            q_2,
+            maturity,
+        );
+        let vanilla_1 = bsm_compute_all(s_1, strike, sigma_1, rate, q_1, maturity);
+        let vanilla_2 = bsm_compute_all(s_2, strike, sigma_2, rate, q_2, maturity);
+        assert_abs_diff_eq!(
+            best.call_price + worst.call_price,
+            vanilla_1.call_price + vanilla_2.call_price,
+            epsilon = 1e-12
+        );
+        assert_abs_diff_eq!(
+            best.put_price + worst.put_price,
+            vanilla_1.put_price + vanilla_2.put_price,
+            epsilon = 1e-12
+        );
+        assert_abs_diff_eq!(
+            best.call_delta_1 + worst.call_delta_1,
+            vanilla_1.call_delta,
+            epsilon = 1e-7
+        );
+    }
+    #[test]
+    fn matches_simulation() {
+        let (s_1, s_2, strike, sigma_1, sigma_2, correlation, rate, q_1, q_2, maturity): (
+            f64,
+            f64,
+            f64,
+            f64,
+            f64,
+            f64,
+            f64,
+            f64,
+            f64,
+            f64,
+        ) = (100.0, 90.0, 95.0, 0.25, 0.35, 0.5, 0.03, 0.0, 0.02, 1.0);
+        let paths = 200_000;
+        let sqrt_maturity = f64::sqrt(maturity);
+        let orthogonal = (1.0 - correlation * correlation).sqrt();
+        let terminal = |s: f64, sigma: f64, q: f64, z: f64| {
+            s * ((rate - q - 0.5 * sigma * sigma) * maturity + sigma * sqrt_maturity * z).exp()
+        };
+        // sums of the best-of call, worst-of put and its square
+        let mut sums = [0.0; 3];
+        MonteCarlo::new(paths, 2, 17).for_each_draw(|_, normals| {
+            let s_1 = terminal(s_1, sigma_1, q_1, normals[0]);
+            let s_2 = terminal(
+                s_2,
+                sigma_2,
+                q_2,
+                correlation * normals[0] + orthogonal * normals[1],
+            );
+            let worst_put = (strike - s_1.min(s_2)).max(0.0);
+            sums[0] += (s_1.max(s_2) - strike).max(0.0);
+            sums[1] += worst_put;
+            sums[2] += worst_put * worst_put;
+        });
+        let n = paths as f64;
+        let discount = (-rate * maturity).exp();
+        let best = best_of(
+            s_1,
+            s_2,
+            strike,
+            sigma_1,
+            sigma_2,
+            correlation,
+            rate,
+            q_1,
+            q_2,
+            maturity,
+        );
+        let worst = worst_of(
+            s_1,
+            s_2,
+            strike,
+            sigma_1,
+            sigma_2,
+            correlation,
+            rate,
+            q_1,
+            q_2,
+            maturity,
+        );
+        let put_standard_error = discount * ((sums[2] / n - (sums[1] / n).powi(2)) / n).sqrt();
+        assert!((worst.put_price - discount * sums[1] / n).abs() < 4.0 * put_standard_error);
+        assert_relative_eq!(best.call_price, discount * sums[0] / n, max_relative = 1e-2);
+    }
+    #[test]
+    fn perfectly_correlated_assets() {
+        // with equal volatilities the ratio of the assets is fixed, and the
+        // best of is a vanilla on the asset with the higher forward
+        let best = best_of(100.0, 100.0, 100.0, 0.2, 0.2, 1.0, 0.05, 0.0, 0.0, 1.0);
+        let vanilla = bsm_compute_all(100.0, 100.0, 0.2, 0.05, 0.0, 1.0);
+        assert_abs_diff_eq!(best.call_price, vanilla.call_price, epsilon = 1e-12);
+        assert_abs_diff_eq!(best.put_price, vanilla.put_price, epsilon = 1e-12);
+        let worst = worst_of(100.0, 95.0, 100.0, 0.2, 0.2, 1.0, 0.05, 0.0, 0.0, 1.0);
+        let vanilla = bsm_compute_all(95.0, 100.0, 0.2, 0.05, 0.0, 1.0);
+        assert_abs_diff_eq!(worst.call_price, vanilla.call_price, epsilon = 1e-12);
+        assert_abs_diff_eq!(worst.put_price, vanilla.put_price, epsilon = 1e-12);
+        // otherwise the prices are continuous in the correlation
+        for correlation in [1.0, -1.0] {
+            let limit = correlation * (1.0 - 1e-10);
+            let at = best_of(
+                100.0,
+                95.0,
+                98.0,
+                0.2,
+                0.3,
+                correlation,
+                0.05,
+                0.01,
+                0.0,
+                1.0,
+            );
+            let near = best_of(100.0, 95.0, 98.0, 0.2, 0.3, limit, 0.05, 0.01, 0.0, 1.0);
+            assert_abs_diff_eq!(at.call_price, near.call_price, epsilon = 1e-6);
+            assert_abs_diff_eq!(at.put_price, near.put_price, epsilon = 1e-6);
+            let at = worst_of(
+                100.0,
+                95.0,
+                98.0,
+                0.2,
+                0.3,
+                correlation,
+                0.05,
+                0.01,
+                0.0,
+                1.0,
+            );
+            let near = worst_of(100.0, 95.0, 98.0, 0.2, 0.3, limit, 0.05, 0.01, 0.0, 1.0);
+            assert_abs_diff_eq!(at.call_price, near.call_price, epsilon = 1e-6);
+            assert_abs_diff_eq!(at.put_price, near.put_price, epsilon = 1e-6);
+        }
+    }
+    #[test]
+    fn zero_strike_is_exchange_option() {
+        let (s_1, s_2, sigma_1, sigma_2, correlation, q_1, q_2, maturity) =
+            (100.0, 95.0, 0.2, 0.3, 0.5, 0.02, 0.01, 1.5);
+        let exchange = margrabe(s_1, s_2, sigma_1, sigma_2, correlation, q_1, q_2, maturity);
+        let best = best_of(
+            s_1,
+            s_2,
+            0.0,
+            sigma_1,
+            sigma_2,
+            correlation,
+            0.05,
+            q_1,
+            q_2,
+            maturity,
+        );
+        let worst = worst_of(
+            s_1,
+            s_2,
+            0.0,
+            sigma_1,
+            sigma_2,
+            correlation,
+            0.05,
+            q_1,
+            q_2,
+            maturity,
+        );
+        let forward_value_1 = s_1 * (-q_1 * maturity).exp();
+        let forward_value_2 = s_2 * (-q_2 * maturity).exp();
+        assert_abs_diff_eq!(
+            best.call_price,
+            forward_value_2 + exchange.call_price,
+            epsilon = 1e-12
+        );
+        assert_abs_diff_eq!(
+            worst.call_price,
+            forward_value_1 - exchange.call_price,
+            epsilon = 1e-12
+        );
+        assert_eq!(best.put_price, 0.0);
+        assert_eq!(worst.put_price, 0.0);
+    }
+    #[test]
+    fn expiry_is_intrinsic() {
+        let best = best_of(100.0, 95.0, 98.0, 0.2, 0.3, 0.5, 0.05, 0.0, 0.0, 0.0);
+        let worst = worst_of(100.0, 95.0, 98.0, 0.2, 0.3, 0.5, 0.05, 0.0, 0.0, 0.0);
+        assert_eq!(best.call_price, 2.0);
+        assert_eq!(best.put_price, 0.0);
+        assert_eq!(worst.call_price, 0.0);
+        assert_eq!(worst.put_price, 3.0);
+    }
+}
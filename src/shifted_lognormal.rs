@@ -0,0 +1,228 @@
+//! Shifted (displaced-diffusion) lognormal model for options on a forward.
+//!
+//! `F + shift` follows a driftless lognormal process, so this is Black-76
+//! applied to the displaced forward `F + shift` and strike `K + shift`.
+//! Negative-rate caplets and swaptions are quoted in shifted-Black vol.
+//! Both `F + shift` and `K + shift` must be positive.
+use crate::{IvError, PricesAndGreeks, black76};
+
+/// Returns call and put prices and greeks under the shifted lognormal model.
+/// Greeks are with respect to the (unshifted) forward price.
+///
+/// # Examples
+///
+/// ```
+/// let forward = -0.002;
+/// let strike = 0.001;
+/// let shift = 0.03;
+/// let rate = 0.01;
+/// let sigma = 0.2;
+/// let maturity = 2.0;
+/// let all_prices_and_greeks = black_scholes::shifted_lognormal::compute_all(
+///     forward,
+///     strike,
+///     shift,
+///     rate,
+///     sigma,
+///     maturity,
+/// );
+/// ```
+pub fn compute_all(
+    forward_price: f64,
+    strike: f64,
+    shift: f64,
+    rate: f64,
+    sigma: f64,
+    maturity: f64,
+) -> PricesAndGreeks {
+    black76(forward_price + shift, strike + shift, rate, sigma, maturity)
+}
+
+/// Returns implied shifted volatility from a call option on a forward
+/// with initial guess
+///
+/// # Examples
+///
+/// ```
+/// let price = 0.0015;
+/// let forward = -0.002;
+/// let strike = 0.001;
+/// let shift = 0.03;
+/// let rate = 0.01;
+/// let maturity = 2.0;
+/// let initial_guess = 0.3;
+/// let iv = black_scholes::shifted_lognormal::call_iv_guess(
+///     price, forward, strike, shift, rate,
+///     maturity, initial_guess
+/// ).unwrap();
+/// ```
+pub fn call_iv_guess(
+    price: f64,
+    forward_price: f64,
+    strike: f64,
+    shift: f64,
+    rate: f64,
+    maturity: f64,
+    initial_guess: f64,
+) -> Result<f64, IvError> {
+    crate::black76_call_iv_guess(
+        price,
+        forward_price + shift,
+        strike + shift,
+        rate,
+        maturity,
+        initial_guess,
+    )
+}
+
+/// Returns implied shifted volatility from a call option on a forward
+///
+/// # Examples
+///
+/// ```
+/// let price = 0.0015;
+/// let forward = -0.002;
+/// let strike = 0.001;
+/// let shift = 0.03;
+/// let rate = 0.01;
+/// let maturity = 2.0;
+/// let iv = black_scholes::shifted_lognormal::call_iv(
+///     price, forward, strike, shift, rate, maturity
+/// ).unwrap();
+/// ```
+pub fn call_iv(
+    price: f64,
+    forward_price: f64,
+    strike: f64,
+    shift: f64,
+    rate: f64,
+    maturity: f64,
+) -> Result<f64, IvError> {
+    crate::black76_call_iv(price, forward_price + shift, strike + shift, rate, maturity)
+}
+
+/// Returns implied shifted volatility from a put option on a forward
+/// with initial guess
+///
+/// # Examples
+///
+/// ```
+/// let price = 0.0045;
+/// let forward = -0.002;
+/// let strike = 0.001;
+/// let shift = 0.03;
+/// let rate = 0.01;
+/// let maturity = 2.0;
+/// let initial_guess = 0.3;
+/// let iv = black_scholes::shifted_lognormal::put_iv_guess(
+///     price, forward, strike, shift, rate,
+///     maturity, initial_guess
+/// ).unwrap();
+/// ```
+pub fn put_iv_guess(
+    price: f64,
+    forward_price: f64,
+    strike: f64,
+    shift: f64,
+    rate: f64,
+    maturity: f64,
+    initial_guess: f64,
+) -> Result<f64, IvError> {
+    crate::black76_put_iv_guess(
+        price,
+        forward_price + shift,
+        strike + shift,
+        rate,
+        maturity,
+        initial_guess,
+    )
+}
+
+/// Returns implied shifted volatility from a put option on a forward
+///
+/// # Examples
+///
+/// ```
+/// let price = 0.0045;
+/// let forward = -0.002;
+/// let strike = 0.001;
+/// let shift = 0.03;
+/// let rate = 0.01;
+/// let maturity = 2.0;
+/// let iv = black_scholes::shifted_lognormal::put_iv(
+///     price, forward, strike, shift, rate, maturity
+/// ).unwrap();
+/// ```
+pub fn put_iv(
+    price: f64,
+    forward_price: f64,
+    strike: f64,
+    shift: f64,
+    rate: f64,
+    maturity: f64,
+) -> Result<f64, IvError> {
+    crate::black76_put_iv(price, forward_price + shift, strike + shift, rate, maturity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::*;
+
+    #[test]
+    fn compute_all_is_finite_for_negative_forward() {
+        let result = compute_all(-0.002, 0.001, 0.03, 0.01, 0.2, 2.0);
+        assert!(result.call_price.is_finite() && result.call_price > 0.0);
+        assert!(result.put_price.is_finite() && result.put_price > 0.0);
+        assert!(result.call_delta.is_finite());
+        assert!(black76(-0.002, 0.001, 0.01, 0.2, 2.0).call_price.is_nan());
+    }
+    #[test]
+    fn zero_shift_matches_black76() {
+        let shifted = compute_all(55.0, 50.0, 0.0, 0.0025, 0.15, 1.0);
+        let unshifted = black76(55.0, 50.0, 0.0025, 0.15, 1.0);
+        assert_abs_diff_eq!(shifted.call_price, unshifted.call_price);
+        assert_abs_diff_eq!(shifted.put_vega, unshifted.put_vega);
+    }
+    #[test]
+    fn iv_round_trips_with_negative_forward_and_strike() {
+        let forward = -0.004;
+        let strike = -0.001;
+        let shift = 0.02;
+        let rate = -0.005;
+        let sigma = 0.25;
+        let maturity = 3.0;
+        let PricesAndGreeks {
+            call_price,
+            put_price,
+            ..
+        } = compute_all(forward, strike, shift, rate, sigma, maturity);
+        assert_abs_diff_eq!(
+            call_iv(call_price, forward, strike, shift, rate, maturity).unwrap(),
+            sigma,
+            epsilon = 0.00000001
+        );
+        assert_abs_diff_eq!(
+            put_iv(put_price, forward, strike, shift, rate, maturity).unwrap(),
+            sigma,
+            epsilon = 0.00000001
+        );
+        assert_abs_diff_eq!(
+            call_iv_guess(call_price, forward, strike, shift, rate, maturity, 0.5).unwrap(),
+            sigma,
+            epsilon = 0.00000001
+        );
+        assert_abs_diff_eq!(
+            put_iv_guess(put_price, forward, strike, shift, rate, maturity, 0.5).unwrap(),
+            sigma,
+            epsilon = 0.00000001
+        );
+    }
+    #[test]
+    fn iv_returns_invalid_input_if_shift_too_small() {
+        assert_eq!(
+            call_iv(0.001, -0.002, 0.001, 0.001, 0.01, 2.0),
+            Err(IvError::InvalidInput)
+        );
+    }
+}
@@ -0,0 +1,191 @@
+//! Bracketed root finders shared by the implied volatility and strike solvers.
+
+/// Newton-Raphson safeguarded by bisection on a bracket `[lower, upper]`
+/// over which `f` changes sign.  `fn_and_derivative` returns `(f(x), f'(x))`.
+/// Returns `Err` with the last iterate if the root is not bracketed or
+/// `max_iterations` is reached before the step falls below `precision`.
+pub(crate) fn newton_bisection(
+    fn_and_derivative: impl Fn(f64) -> (f64, f64),
+    lower: f64,
+    upper: f64,
+    initial_guess: f64,
+    precision: f64,
+    max_iterations: usize,
+) -> Result<f64, f64> {
+    let (f_lower, _) = fn_and_derivative(lower);
+    let (f_upper, _) = fn_and_derivative(upper);
+    if f_lower == 0.0 {
+        return Ok(lower);
+    }
+    if f_upper == 0.0 {
+        return Ok(upper);
+    }
+    if f_lower * f_upper > 0.0 || f_lower.is_nan() || f_upper.is_nan() {
+        return Err(initial_guess);
+    }
+    // orient the bracket so that f(x_low) < 0
+    let (mut x_low, mut x_high) = if f_lower < 0.0 {
+        (lower, upper)
+    } else {
+        (upper, lower)
+    };
+    let mut x = if initial_guess > lower.min(upper) && initial_guess < lower.max(upper) {
+        initial_guess
+    } else {
+        0.5 * (lower + upper)
+    };
+    let mut dx_old = (upper - lower).abs();
+    let mut dx = dx_old;
+    let (mut f, mut df) = fn_and_derivative(x);
+    for _ in 0..max_iterations {
+        let newton_leaves_bracket = ((x - x_high) * df - f) * ((x - x_low) * df - f) > 0.0;
+        let newton_too_slow = (2.0 * f).abs() > (dx_old * df).abs();
+        if newton_leaves_bracket || newton_too_slow || df.is_nan() {
+            dx_old = dx;
+            dx = 0.5 * (x_high - x_low);
+            x = x_low + dx;
+        } else {
+            dx_old = dx;
+            dx = f / df;
+            x -= dx;
+        }
+        if dx.abs() < precision {
+            return Ok(x);
+        }
+        (f, df) = fn_and_derivative(x);
+        if f == 0.0 {
+            return Ok(x);
+        }
+        if f < 0.0 {
+            x_low = x;
+        } else {
+            x_high = x;
+        }
+    }
+    Err(x)
+}
+
+/// Brent's method on a bracket `[lower, upper]` over which `f` changes sign.
+/// Returns `Err` with the last iterate if the root is not bracketed or
+/// `max_iterations` is reached before the bracket shrinks below `precision`.
+pub(crate) fn brent(
+    f: impl Fn(f64) -> f64,
+    lower: f64,
+    upper: f64,
+    precision: f64,
+    max_iterations: usize,
+) -> Result<f64, f64> {
+    let (mut a, mut b) = (lower, upper);
+    let (mut f_a, mut f_b) = (f(a), f(b));
+    if f_a == 0.0 {
+        return Ok(a);
+    }
+    if f_b == 0.0 {
+        return Ok(b);
+    }
+    if f_a * f_b > 0.0 || f_a.is_nan() || f_b.is_nan() {
+        return Err(b);
+    }
+    let (mut c, mut f_c) = (a, f_a);
+    let mut d = b - a;
+    let mut e = d;
+    for _ in 0..max_iterations {
+        if f_b * f_c > 0.0 {
+            // keep the root between b and c
+            (c, f_c) = (a, f_a);
+            d = b - a;
+            e = d;
+        }
+        if f_c.abs() < f_b.abs() {
+            (a, b, c) = (b, c, b);
+            (f_a, f_b, f_c) = (f_b, f_c, f_b);
+        }
+        let tolerance = 2.0 * f64::EPSILON * b.abs() + 0.5 * precision;
+        let half_interval = 0.5 * (c - b);
+        if half_interval.abs() <= tolerance || f_b == 0.0 {
+            return Ok(b);
+        }
+        if e.abs() >= tolerance && f_a.abs() > f_b.abs() {
+            // inverse quadratic interpolation, or secant if only two points
+            let s = f_b / f_a;
+            let (mut p, mut q) = if a == c {
+                (2.0 * half_interval * s, 1.0 - s)
+            } else {
+                let q = f_a / f_c;
+                let r = f_b / f_c;
+                (
+                    s * (2.0 * half_interval * q * (q - r) - (b - a) * (r - 1.0)),
+                    (q - 1.0) * (r - 1.0) * (s - 1.0),
+                )
+            };
+            if p > 0.0 {
+                q = -q;
+            }
+            p = p.abs();
+            let min_step = (3.0 * half_interval * q - (tolerance * q).abs()).min((e * q).abs());
+            if 2.0 * p < min_step {
+                e = d;
+                d = p / q;
+            } else {
+                d = half_interval;
+                e = d;
+            }
+        } else {
+            d = half_interval;
+            e = d;
+        }
+        (a, f_a) = (b, f_b);
+        b += if d.abs() > tolerance {
+            d
+        } else {
+            tolerance.copysign(half_interval)
+        };
+        f_b = f(b);
+    }
+    Err(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::*;
+
+    #[test]
+    fn newton_bisection_finds_root() {
+        let root = newton_bisection(|x| (x * x - 2.0, 2.0 * x), 0.0, 2.0, 1.0, 1e-14, 100).unwrap();
+        assert_abs_diff_eq!(root, 2.0f64.sqrt(), epsilon = 1e-14);
+    }
+    #[test]
+    fn newton_bisection_survives_flat_derivative() {
+        // Newton alone diverges from x = 1.5 on atan
+        let root = newton_bisection(
+            |x: f64| (x.atan(), 1.0 / (1.0 + x * x)),
+            -1.0,
+            10.0,
+            1.5,
+            1e-14,
+            100,
+        )
+        .unwrap();
+        assert_abs_diff_eq!(root, 0.0, epsilon = 1e-14);
+    }
+    #[test]
+    fn newton_bisection_errs_without_bracket() {
+        assert!(newton_bisection(|x| (x * x + 1.0, 2.0 * x), -1.0, 1.0, 0.5, 1e-14, 100).is_err());
+    }
+    #[test]
+    fn brent_finds_root() {
+        let root = brent(|x: f64| x.powi(3) - 2.0 * x - 5.0, 2.0, 3.0, 1e-15, 100).unwrap();
+        assert_abs_diff_eq!(root, 2.0945514815423265, epsilon = 1e-14);
+    }
+    #[test]
+    fn brent_handles_flat_function() {
+        // Newton stalls where the derivative vanishes
+        let root = brent(|x: f64| (x - 1.0).powi(3), -4.0, 2.5, 1e-14, 200).unwrap();
+        assert_abs_diff_eq!(root, 1.0, epsilon = 1e-4);
+    }
+    #[test]
+    fn brent_errs_without_bracket() {
+        assert!(brent(|x| x * x + 1.0, -1.0, 1.0, 1e-14, 100).is_err());
+    }
+}
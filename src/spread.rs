@@ -0,0 +1,529 @@
+//! Options on the difference between two underlyings: Margrabe's (1978)
+//! exchange option and spread options on two futures, by Kirk's (1995)
+//! approximation or its refinement by Bjerksund and Stensland (2011).
+//!
+//! Calls pay the first underlying less the second (less the strike for
+//! spread options) and puts the reverse.
+use crate::{TwoAssetPricesAndGreeks, cum_norm, inc_norm};
+
+// Central finite differences of price(underlying_1, underlying_2, sigma_1,
+// sigma_2, correlation), which returns the call and put prices.  The
+// correlation bumps are clipped to [-1, 1].
+pub(crate) fn finite_difference_two_asset_greeks(
+    price: impl Fn(f64, f64, f64, f64, f64) -> (f64, f64),
+    underlying_1: f64,
+    underlying_2: f64,
+    sigma_1: f64,
+    sigma_2: f64,
+    correlation: f64,
+) -> TwoAssetPricesAndGreeks {
+    let h_1 = 1.0e-4 * underlying_1;
+    let h_2 = 1.0e-4 * underlying_2;
+    let h_sigma_1 = 1.0e-3 * sigma_1.max(1.0e-2);
+    let h_sigma_2 = 1.0e-3 * sigma_2.max(1.0e-2);
+    let correlation_up = (correlation + 1.0e-4).min(1.0);
+    let correlation_down = (correlation - 1.0e-4).max(-1.0);
+    let at = |s_1: f64, s_2: f64| price(s_1, s_2, sigma_1, sigma_2, correlation);
+    let value = at(underlying_1, underlying_2);
+    let up_1 = at(underlying_1 + h_1, underlying_2);
+    let down_1 = at(underlying_1 - h_1, underlying_2);
+    let up_2 = at(underlying_1, underlying_2 + h_2);
+    let down_2 = at(underlying_1, underlying_2 - h_2);
+    let up_up = at(underlying_1 + h_1, underlying_2 + h_2);
+    let up_down = at(underlying_1 + h_1, underlying_2 - h_2);
+    let down_up = at(underlying_1 - h_1, underlying_2 + h_2);
+    let down_down = at(underlying_1 - h_1, underlying_2 - h_2);
+    let sigma_1_up = price(
+        underlying_1,
+        underlying_2,
+        sigma_1 + h_sigma_1,
+        sigma_2,
+        correlation,
+    );
+    let sigma_1_down = price(
+        underlying_1,
+        underlying_2,
+        sigma_1 - h_sigma_1,
+        sigma_2,
+        correlation,
+    );
+    let sigma_2_up = price(
+        underlying_1,
+        underlying_2,
+        sigma_1,
+        sigma_2 + h_sigma_2,
+        correlation,
+    );
+    let sigma_2_down = price(
+        underlying_1,
+        underlying_2,
+        sigma_1,
+        sigma_2 - h_sigma_2,
+        correlation,
+    );
+    let correlation_up_value = price(underlying_1, underlying_2, sigma_1, sigma_2, correlation_up);
+    let correlation_down_value = price(
+        underlying_1,
+        underlying_2,
+        sigma_1,
+        sigma_2,
+        correlation_down,
+    );
+    let first = |up: f64, down: f64, h: f64| (up - down) / (2.0 * h);
+    let second = |up: f64, value: f64, down: f64, h: f64| (up - 2.0 * value + down) / (h * h);
+    let cross = |up_up: f64, up_down: f64, down_up: f64, down_down: f64| {
+        (up_up - up_down - down_up + down_down) / (4.0 * h_1 * h_2)
+    };
+    let correlation_step = 0.5 * (correlation_up - correlation_down);
+    TwoAssetPricesAndGreeks {
+        call_price: value.0,
+        call_delta_1: first(up_1.0, down_1.0, h_1),
+        call_delta_2: first(up_2.0, down_2.0, h_2),
+        call_gamma_1: second(up_1.0, value.0, down_1.0, h_1),
+        call_gamma_2: second(up_2.0, value.0, down_2.0, h_2),
+        call_cross_gamma: cross(up_up.0, up_down.0, down_up.0, down_down.0),
+        call_vega_1: first(sigma_1_up.0, sigma_1_down.0, h_sigma_1),
+        call_vega_2: first(sigma_2_up.0, sigma_2_down.0, h_sigma_2),
+        call_correlation_sensitivity: first(
+            correlation_up_value.0,
+            correlation_down_value.0,
+            correlation_step,
+        ),
+        put_price: value.1,
+        put_delta_1: first(up_1.1, down_1.1, h_1),
+        put_delta_2: first(up_2.1, down_2.1, h_2),
+        put_gamma_1: second(up_1.1, value.1, down_1.1, h_1),
+        put_gamma_2: second(up_2.1, value.1, down_2.1, h_2),
+        put_cross_gamma: cross(up_up.1, up_down.1, down_up.1, down_down.1),
+        put_vega_1: first(sigma_1_up.1, sigma_1_down.1, h_sigma_1),
+        put_vega_2: first(sigma_2_up.1, sigma_2_down.1, h_sigma_2),
+        put_correlation_sensitivity: first(
+            correlation_up_value.1,
+            correlation_down_value.1,
+            correlation_step,
+        ),
+    }
+}
+
+/// Returns the prices and greeks of the option to exchange the second
+/// asset for the first (the call) and the first for the second (the put).
+/// The price does not depend on interest rates.
+///
+/// # Examples
+///
+/// ```
+/// let stock_1 = 100.0;
+/// let stock_2 = 95.0;
+/// let sigma_1 = 0.3;
+/// let sigma_2 = 0.2;
+/// let correlation = 0.5;
+/// let dividend_yield_1 = 0.02;
+/// let dividend_yield_2 = 0.01;
+/// let maturity = 1.0;
+/// let exchange = black_scholes::spread::margrabe(
+///     stock_1, stock_2, sigma_1, sigma_2, correlation,
+///     dividend_yield_1, dividend_yield_2, maturity
+/// );
+/// assert!(exchange.call_price > exchange.put_price);
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn margrabe(
+    stock_1: f64,
+    stock_2: f64,
+    sigma_1: f64,
+    sigma_2: f64,
+    correlation: f64,
+    dividend_yield_1: f64,
+    dividend_yield_2: f64,
+    maturity: f64,
+) -> TwoAssetPricesAndGreeks {
+    let forward_value_1 = stock_1 * (-dividend_yield_1 * maturity).exp();
+    let forward_value_2 = stock_2 * (-dividend_yield_2 * maturity).exp();
+    let sigma = (sigma_1 * sigma_1 + sigma_2 * sigma_2 - 2.0 * correlation * sigma_1 * sigma_2)
+        .max(0.0)
+        .sqrt();
+    let sqrt_maturity = maturity.sqrt();
+    let sqrt_maturity_sigma = sigma * sqrt_maturity;
+    let (cdf_d1, cdf_d2, gamma_scale, vega) = if sqrt_maturity_sigma > 0.0 {
+        let d1 = (forward_value_1 / forward_value_2).ln() / sqrt_maturity_sigma
+            + 0.5 * sqrt_maturity_sigma;
+        let pdf_d1 = inc_norm(d1);
+        (
+            cum_norm(d1),
+            cum_norm(d1 - sqrt_maturity_sigma),
+            forward_value_1 * pdf_d1 / sqrt_maturity_sigma,
+            forward_value_1 * pdf_d1 * sqrt_maturity,
+        )
+    } else {
+        let in_the_money = if forward_value_1 > forward_value_2 {
+            1.0
+        } else {
+            0.0
+        };
+        (in_the_money, in_the_money, 0.0, 0.0)
+    };
+    let call_price = forward_value_1 * cdf_d1 - forward_value_2 * cdf_d2;
+    let call_delta_1 = forward_value_1 / stock_1 * cdf_d1;
+    let call_delta_2 = -forward_value_2 / stock_2 * cdf_d2;
+    // S₁e^{-q₁T}φ(d1) = S₂e^{-q₂T}φ(d2)
+    let gamma_1 = gamma_scale / (stock_1 * stock_1);
+    let gamma_2 = gamma_scale / (stock_2 * stock_2);
+    let cross_gamma = -gamma_scale / (stock_1 * stock_2);
+    let (vega_1, vega_2, correlation_sensitivity) = if sigma > 0.0 {
+        (
+            vega * (sigma_1 - correlation * sigma_2) / sigma,
+            vega * (sigma_2 - correlation * sigma_1) / sigma,
+            -vega * sigma_1 * sigma_2 / sigma,
+        )
+    } else {
+        (0.0, 0.0, 0.0)
+    };
+    TwoAssetPricesAndGreeks {
+        call_price,
+        call_delta_1,
+        call_delta_2,
+        call_gamma_1: gamma_1,
+        call_gamma_2: gamma_2,
+        call_cross_gamma: cross_gamma,
+        call_vega_1: vega_1,
+        call_vega_2: vega_2,
+        call_correlation_sensitivity: correlation_sensitivity,
+        put_price: call_price - forward_value_1 + forward_value_2,
+        put_delta_1: call_delta_1 - forward_value_1 / stock_1,
+        put_delta_2: call_delta_2 + forward_value_2 / stock_2,
+        put_gamma_1: gamma_1,
+        put_gamma_2: gamma_2,
+        put_cross_gamma: cross_gamma,
+        put_vega_1: vega_1,
+        put_vega_2: vega_2,
+        put_correlation_sensitivity: correlation_sensitivity,
+    }
+}
+
+// Call and put prices from the undiscounted call price by put-call parity
+fn with_parity(
+    undiscounted_call: f64,
+    future_1: f64,
+    future_2: f64,
+    strike: f64,
+    discount: f64,
+) -> (f64, f64) {
+    let call = discount * undiscounted_call;
+    (call, call - discount * (future_1 - future_2 - strike))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn kirk_prices(
+    future_1: f64,
+    future_2: f64,
+    strike: f64,
+    sigma_1: f64,
+    sigma_2: f64,
+    correlation: f64,
+    discount: f64,
+    maturity: f64,
+) -> (f64, f64) {
+    let shifted = future_2 + strike;
+    let weight = future_2 / shifted;
+    let sigma = (sigma_1 * sigma_1 - 2.0 * correlation * sigma_1 * sigma_2 * weight
+        + (sigma_2 * weight).powi(2))
+    .max(0.0)
+    .sqrt();
+    let sqrt_maturity_sigma = sigma * maturity.sqrt();
+    let undiscounted_call = if sqrt_maturity_sigma > 0.0 {
+        let d1 = (future_1 / shifted).ln() / sqrt_maturity_sigma + 0.5 * sqrt_maturity_sigma;
+        future_1 * cum_norm(d1) - shifted * cum_norm(d1 - sqrt_maturity_sigma)
+    } else {
+        (future_1 - shifted).max(0.0)
+    };
+    with_parity(undiscounted_call, future_1, future_2, strike, discount)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn bjerksund_stensland_prices(
+    future_1: f64,
+    future_2: f64,
+    strike: f64,
+    sigma_1: f64,
+    sigma_2: f64,
+    correlation: f64,
+    discount: f64,
+    maturity: f64,
+) -> (f64, f64) {
+    let shifted = future_2 + strike;
+    let weight = future_2 / shifted;
+    let variance_1 = sigma_1 * sigma_1;
+    let variance_2 = sigma_2 * sigma_2;
+    let covariance = correlation * sigma_1 * sigma_2;
+    let sigma = (variance_1 - 2.0 * weight * covariance + weight * weight * variance_2)
+        .max(0.0)
+        .sqrt();
+    let sqrt_maturity_sigma = sigma * maturity.sqrt();
+    let undiscounted_call = if sqrt_maturity_sigma > 0.0 {
+        let log_moneyness = (future_1 / shifted).ln();
+        let d = |drift: f64| (log_moneyness + drift * maturity) / sqrt_maturity_sigma;
+        let d1 = d(0.5 * variance_1 - weight * covariance + 0.5 * weight * weight * variance_2);
+        let d2 = d(-0.5 * variance_1 + covariance + (0.5 * weight * weight - weight) * variance_2);
+        let d3 = d(-0.5 * variance_1 + 0.5 * weight * weight * variance_2);
+        future_1 * cum_norm(d1) - future_2 * cum_norm(d2) - strike * cum_norm(d3)
+    } else {
+        (future_1 - shifted).max(0.0)
+    };
+    with_parity(undiscounted_call, future_1, future_2, strike, discount)
+}
+
+/// Returns Kirk's approximation to the prices and greeks of options on the
+/// spread between two futures, paying `future_1 - future_2 - strike` (the
+/// call) or its negative (the put) if positive.
+///
+/// # Examples
+///
+/// ```
+/// // a crack spread
+/// let crude = 75.0;
+/// let gasoline = 92.0;
+/// let spread = black_scholes::spread::kirk(
+///     gasoline, crude, 15.0, 0.35, 0.3, 0.85, 0.04, 0.5
+/// );
+/// assert!(spread.call_delta_1 > 0.0 && spread.call_delta_2 < 0.0);
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn kirk(
+    future_1: f64,
+    future_2: f64,
+    strike: f64,
+    sigma_1: f64,
+    sigma_2: f64,
+    correlation: f64,
+    rate: f64,
+    maturity: f64,
+) -> TwoAssetPricesAndGreeks {
+    let discount = (-rate * maturity).exp();
+    finite_difference_two_asset_greeks(
+        |future_1, future_2, sigma_1, sigma_2, correlation| {
+            kirk_prices(
+                future_1,
+                future_2,
+                strike,
+                sigma_1,
+                sigma_2,
+                correlation,
+                discount,
+                maturity,
+            )
+        },
+        future_1,
+        future_2,
+        sigma_1,
+        sigma_2,
+        correlation,
+    )
+}
+
+/// Returns the Bjerksund and Stensland (2011) approximation to the prices
+/// and greeks of options on the spread between two futures.  It refines
+/// Kirk's approximation, which misprices deep in- and out-of-the-money
+/// strikes.
+///
+/// # Examples
+///
+/// ```
+/// // a spark spread: power less gas times the heat rate
+/// let power = 60.0;
+/// let gas = 3.5 * 7.5;
+/// let spread = black_scholes::spread::bjerksund_stensland(
+///     power, gas, 30.0, 0.5, 0.4, 0.7, 0.04, 0.25
+/// );
+/// // correlated legs narrow the spread
+/// assert!(spread.call_correlation_sensitivity < 0.0);
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn bjerksund_stensland(
+    future_1: f64,
+    future_2: f64,
+    strike: f64,
+    sigma_1: f64,
+    sigma_2: f64,
+    correlation: f64,
+    rate: f64,
+    maturity: f64,
+) -> TwoAssetPricesAndGreeks {
+    let discount = (-rate * maturity).exp();
+    finite_difference_two_asset_greeks(
+        |future_1, future_2, sigma_1, sigma_2, correlation| {
+            bjerksund_stensland_prices(
+                future_1,
+                future_2,
+                strike,
+                sigma_1,
+                sigma_2,
+                correlation,
+                discount,
+                maturity,
+            )
+        },
+        future_1,
+        future_2,
+        sigma_1,
+        sigma_2,
+        correlation,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monte_carlo::MonteCarlo;
+    use approx::*;
+
+    // Discounted expected payoffs of the call and put on exactly simulated
+    // correlated futures
+    #[allow(clippy::too_many_arguments)]
+    fn simulate(
+        future_1: f64,
+        future_2: f64,
+        strike: f64,
+        sigma_1: f64,
+        sigma_2: f64,
+        correlation: f64,
+        discount: f64,
+        maturity: f64,
+    ) -> (f64, f64, f64) {
+        let paths = 200_000;
+        let (mut call, mut put, mut call_squares) = (0.0, 0.0, 0.0);
+        let sqrt_maturity = maturity.sqrt();
+        let orthogonal = (1.0 - correlation * correlation).sqrt();
+        MonteCarlo::new(paths, 2, 5).for_each_draw(|_, normals| {
+            let z_1 = normals[0];
+            let z_2 = correlation * normals[0] + orthogonal * normals[1];
+            let f_1 = future_1
+                * (sigma_1 * sqrt_maturity * z_1 - 0.5 * sigma_1 * sigma_1 * maturity).exp();
+            let f_2 = future_2
+                * (sigma_2 * sqrt_maturity * z_2 - 0.5 * sigma_2 * sigma_2 * maturity).exp();
+            let spread = f_1 - f_2 - strike;
+            call += spread.max(0.0);
+            put += (-spread).max(0.0);
+            call_squares += spread.max(0.0).powi(2);
+        });
+        let n = paths as f64;
+        let standard_error = ((call_squares / n - (call / n).powi(2)) / n).sqrt();
+        (
+            discount * call / n,
+            discount * put / n,
+            discount * standard_error,
+        )
+    }
+
+    #[test]
+    fn margrabe_greeks_match_finite_differences() {
+        let (s_1, s_2, sigma_1, sigma_2, correlation, q_1, q_2, maturity) =
+            (100.0, 90.0, 0.3, 0.25, 0.4, 0.03, 0.01, 0.75);
+        let analytic = margrabe(s_1, s_2, sigma_1, sigma_2, correlation, q_1, q_2, maturity);
+        let numeric = finite_difference_two_asset_greeks(
+            |s_1, s_2, sigma_1, sigma_2, correlation| {
+                let prices = margrabe(s_1, s_2, sigma_1, sigma_2, correlation, q_1, q_2, maturity);
+                (prices.call_price, prices.put_price)
+            },
+            s_1,
+            s_2,
+            sigma_1,
+            sigma_2,
+            correlation,
+        );
+        let pairs = [
+            (analytic.call_delta_1, numeric.call_delta_1),
+            (analytic.call_delta_2, numeric.call_delta_2),
+            (analytic.call_gamma_1, numeric.call_gamma_1),
+            (analytic.call_gamma_2, numeric.call_gamma_2),
+            (analytic.call_cross_gamma, numeric.call_cross_gamma),
+            (analytic.call_vega_1, numeric.call_vega_1),
+            (analytic.call_vega_2, numeric.call_vega_2),
+            (
+                analytic.call_correlation_sensitivity,
+                numeric.call_correlation_sensitivity,
+            ),
+            (analytic.put_delta_1, numeric.put_delta_1),
+            (analytic.put_delta_2, numeric.put_delta_2),
+            (analytic.put_cross_gamma, numeric.put_cross_gamma),
+            (analytic.put_vega_1, numeric.put_vega_1),
+        ];
+        for (analytic, numeric) in pairs {
+            assert_relative_eq!(analytic, numeric, max_relative = 1e-5);
+        }
+    }
+    #[test]
+    fn zero_strike_spread_is_exchange_option() {
+        let (f_1, f_2, sigma_1, sigma_2, correlation, rate, maturity) =
+            (52.0, 50.0, 0.35, 0.3, 0.6, 0.05, 1.0);
+        let exchange = margrabe(
+            f_1,
+            f_2,
+            sigma_1,
+            sigma_2,
+            correlation,
+            rate,
+            rate,
+            maturity,
+        );
+        for spread in [
+            kirk(f_1, f_2, 0.0, sigma_1, sigma_2, correlation, rate, maturity),
+            bjerksund_stensland(f_1, f_2, 0.0, sigma_1, sigma_2, correlation, rate, maturity),
+        ] {
+            assert_abs_diff_eq!(spread.call_price, exchange.call_price, epsilon = 1e-12);
+            assert_abs_diff_eq!(spread.put_price, exchange.put_price, epsilon = 1e-12);
+            assert_abs_diff_eq!(spread.call_vega_1, exchange.call_vega_1, epsilon = 1e-5);
+        }
+    }
+    #[test]
+    fn spread_approximations_match_simulation() {
+        let (f_1, f_2, sigma_1, sigma_2, correlation, rate, maturity): (
+            f64,
+            f64,
+            f64,
+            f64,
+            f64,
+            f64,
+            f64,
+        ) = (110.0, 100.0, 0.3, 0.25, 0.8, 0.05, 1.0);
+        let discount = (-rate * maturity).exp();
+        for strike in [-10.0, 5.0, 10.0, 30.0] {
+            let (call, put, standard_error) = simulate(
+                f_1,
+                f_2,
+                strike,
+                sigma_1,
+                sigma_2,
+                correlation,
+                discount,
+                maturity,
+            );
+            let kirk = kirk(
+                f_1,
+                f_2,
+                strike,
+                sigma_1,
+                sigma_2,
+                correlation,
+                rate,
+                maturity,
+            );
+            let refined = bjerksund_stensland(
+                f_1,
+                f_2,
+                strike,
+                sigma_1,
+                sigma_2,
+                correlation,
+                rate,
+                maturity,
+            );
+            assert!((refined.call_price - call).abs() < 4.0 * standard_error);
+            assert!((kirk.call_price - call).abs() < 4.0 * standard_error + 0.05);
+            assert_abs_diff_eq!(
+                refined.call_price - refined.put_price,
+                call - put,
+                epsilon = 8.0 * standard_error
+            );
+        }
+    }
+}
@@ -0,0 +1,499 @@
+//! Univariate, bivariate and trivariate standard normal distribution
+//! functions, as used by the pricers throughout the crate.
+use crate::lets_be_rational::{erfcx, inverse_norm_cdf};
+use special::Error;
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+/// Returns the standard normal CDF as `erfc(-x/√2)/2`, the form every pricer
+/// in the crate uses.  Unlike `(1 + erf(x/√2))/2`, which cancels to zero
+/// below about -8, it keeps full relative accuracy deep in the lower tail,
+/// down to about -37 where the result underflows.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::stats::cum_norm;
+/// assert!((cum_norm(-10.0) / 7.619853024160527e-24 - 1.0).abs() < 1e-12);
+/// ```
+pub fn cum_norm(x: f64) -> f64 {
+    0.5 * (-x * FRAC_1_SQRT_2).compl_error()
+}
+
+// Log of the standard normal CDF, finite far beyond where the CDF itself
+// underflows
+pub(crate) fn log_cum_norm(x: f64) -> f64 {
+    if x < -5.0 {
+        // Φ(x) = erfcx(-x/√2)·e^{-x²/2}/2
+        (0.5 * erfcx(-x * FRAC_1_SQRT_2)).ln() - 0.5 * x * x
+    } else {
+        cum_norm(x).ln()
+    }
+}
+
+/// Returns the standard normal quantile, the inverse of [`cum_norm`], by
+/// Wichura's (1988) algorithm AS241.  This is the routine behind the
+/// implied volatility, strike-from-delta and quasi-random normal code.
+/// Returns minus or plus infinity at 0 and 1.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::stats::{cum_norm, inverse_cum_norm};
+/// let z = inverse_cum_norm(0.975);
+/// assert!((z - 1.959963984540054).abs() < 1e-13);
+/// assert!((cum_norm(inverse_cum_norm(1e-200)) / 1e-200 - 1.0).abs() < 1e-12);
+/// ```
+pub fn inverse_cum_norm(p: f64) -> f64 {
+    inverse_norm_cdf(p)
+}
+
+// Half of the Gauss-Legendre nodes in (-1, 0) and their weights for 6, 12
+// and 20 points
+#[allow(clippy::excessive_precision)]
+const GAUSS_LEGENDRE_6: [(f64, f64); 3] = [
+    (-0.932_469_514_203_152, 0.171_324_492_379_170_35),
+    (-0.661_209_386_466_264_5, 0.360_761_573_048_138_6),
+    (-0.238_619_186_083_196_9, 0.467_913_934_572_691_05),
+];
+#[allow(clippy::excessive_precision)]
+const GAUSS_LEGENDRE_12: [(f64, f64); 6] = [
+    (-0.981_560_634_246_719_3, 0.047_175_336_386_511_827),
+    (-0.904_117_256_370_474_9, 0.106_939_325_995_318_43),
+    (-0.769_902_674_194_304_7, 0.160_078_328_543_346_23),
+    (-0.587_317_954_286_617_4, 0.203_167_426_723_065_92),
+    (-0.367_831_498_998_180_2, 0.233_492_536_538_354_81),
+    (-0.125_233_408_511_468_92, 0.249_147_045_813_402_8),
+];
+#[allow(clippy::excessive_precision)]
+const GAUSS_LEGENDRE_20: [(f64, f64); 10] = [
+    (-0.993_128_599_185_094_9, 0.017_614_007_139_152_118),
+    (-0.963_971_927_277_913_8, 0.040_601_429_800_386_94),
+    (-0.912_234_428_251_325_9, 0.062_672_048_334_109_06),
+    (-0.839_116_971_822_218_8, 0.083_276_741_576_704_75),
+    (-0.746_331_906_460_150_8, 0.101_930_119_817_240_44),
+    (-0.636_053_680_726_515, 0.118_194_531_961_518_42),
+    (-0.510_867_001_950_827_1, 0.131_688_638_449_176_63),
+    (-0.373_706_088_715_419_56, 0.142_096_109_318_382_05),
+    (-0.227_785_851_141_645_08, 0.149_172_986_472_603_75),
+    (-0.076_526_521_133_497_33, 0.152_753_387_130_725_85),
+];
+
+/// Returns `P(X < x, Y < y)` for standard normal `X` and `Y` with
+/// correlation `rho`, accurate to about 1e-15 (Genz, 2004).
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::stats::bivariate_cum_norm;
+/// let rho: f64 = 0.5;
+/// let orthant = 0.25 + rho.asin() / (2.0 * std::f64::consts::PI);
+/// assert!((bivariate_cum_norm(0.0, 0.0, rho) - orthant).abs() < 1e-15);
+/// ```
+pub fn bivariate_cum_norm(x: f64, y: f64, rho: f64) -> f64 {
+    // an infinite limit leaves the marginal of the other variable
+    if x == f64::INFINITY {
+        return cum_norm(y);
+    }
+    if y == f64::INFINITY {
+        return cum_norm(x);
+    }
+    if x == f64::NEG_INFINITY || y == f64::NEG_INFINITY {
+        return 0.0;
+    }
+    let nodes: &[(f64, f64)] = if rho.abs() < 0.3 {
+        &GAUSS_LEGENDRE_6
+    } else if rho.abs() < 0.75 {
+        &GAUSS_LEGENDRE_12
+    } else {
+        &GAUSS_LEGENDRE_20
+    };
+    // Genz integrates over the upper orthant [h, ∞) × [k, ∞)
+    let h = -x;
+    let mut k = -y;
+    let mut hk = h * k;
+    if rho.abs() < 0.925 {
+        let mut bvn = 0.0;
+        if rho != 0.0 {
+            let hs = 0.5 * (h * h + k * k);
+            let asr = rho.asin();
+            for &(node, weight) in nodes {
+                for sign in [-1.0, 1.0] {
+                    let sn = (0.5 * asr * (sign * node + 1.0)).sin();
+                    bvn += weight * ((sn * hk - hs) / (1.0 - sn * sn)).exp();
+                }
+            }
+            bvn *= asr / (4.0 * PI);
+        }
+        return bvn + cum_norm(-h) * cum_norm(-k);
+    }
+    if rho < 0.0 {
+        k = -k;
+        hk = -hk;
+    }
+    let mut bvn = 0.0;
+    if rho.abs() < 1.0 {
+        let ass = (1.0 - rho) * (1.0 + rho);
+        let mut a = ass.sqrt();
+        let bs = (h - k) * (h - k);
+        let c = (4.0 - hk) / 8.0;
+        let d = (12.0 - hk) / 16.0;
+        let asr = -0.5 * (bs / ass + hk);
+        if asr > -100.0 {
+            bvn = a
+                * asr.exp()
+                * (1.0 - c * (bs - ass) * (1.0 - d * bs / 5.0) / 3.0 + c * d * ass * ass / 5.0);
+        }
+        if -hk < 100.0 {
+            let b = bs.sqrt();
+            bvn -= (-0.5 * hk).exp()
+                * (2.0 * PI).sqrt()
+                * cum_norm(-b / a)
+                * b
+                * (1.0 - c * bs * (1.0 - d * bs / 5.0) / 3.0);
+        }
+        a *= 0.5;
+        for &(node, weight) in nodes {
+            for sign in [-1.0, 1.0] {
+                let xs = (a * (sign * node + 1.0)).powi(2);
+                let rs = (1.0 - xs).sqrt();
+                let asr = -0.5 * (bs / xs + hk);
+                if asr > -100.0 {
+                    bvn += a
+                        * weight
+                        * asr.exp()
+                        * ((-hk * (1.0 - rs) / (2.0 * (1.0 + rs))).exp() / rs
+                            - (1.0 + c * xs * (1.0 + d * xs)));
+                }
+            }
+        }
+        bvn = -bvn / (2.0 * PI);
+    }
+    if rho > 0.0 {
+        bvn + cum_norm(-h.max(k))
+    } else {
+        let bvn = -bvn;
+        if k > h {
+            bvn + cum_norm(k) - cum_norm(h)
+        } else {
+            bvn
+        }
+    }
+}
+
+// Density of the standard bivariate normal with correlation rho
+fn bivariate_norm_pdf(x: f64, y: f64, rho: f64) -> f64 {
+    let one_minus_rho_squared = 1.0 - rho * rho;
+    (-(x * x - 2.0 * rho * x * y + y * y) / (2.0 * one_minus_rho_squared)).exp()
+        / (2.0 * PI * one_minus_rho_squared.sqrt())
+}
+
+// P(Z < z | X = x, Y = y) where X, Y and Z are standard normal with
+// correlations rho_xy, rho_xz and rho_yz
+fn conditional_cum_norm(x: f64, y: f64, z: f64, rho_xy: f64, rho_xz: f64, rho_yz: f64) -> f64 {
+    let one_minus_rho_squared = 1.0 - rho_xy * rho_xy;
+    let mean =
+        ((rho_xz - rho_xy * rho_yz) * x + (rho_yz - rho_xy * rho_xz) * y) / one_minus_rho_squared;
+    let determinant =
+        one_minus_rho_squared - rho_xz * rho_xz - rho_yz * rho_yz + 2.0 * rho_xy * rho_xz * rho_yz;
+    if determinant > 0.0 {
+        cum_norm((z - mean) / (determinant / one_minus_rho_squared).sqrt())
+    } else if z > mean {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+fn gauss_legendre_20(f: &impl Fn(f64) -> f64, a: f64, b: f64) -> f64 {
+    let centre = 0.5 * (a + b);
+    let half_width = 0.5 * (b - a);
+    half_width
+        * GAUSS_LEGENDRE_20
+            .iter()
+            .map(|&(node, weight)| {
+                weight * (f(centre + half_width * node) + f(centre - half_width * node))
+            })
+            .sum::<f64>()
+}
+
+// Bisects, at most `depth` times, until the 20 point rule agrees with the sum
+// over both halves
+fn adaptive_gauss_legendre(
+    f: &impl Fn(f64) -> f64,
+    a: f64,
+    b: f64,
+    whole: f64,
+    tolerance: f64,
+    depth: usize,
+) -> f64 {
+    let middle = 0.5 * (a + b);
+    let left = gauss_legendre_20(f, a, middle);
+    let right = gauss_legendre_20(f, middle, b);
+    // a halved absolute tolerance soon drops below the rounding error of the
+    // sums, so never ask for better than a few ulps of them
+    let tolerance = tolerance.max(4.0 * f64::EPSILON * (left.abs() + right.abs()));
+    if depth == 0 || (left + right - whole).abs() <= tolerance {
+        left + right
+    } else {
+        adaptive_gauss_legendre(f, a, middle, left, 0.5 * tolerance, depth - 1)
+            + adaptive_gauss_legendre(f, middle, b, right, 0.5 * tolerance, depth - 1)
+    }
+}
+
+/// Returns `P(X < x, Y < y, Z < z)` for standard normal `X`, `Y` and `Z`
+/// with the given pairwise correlations, accurate to about 1e-14.
+///
+/// The probability is reduced to a bivariate one in the pair with the
+/// largest correlation, plus a one dimensional integral along the
+/// correlations of the third variable (Plackett, 1954; Genz, 2004).  Only
+/// the largest correlation may be ±1, and the correlations must form a
+/// positive semi-definite matrix.  Close to a singular matrix the accuracy
+/// falls to about 1e-9.
+///
+/// # Examples
+///
+/// ```
+/// use black_scholes::stats::trivariate_cum_norm;
+/// use std::f64::consts::PI;
+/// let (rho_xy, rho_xz, rho_yz): (f64, f64, f64) = (0.5, -0.3, 0.2);
+/// let orthant = 0.125 + (rho_xy.asin() + rho_xz.asin() + rho_yz.asin()) / (4.0 * PI);
+/// let p = trivariate_cum_norm(0.0, 0.0, 0.0, rho_xy, rho_xz, rho_yz);
+/// assert!((p - orthant).abs() < 1e-14);
+/// ```
+pub fn trivariate_cum_norm(x: f64, y: f64, z: f64, rho_xy: f64, rho_xz: f64, rho_yz: f64) -> f64 {
+    // order the variables so that the largest correlation is between the
+    // second and third
+    let (h_1, h_2, h_3, rho_12, rho_13, rho_23) =
+        if rho_xy.abs() >= rho_xz.abs() && rho_xy.abs() >= rho_yz.abs() {
+            (z, x, y, rho_xz, rho_yz, rho_xy)
+        } else if rho_xz.abs() >= rho_yz.abs() {
+            (y, x, z, rho_xy, rho_yz, rho_xz)
+        } else {
+            (x, y, z, rho_xy, rho_xz, rho_yz)
+        };
+    // with Y = ±Z the third variable only tightens the bound on the second
+    if rho_23 == 1.0 {
+        return bivariate_cum_norm(h_1, h_2.min(h_3), rho_12);
+    }
+    if rho_23 == -1.0 {
+        return if h_2 > -h_3 {
+            (bivariate_cum_norm(h_1, h_2, rho_12) - bivariate_cum_norm(h_1, -h_3, rho_12)).max(0.0)
+        } else {
+            0.0
+        };
+    }
+    let independent = cum_norm(h_1) * bivariate_cum_norm(h_2, h_3, rho_23);
+    if rho_12 == 0.0 && rho_13 == 0.0 {
+        return independent;
+    }
+    // derivative along correlations t·rho_12 and t·rho_13 of the first
+    // variable with the others
+    let derivative = |t: f64| {
+        let (r_12, r_13) = (t * rho_12, t * rho_13);
+        rho_12
+            * bivariate_norm_pdf(h_1, h_2, r_12)
+            * conditional_cum_norm(h_1, h_2, h_3, r_12, r_13, rho_23)
+            + rho_13
+                * bivariate_norm_pdf(h_1, h_3, r_13)
+                * conditional_cum_norm(h_1, h_3, h_2, r_13, r_12, rho_23)
+    };
+    let whole = gauss_legendre_20(&derivative, 0.0, 1.0);
+    let integral = adaptive_gauss_legendre(&derivative, 0.0, 1.0, whole, 1e-15, 14);
+    (independent + integral).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::*;
+
+    #[test]
+    fn bivariate_cum_norm_matches_quadrature() {
+        // reference values by adaptive quadrature in 30 digit arithmetic
+        let cases = [
+            (1.0, -1.0, 0.5, 0.15487295185860279),
+            (-2.0, 0.5, -0.7, 0.001363717266368046),
+            (0.3, 0.3, 0.95, 0.5696125069835463),
+            (-3.0, -4.0, 0.99, 3.1671241833107543e-5),
+            (2.5, -0.5, -0.95, 0.30232787340098427),
+            (-8.0, -8.0, 0.9, 3.89027249591489e-17),
+            (1.2, 2.1, 0.0, 0.8691215621983404),
+            (-1.5, 1.5, -0.3, 0.055477286945029787),
+        ];
+        for (x, y, rho, expected) in cases {
+            assert_relative_eq!(
+                bivariate_cum_norm(x, y, rho),
+                expected,
+                max_relative = 1e-13
+            );
+        }
+    }
+    #[test]
+    fn bivariate_cum_norm_at_origin() {
+        for &rho in &[-0.99, -0.5, 0.0, 0.2, 0.8, 0.95] {
+            assert_abs_diff_eq!(
+                bivariate_cum_norm(0.0, 0.0, rho),
+                0.25 + f64::asin(rho) / (2.0 * PI),
+                epsilon = 1e-15
+            );
+        }
+    }
+    #[test]
+    fn bivariate_cum_norm_with_infinite_limits() {
+        for rho in [-1.0, -0.5, 0.0, 0.8, 1.0] {
+            assert_eq!(bivariate_cum_norm(f64::INFINITY, 0.3, rho), cum_norm(0.3));
+            assert_eq!(bivariate_cum_norm(-1.2, f64::INFINITY, rho), cum_norm(-1.2));
+            assert_eq!(bivariate_cum_norm(f64::NEG_INFINITY, 0.3, rho), 0.0);
+            assert_eq!(bivariate_cum_norm(0.3, f64::NEG_INFINITY, rho), 0.0);
+        }
+    }
+    #[test]
+    fn bivariate_cum_norm_with_perfect_correlation() {
+        assert_abs_diff_eq!(
+            bivariate_cum_norm(0.5, -0.2, 1.0),
+            cum_norm(-0.2),
+            epsilon = 1e-15
+        );
+        assert_abs_diff_eq!(
+            bivariate_cum_norm(0.5, -0.2, -1.0),
+            cum_norm(0.5) - cum_norm(0.2),
+            epsilon = 1e-15
+        );
+    }
+    #[test]
+    fn cum_norm_matches_tail_table() {
+        let cases = [
+            (-8.0, 6.22096057427178e-16),
+            (-10.0, 7.619853024160527e-24),
+            (-20.0, 2.7536241186062337e-89),
+            (-30.0, 4.906713927148187e-198),
+            (0.0, 0.5),
+            (1.0, 0.8413447460685429),
+        ];
+        for (x, expected) in cases {
+            assert_relative_eq!(cum_norm(x), expected, max_relative = 1e-12);
+        }
+    }
+    #[test]
+    fn log_cum_norm_extends_the_tail() {
+        for x in [-30.0, -6.0, -5.0, -1.0, 0.0, 2.0] {
+            assert_relative_eq!(log_cum_norm(x), cum_norm(x).ln(), max_relative = 1e-14);
+        }
+        // ln Φ(x) ≈ -x²/2 - ln(-x√(2π)) as x → -∞
+        let x: f64 = -1e4;
+        let asymptote = -0.5 * x * x - (-x * (2.0 * PI).sqrt()).ln();
+        assert_relative_eq!(log_cum_norm(x), asymptote, max_relative = 1e-12);
+    }
+    #[test]
+    fn inverse_cum_norm_matches_quantile_table() {
+        let cases = [
+            (0.5, 0.0),
+            (0.975, 1.959963984540054),
+            (0.995, 2.5758293035489004),
+            (0.001, -3.090232306167813),
+            (1e-10, -6.361340902404056),
+        ];
+        for (p, expected) in cases {
+            assert_abs_diff_eq!(inverse_cum_norm(p), expected, epsilon = 1e-13);
+        }
+        for p in [1e-300, 1e-100, 1e-20, 0.3, 0.999_999] {
+            assert_relative_eq!(cum_norm(inverse_cum_norm(p)), p, max_relative = 1e-12);
+        }
+    }
+    #[test]
+    fn trivariate_cum_norm_matches_orthant_probabilities() {
+        let cases = [
+            (0.5, 0.5, 0.5),
+            (0.9, 0.8, 0.75),
+            (-0.4, 0.3, 0.6),
+            (0.99, -0.2, -0.1),
+            (-0.45, -0.45, -0.1),
+        ];
+        for (rho_xy, rho_xz, rho_yz) in cases {
+            let orthant =
+                0.125 + (f64::asin(rho_xy) + f64::asin(rho_xz) + f64::asin(rho_yz)) / (4.0 * PI);
+            assert_abs_diff_eq!(
+                trivariate_cum_norm(0.0, 0.0, 0.0, rho_xy, rho_xz, rho_yz),
+                orthant,
+                epsilon = 1e-14
+            );
+        }
+    }
+    #[test]
+    fn trivariate_cum_norm_matches_conditional_quadrature() {
+        // integrates the bivariate probability conditional on X = u by
+        // Simpson's rule over [-12, x]
+        let reference = |x: f64, y: f64, z: f64, rho_xy: f64, rho_xz: f64, rho_yz: f64| {
+            let (s_y, s_z) = (
+                (1.0 - rho_xy * rho_xy).sqrt(),
+                (1.0 - rho_xz * rho_xz).sqrt(),
+            );
+            let rho = (rho_yz - rho_xy * rho_xz) / (s_y * s_z);
+            let f = |u: f64| {
+                (-0.5 * u * u).exp() / (2.0 * PI).sqrt()
+                    * bivariate_cum_norm((y - rho_xy * u) / s_y, (z - rho_xz * u) / s_z, rho)
+            };
+            let intervals = 20_000;
+            let h = (x + 12.0) / intervals as f64;
+            let interior: f64 = (1..intervals)
+                .map(|i| if i % 2 == 1 { 4.0 } else { 2.0 } * f(-12.0 + i as f64 * h))
+                .sum();
+            h / 3.0 * (f(-12.0) + interior + f(x))
+        };
+        let cases = [
+            (1.0, -0.5, 0.3, 0.6, 0.2, -0.3),
+            (-2.0, -1.5, -1.0, 0.7, 0.7, 0.7),
+            (0.5, 2.0, -1.0, -0.3, 0.8, -0.5),
+            (-1.0, 0.0, 1.5, 0.2, -0.95, 0.1),
+        ];
+        for (x, y, z, rho_xy, rho_xz, rho_yz) in cases {
+            let expected = reference(x, y, z, rho_xy, rho_xz, rho_yz);
+            assert_abs_diff_eq!(
+                trivariate_cum_norm(x, y, z, rho_xy, rho_xz, rho_yz),
+                expected,
+                epsilon = 1e-12
+            );
+            // the order of the variables does not matter
+            assert_abs_diff_eq!(
+                trivariate_cum_norm(z, x, y, rho_xz, rho_yz, rho_xy),
+                expected,
+                epsilon = 1e-12
+            );
+        }
+    }
+    #[test]
+    fn trivariate_cum_norm_reduces_to_bivariate() {
+        assert_abs_diff_eq!(
+            trivariate_cum_norm(0.7, -0.4, 40.0, 0.6, 0.3, -0.2),
+            bivariate_cum_norm(0.7, -0.4, 0.6),
+            epsilon = 1e-15
+        );
+        assert_abs_diff_eq!(
+            trivariate_cum_norm(0.7, -0.4, 1.1, 0.0, 0.0, 0.0),
+            cum_norm(0.7) * cum_norm(-0.4) * cum_norm(1.1),
+            epsilon = 1e-16
+        );
+    }
+    #[test]
+    fn trivariate_cum_norm_with_perfectly_correlated_pair() {
+        assert_abs_diff_eq!(
+            trivariate_cum_norm(1.0, 1.0, 1.0, 0.6, 0.6, 1.0),
+            bivariate_cum_norm(1.0, 1.0, 0.6),
+            epsilon = 1e-15
+        );
+        // Z = -Y: P(X < 0.3, -0.2 < Y < 0.5)
+        assert_abs_diff_eq!(
+            trivariate_cum_norm(0.3, 0.5, 0.2, 0.4, -0.4, -1.0),
+            bivariate_cum_norm(0.3, 0.5, 0.4) - bivariate_cum_norm(0.3, -0.2, 0.4),
+            epsilon = 1e-15
+        );
+        assert_eq!(trivariate_cum_norm(0.3, -0.5, -0.2, 0.4, -0.4, -1.0), 0.0);
+        // a singular matrix with no correlation of ±1 still terminates
+        let (rho_xy, rho_xz, rho_yz): (f64, f64, f64) = (0.5, 0.5, -0.5);
+        assert_abs_diff_eq!(
+            trivariate_cum_norm(0.0, 0.0, 0.0, rho_xy, rho_xz, rho_yz),
+            0.125 + (rho_xy.asin() + rho_xz.asin() + rho_yz.asin()) / (4.0 * PI),
+            epsilon = 1e-14
+        );
+    }
+}
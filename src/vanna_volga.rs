@@ -0,0 +1,332 @@
+//! Vanna-volga smile for FX options.
+//!
+//! The market quotes an at-the-money volatility with 25 delta risk-reversal
+//! and butterfly quotes.  Vanna-volga adds to the flat-vol Garman-Kohlhagen
+//! price the cost of a portfolio of the three quoted options which matches
+//! the option's vega, vanna and vomma, which reprices the quoted options
+//! and interpolates the smile between them (Castagna and Mercurio, 2007).
+use crate::{
+    FxDeltaConvention, IvError, call, call_iv_rational, call_vanna, call_vega, call_vomma,
+    fx_atm_dns_strike, fx_call_strike_from_delta, fx_put_strike_from_delta,
+};
+use serde::Serialize;
+
+/// Market volatility quotes for one expiry.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct FxSmileQuotes {
+    /// ATM delta-neutral-straddle volatility
+    pub atm_vol: f64,
+    /// 25 delta call volatility less 25 delta put volatility
+    pub risk_reversal_25: f64,
+    /// Average of the 25 delta call and put volatilities less the ATM volatility
+    pub butterfly_25: f64,
+}
+
+/// A vanna-volga smile for one expiry, calibrated to the 25 delta put,
+/// ATM and 25 delta call quotes.
+#[derive(Debug, Clone, Serialize)]
+pub struct VannaVolga {
+    discounted_spot: f64,
+    domestic_rate: f64,
+    maturity: f64,
+    atm_vol: f64,
+    /// 25 delta put, ATM and 25 delta call strikes
+    pub pivot_strikes: [f64; 3],
+    /// 25 delta put, ATM and 25 delta call volatilities
+    pub pivot_vols: [f64; 3],
+}
+
+// Cramer's rule for a x = b
+fn solve_3x3(a: [[f64; 3]; 3], b: [f64; 3]) -> [f64; 3] {
+    let det = |m: [[f64; 3]; 3]| {
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    };
+    let det_a = det(a);
+    [0, 1, 2].map(|column| {
+        let mut m = a;
+        for row in 0..3 {
+            m[row][column] = b[row];
+        }
+        det(m) / det_a
+    })
+}
+
+impl VannaVolga {
+    /// Calibrates the smile to market quotes.  The 25 delta volatilities are
+    /// `atm_vol + butterfly_25 ± risk_reversal_25 / 2` and the pivot strikes
+    /// follow from `convention`.  Returns `None` if a pivot strike cannot be
+    /// found or the strikes are not increasing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use black_scholes::FxDeltaConvention;
+    /// use black_scholes::vanna_volga::{FxSmileQuotes, VannaVolga};
+    /// let quotes = FxSmileQuotes {
+    ///     atm_vol: 0.0905,
+    ///     risk_reversal_25: -0.005,
+    ///     butterfly_25: 0.0013,
+    /// };
+    /// let spot = 1.205;
+    /// let domestic_rate = 0.029;
+    /// let foreign_rate = 0.025;
+    /// let maturity = 0.94;
+    /// let smile = VannaVolga::new(
+    ///     spot, domestic_rate, foreign_rate, maturity,
+    ///     quotes, FxDeltaConvention::Spot
+    /// ).unwrap();
+    /// let iv = smile.implied_vol(1.30).unwrap();
+    /// ```
+    pub fn new(
+        spot: f64,
+        domestic_rate: f64,
+        foreign_rate: f64,
+        maturity: f64,
+        quotes: FxSmileQuotes,
+        convention: FxDeltaConvention,
+    ) -> Option<Self> {
+        let FxSmileQuotes {
+            atm_vol,
+            risk_reversal_25,
+            butterfly_25,
+        } = quotes;
+        let put_vol = atm_vol + butterfly_25 - 0.5 * risk_reversal_25;
+        let call_vol = atm_vol + butterfly_25 + 0.5 * risk_reversal_25;
+        let put_strike = fx_put_strike_from_delta(
+            -0.25,
+            spot,
+            put_vol,
+            domestic_rate,
+            foreign_rate,
+            maturity,
+            convention,
+        )?;
+        let atm_strike = fx_atm_dns_strike(
+            spot,
+            atm_vol,
+            domestic_rate,
+            foreign_rate,
+            maturity,
+            convention,
+        );
+        let call_strike = fx_call_strike_from_delta(
+            0.25,
+            spot,
+            call_vol,
+            domestic_rate,
+            foreign_rate,
+            maturity,
+            convention,
+        )?;
+        if !(put_strike < atm_strike && atm_strike < call_strike) {
+            return None;
+        }
+        Some(VannaVolga {
+            discounted_spot: spot * (-foreign_rate * maturity).exp(),
+            domestic_rate,
+            maturity,
+            atm_vol,
+            pivot_strikes: [put_strike, atm_strike, call_strike],
+            pivot_vols: [put_vol, atm_vol, call_vol],
+        })
+    }
+
+    // vega, vanna and vomma at the ATM volatility
+    fn greeks(&self, strike: f64) -> [f64; 3] {
+        let (s, rate, sigma, maturity) = (
+            self.discounted_spot,
+            self.domestic_rate,
+            self.atm_vol,
+            self.maturity,
+        );
+        [
+            call_vega(s, strike, rate, sigma, maturity),
+            call_vanna(s, strike, rate, sigma, maturity),
+            call_vomma(s, strike, rate, sigma, maturity),
+        ]
+    }
+
+    /// Returns the weights of the 25 delta put, ATM and 25 delta call in the
+    /// portfolio that matches the vega, vanna and vomma of an option struck
+    /// at `strike`.
+    pub fn weights(&self, strike: f64) -> [f64; 3] {
+        let pivot_greeks = self.pivot_strikes.map(|k| self.greeks(k));
+        let matrix = [0, 1, 2].map(|greek| [0, 1, 2].map(|pivot| pivot_greeks[pivot][greek]));
+        solve_3x3(matrix, self.greeks(strike))
+    }
+
+    // Smile cost of the hedge portfolio, which is the same for calls and puts
+    fn adjustment(&self, strike: f64) -> f64 {
+        let (s, rate, maturity) = (self.discounted_spot, self.domestic_rate, self.maturity);
+        self.weights(strike)
+            .iter()
+            .zip(self.pivot_strikes.iter().zip(self.pivot_vols.iter()))
+            .map(|(weight, (&k, &sigma))| {
+                weight
+                    * (call(s, k, rate, sigma, maturity) - call(s, k, rate, self.atm_vol, maturity))
+            })
+            .sum()
+    }
+
+    /// Returns the smile-consistent call price, per unit of foreign notional
+    /// in domestic currency.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use black_scholes::FxDeltaConvention;
+    /// use black_scholes::vanna_volga::{FxSmileQuotes, VannaVolga};
+    /// let quotes = FxSmileQuotes {
+    ///     atm_vol: 0.0905,
+    ///     risk_reversal_25: -0.005,
+    ///     butterfly_25: 0.0013,
+    /// };
+    /// let smile = VannaVolga::new(
+    ///     1.205, 0.029, 0.025, 0.94, quotes, FxDeltaConvention::Spot
+    /// ).unwrap();
+    /// let price = smile.call(1.30);
+    /// ```
+    pub fn call(&self, strike: f64) -> f64 {
+        call(
+            self.discounted_spot,
+            strike,
+            self.domestic_rate,
+            self.atm_vol,
+            self.maturity,
+        ) + self.adjustment(strike)
+    }
+
+    /// Returns the smile-consistent put price, per unit of foreign notional
+    /// in domestic currency.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use black_scholes::FxDeltaConvention;
+    /// use black_scholes::vanna_volga::{FxSmileQuotes, VannaVolga};
+    /// let quotes = FxSmileQuotes {
+    ///     atm_vol: 0.0905,
+    ///     risk_reversal_25: -0.005,
+    ///     butterfly_25: 0.0013,
+    /// };
+    /// let smile = VannaVolga::new(
+    ///     1.205, 0.029, 0.025, 0.94, quotes, FxDeltaConvention::Spot
+    /// ).unwrap();
+    /// let price = smile.put(1.10);
+    /// ```
+    pub fn put(&self, strike: f64) -> f64 {
+        crate::put(
+            self.discounted_spot,
+            strike,
+            self.domestic_rate,
+            self.atm_vol,
+            self.maturity,
+        ) + self.adjustment(strike)
+    }
+
+    /// Returns the Garman-Kohlhagen volatility that reproduces the
+    /// vanna-volga price at `strike`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use black_scholes::FxDeltaConvention;
+    /// use black_scholes::vanna_volga::{FxSmileQuotes, VannaVolga};
+    /// let quotes = FxSmileQuotes {
+    ///     atm_vol: 0.0905,
+    ///     risk_reversal_25: -0.005,
+    ///     butterfly_25: 0.0013,
+    /// };
+    /// let smile = VannaVolga::new(
+    ///     1.205, 0.029, 0.025, 0.94, quotes, FxDeltaConvention::Spot
+    /// ).unwrap();
+    /// let iv = smile.implied_vol(1.10).unwrap();
+    /// ```
+    pub fn implied_vol(&self, strike: f64) -> Result<f64, IvError> {
+        call_iv_rational(
+            self.call(strike),
+            self.discounted_spot,
+            strike,
+            self.domestic_rate,
+            self.maturity,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::*;
+
+    // EURUSD example from Castagna and Mercurio (2007)
+    fn smile() -> VannaVolga {
+        let quotes = FxSmileQuotes {
+            atm_vol: 0.0905,
+            risk_reversal_25: -0.005,
+            butterfly_25: 0.0013,
+        };
+        VannaVolga::new(1.205, 0.029, 0.025, 0.94, quotes, FxDeltaConvention::Spot).unwrap()
+    }
+
+    #[test]
+    fn reprices_pivots() {
+        let smile = smile();
+        for (strike, sigma) in smile.pivot_strikes.iter().zip(smile.pivot_vols.iter()) {
+            assert_abs_diff_eq!(smile.implied_vol(*strike).unwrap(), sigma, epsilon = 1e-10);
+        }
+    }
+    #[test]
+    fn weights_match_closed_form() {
+        let smile = smile();
+        let [k1, k2, k3] = smile.pivot_strikes;
+        let strike = 1.3;
+        let vega = |k| smile.greeks(k)[0];
+        let ln = |a: f64, b: f64| (a / b).ln();
+        let expected = [
+            vega(strike) / vega(k1) * ln(k2, strike) * ln(k3, strike) / (ln(k2, k1) * ln(k3, k1)),
+            vega(strike) / vega(k2) * ln(strike, k1) * ln(k3, strike) / (ln(k2, k1) * ln(k3, k2)),
+            vega(strike) / vega(k3) * ln(strike, k1) * ln(strike, k2) / (ln(k3, k1) * ln(k3, k2)),
+        ];
+        for (weight, expected) in smile.weights(strike).iter().zip(expected.iter()) {
+            assert_abs_diff_eq!(weight, expected, epsilon = 1e-8);
+        }
+    }
+    #[test]
+    fn satisfies_put_call_parity() {
+        let smile = smile();
+        let strike = 1.25;
+        let discount: f64 = (-0.029 * 0.94_f64).exp();
+        assert_abs_diff_eq!(
+            smile.call(strike) - smile.put(strike),
+            smile.discounted_spot - strike * discount,
+            epsilon = 1e-14
+        );
+    }
+    #[test]
+    fn smile_is_skewed_by_risk_reversal() {
+        let smile = smile();
+        let [put_strike, _, call_strike] = smile.pivot_strikes;
+        let low = smile.implied_vol(put_strike * 0.97).unwrap();
+        let high = smile.implied_vol(call_strike * 1.03).unwrap();
+        // negative risk reversal: downside vols are higher
+        assert!(low > high);
+        assert!(low > 0.0905);
+    }
+    #[test]
+    fn smile_is_convex_with_butterfly() {
+        let quotes = FxSmileQuotes {
+            atm_vol: 0.0905,
+            risk_reversal_25: 0.0,
+            butterfly_25: 0.0013,
+        };
+        let smile =
+            VannaVolga::new(1.205, 0.029, 0.025, 0.94, quotes, FxDeltaConvention::Spot).unwrap();
+        let [put_strike, atm_strike, call_strike] = smile.pivot_strikes;
+        let atm = smile.implied_vol(atm_strike).unwrap();
+        assert!(smile.implied_vol(put_strike * 0.97).unwrap() > 0.0918);
+        assert!(smile.implied_vol(call_strike * 1.03).unwrap() > 0.0918);
+        assert!(smile.implied_vol(0.5 * (atm_strike + call_strike)).unwrap() > atm);
+    }
+}